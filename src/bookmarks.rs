@@ -0,0 +1,126 @@
+//! Per-file bookmarks on nodes.
+//!
+//! Bookmarks are stored in ~/.unfold/bookmarks.json, grouped by the file's
+//! canonical path. Nodes are identified by JSON Pointer rather than by index,
+//! so a bookmark still finds its node after a reload or an edit to the file.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+use crate::parser::JsonTree;
+
+/// A bookmarked node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// JSON Pointer to the node
+    pub pointer: String,
+    /// Optional user label (empty when unset)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub label: String,
+}
+
+impl Bookmark {
+    pub fn new(pointer: String) -> Self {
+        Bookmark { pointer, label: String::new() }
+    }
+
+    /// The label, or the pointer when there is none
+    pub fn display_name(&self) -> &str {
+        match (self.label.as_str(), self.pointer.as_str()) {
+            ("", "") => "/",
+            ("", pointer) => pointer,
+            (label, _) => label,
+        }
+    }
+}
+
+/// Map each bookmark that resolves in `tree` from its node index to its
+/// position in `bookmarks`. Bookmarks whose node is gone are left out.
+pub fn resolve(tree: &JsonTree, bookmarks: &[Bookmark]) -> HashMap<usize, usize> {
+    bookmarks.iter()
+        .enumerate()
+        .filter_map(|(i, bookmark)| tree.resolve_pointer(&bookmark.pointer).map(|node| (node, i)))
+        .collect()
+}
+
+/// File in the config directory holding every file's bookmarks
+const STORE_FILE: &str = "bookmarks.json";
+
+/// All saved bookmarks, by file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BookmarkStore {
+    files: BTreeMap<PathBuf, Vec<Bookmark>>,
+}
+
+impl BookmarkStore {
+    fn load() -> Self {
+        config::load_json(STORE_FILE)
+    }
+
+    fn save(&self) -> Result<(), String> {
+        config::save_json(STORE_FILE, "bookmarks", self)
+    }
+}
+
+/// The same file opened through different paths shares its bookmarks
+fn store_key(file: &Path) -> PathBuf {
+    fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf())
+}
+
+/// Whether two paths are the same file, and so share their bookmarks
+pub fn same_file(a: &Path, b: &Path) -> bool {
+    a == b || store_key(a) == store_key(b)
+}
+
+/// Saved bookmarks for `file`
+pub fn load_for(file: &Path) -> Vec<Bookmark> {
+    BookmarkStore::load().files.remove(&store_key(file)).unwrap_or_default()
+}
+
+/// Replace the saved bookmarks for `file`
+pub fn save_for(file: &Path, bookmarks: &[Bookmark]) -> Result<(), String> {
+    let mut store = BookmarkStore::load();
+    if bookmarks.is_empty() {
+        store.files.remove(&store_key(file));
+    } else {
+        store.files.insert(store_key(file), bookmarks.to_vec());
+    }
+    store.save()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::build_tree;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve_survives_edits() {
+        let bookmarks = vec![
+            Bookmark { pointer: "/users/1".to_string(), label: "second user".to_string() },
+            Bookmark::new("/removed".to_string()),
+            Bookmark::new(String::new()),
+        ];
+
+        let tree = build_tree(&json!({"new": true, "users": [{"id": 1}, {"id": 2}]}));
+        let resolved = resolve(&tree, &bookmarks);
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved[&tree.resolve_pointer("/users/1").unwrap()], 0);
+        assert_eq!(resolved[&tree.root_index()], 2);
+
+        assert_eq!(bookmarks[0].display_name(), "second user");
+        assert_eq!(bookmarks[1].display_name(), "/removed");
+        assert_eq!(bookmarks[2].display_name(), "/");
+    }
+
+    #[test]
+    fn test_same_file() {
+        let dir = std::env::temp_dir();
+        assert!(same_file(&dir, &dir.join(".")));
+        assert!(same_file(Path::new("/no/such/file.json"), Path::new("/no/such/file.json")));
+        assert!(!same_file(Path::new("/no/such/file.json"), Path::new("/no/such/other.json")));
+    }
+}
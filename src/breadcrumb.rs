@@ -0,0 +1,68 @@
+//! Breadcrumb segments for the selected node.
+//!
+//! One segment per ancestor from the root (`$`) down to the node. Object
+//! members are labeled by key and array items by `[index]`.
+
+use crate::parser::JsonTree;
+
+/// One breadcrumb segment, or an entry of a segment's sibling dropdown
+#[derive(Debug, Clone, PartialEq)]
+pub struct Crumb {
+    pub node_index: usize,
+    pub label: String,
+}
+
+/// Segments from the root to `node_index`
+pub fn crumbs(tree: &JsonTree, node_index: usize) -> Vec<Crumb> {
+    let path = tree.get_path_to_node(node_index);
+    let mut crumbs = vec![Crumb { node_index: tree.root_index(), label: "$".to_string() }];
+    for pair in path.windows(2) {
+        let (parent, child) = (pair[0], pair[1]);
+        let position = tree.get_node(parent)
+            .and_then(|node| node.children.iter().position(|&c| c == child))
+            .unwrap_or(0);
+        crumbs.push(Crumb { node_index: child, label: child_label(tree, child, position) });
+    }
+    crumbs
+}
+
+/// The children of `parent`, labeled like breadcrumb segments
+pub fn children(tree: &JsonTree, parent: usize) -> Vec<Crumb> {
+    let Some(node) = tree.get_node(parent) else {
+        return Vec::new();
+    };
+    node.children.iter()
+        .enumerate()
+        .map(|(position, &child)| Crumb { node_index: child, label: child_label(tree, child, position) })
+        .collect()
+}
+
+fn child_label(tree: &JsonTree, child: usize, position: usize) -> String {
+    match tree.get_node(child).and_then(|node| node.key.as_ref()) {
+        Some(key) => key.clone(),
+        None => format!("[{}]", position),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::build_tree;
+    use serde_json::json;
+
+    #[test]
+    fn test_crumbs_and_siblings() {
+        let tree = build_tree(&json!({"users": [{"name": "a"}, {"name": "b"}], "count": 2}));
+        let name = tree.resolve_pointer("/users/1/name").unwrap();
+
+        let labels: Vec<String> = crumbs(&tree, name).into_iter().map(|c| c.label).collect();
+        assert_eq!(labels, ["$", "users", "[1]", "name"]);
+
+        let users = tree.resolve_pointer("/users").unwrap();
+        let siblings = children(&tree, users);
+        assert_eq!(siblings.len(), 2);
+        assert_eq!(siblings[1], Crumb { node_index: tree.resolve_pointer("/users/1").unwrap(), label: "[1]".to_string() });
+
+        assert_eq!(crumbs(&tree, tree.root_index()).len(), 1);
+    }
+}
@@ -0,0 +1,530 @@
+//! Type definitions generated from a sample subtree ("Copy Type As").
+//!
+//! The subtree is first summarized with `schema::infer_schema`, which already
+//! merges the shapes of array elements and tracks which members are always
+//! present. The schema is then rendered as Rust serde structs, TypeScript
+//! interfaces or Go structs.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use serde_json::Value;
+
+use crate::parser::JsonTree;
+use crate::schema::infer_schema;
+
+/// Target languages for generated types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeLanguage {
+    /// Structs deriving serde `Serialize`/`Deserialize`
+    Rust,
+    /// Exported interfaces
+    TypeScript,
+    /// Structs with `json` tags
+    Go,
+}
+
+impl TypeLanguage {
+    /// Name shown in the "Copy Type As" submenu and status messages
+    pub fn label(&self) -> &'static str {
+        match self {
+            TypeLanguage::Rust => "Rust (serde)",
+            TypeLanguage::TypeScript => "TypeScript",
+            TypeLanguage::Go => "Go",
+        }
+    }
+}
+
+/// Rust keywords that cannot be used as field names
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true",
+    "type", "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final",
+    "gen", "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Type names that would clash with a keyword (`Self`) or with a standard
+/// type used in the generated code of one of the languages
+const RESERVED_TYPE_NAMES: &[&str] = &[
+    "Self", "String", "Vec", "Option", "Box", "Result", "Serialize", "Deserialize",
+    "Number", "Boolean", "Object", "Array", "Date", "Record", "Map", "Set", "Promise",
+    "Symbol", "Error", "Function",
+];
+
+/// Words Go spells in all caps inside identifiers
+const GO_INITIALISMS: &[&str] = &["api", "html", "http", "https", "id", "ip", "json", "sql", "uri", "url", "uuid", "xml"];
+
+/// Generate type definitions describing a node and its children
+pub fn generate_types(tree: &JsonTree, node_index: usize, language: TypeLanguage) -> String {
+    let schema = infer_schema(tree, node_index);
+    let root_hint = tree.get_node(node_index)
+        .and_then(|node| node.key.as_deref())
+        .filter(|key| !key.starts_with('['))
+        .map(pascal_case)
+        .filter(|name| !name.is_empty())
+        .map(type_name)
+        .unwrap_or_else(|| "Root".to_string());
+
+    let mut collector = Collector::default();
+    let root = collector.type_of(&schema, &root_hint);
+
+    match language {
+        TypeLanguage::Rust => render_rust(&root_hint, &root, &collector.definitions),
+        TypeLanguage::TypeScript => render_typescript(&root_hint, &root, &collector.definitions),
+        TypeLanguage::Go => render_go(&root_hint, &root, &collector.definitions),
+    }
+}
+
+// ============================================================================
+// Type model
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum TypeRef {
+    /// No samples, or nothing more specific is known
+    Any,
+    /// Only `null` was seen
+    Null,
+    Bool,
+    Integer,
+    Number,
+    String,
+    Array(Box<TypeRef>),
+    /// A generated struct/interface
+    Named(String),
+    /// One type that may also be `null`
+    Nullable(Box<TypeRef>),
+    /// Several non-null types
+    Union(Vec<TypeRef>),
+}
+
+/// A struct/interface collected from an object schema
+struct Definition {
+    name: String,
+    fields: Vec<Field>,
+}
+
+struct Field {
+    key: String,
+    ty: TypeRef,
+    /// Missing from at least one sample
+    optional: bool,
+}
+
+#[derive(Default)]
+struct Collector {
+    /// Definitions in discovery order, outermost first
+    definitions: Vec<Definition>,
+    names: HashSet<String>,
+}
+
+impl Collector {
+    fn type_of(&mut self, schema: &Value, name_hint: &str) -> TypeRef {
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(t)) => vec![t.as_str()],
+            Some(Value::Array(list)) => list.iter().filter_map(Value::as_str).collect(),
+            _ => return TypeRef::Any,
+        };
+
+        let nullable = types.contains(&"null");
+        let mut variants: Vec<TypeRef> = types.iter()
+            .filter_map(|t| match *t {
+                "object" => Some(TypeRef::Named(self.define(schema, name_hint))),
+                "array" => {
+                    let item = match schema.get("items") {
+                        Some(items) => self.type_of(items, &singular(name_hint)),
+                        None => TypeRef::Any,
+                    };
+                    Some(TypeRef::Array(Box::new(item)))
+                }
+                "string" => Some(TypeRef::String),
+                "integer" => Some(TypeRef::Integer),
+                "number" => Some(TypeRef::Number),
+                "boolean" => Some(TypeRef::Bool),
+                _ => None,
+            })
+            .collect();
+
+        let ty = match variants.len() {
+            0 if nullable => return TypeRef::Null,
+            0 => return TypeRef::Any,
+            1 => variants.remove(0),
+            _ => TypeRef::Union(variants),
+        };
+        if nullable { TypeRef::Nullable(Box::new(ty)) } else { ty }
+    }
+
+    /// Collect a struct for an object schema and return its (unique) name
+    fn define(&mut self, schema: &Value, name_hint: &str) -> String {
+        let mut name = name_hint.to_string();
+        let mut suffix = 2;
+        while !self.names.insert(name.clone()) {
+            name = format!("{}{}", name_hint, suffix);
+            suffix += 1;
+        }
+
+        // Reserve the slot first so outer types are listed before inner ones
+        let slot = self.definitions.len();
+        self.definitions.push(Definition { name: name.clone(), fields: Vec::new() });
+
+        let required: Vec<&str> = schema.get("required")
+            .and_then(Value::as_array)
+            .map(|list| list.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut fields = Vec::new();
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (key, property) in properties {
+                let hint = match pascal_case(key) {
+                    hint if hint.is_empty() || hint.starts_with(|c: char| c.is_ascii_digit()) => format!("{}Field", name),
+                    hint => type_name(hint),
+                };
+                fields.push(Field {
+                    key: key.clone(),
+                    ty: self.type_of(property, &hint),
+                    optional: !required.contains(&key.as_str()),
+                });
+            }
+        }
+        self.definitions[slot].fields = fields;
+        name
+    }
+}
+
+// ============================================================================
+// Naming
+// ============================================================================
+
+/// Split a key into words at separators and lower-to-upper case changes
+fn words(key: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in key.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && previous_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let lower = word.to_ascii_lowercase();
+    let mut chars = lower.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+fn pascal_case(key: &str) -> String {
+    words(key).iter().map(|w| capitalize(w)).collect()
+}
+
+/// A type name that doesn't clash with a keyword or standard type
+fn type_name(name: String) -> String {
+    if RESERVED_TYPE_NAMES.contains(&name.as_str()) {
+        format!("{}Item", name)
+    } else {
+        name
+    }
+}
+
+/// Name for the element type of an array type
+fn singular(name: &str) -> String {
+    // Words like "Status", "Address", "Analysis" and "News" aren't plurals
+    let not_plural = ["ss", "us", "is"].iter().any(|end| name.ends_with(end))
+        || (name.ends_with("ews") && !name.ends_with("iews"));
+    let name = if let Some(stem) = name.strip_suffix("ies")
+        && !stem.is_empty()
+    {
+        format!("{}y", stem)
+    } else if let Some(stem) = name.strip_suffix('s')
+        && !stem.is_empty()
+        && !not_plural
+    {
+        stem.to_string()
+    } else {
+        format!("{}Item", name)
+    };
+    type_name(name)
+}
+
+fn rust_field_name(key: &str) -> String {
+    let name = words(key).iter().map(|w| w.to_ascii_lowercase()).collect::<Vec<_>>().join("_");
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field_{}", name)
+    } else if RUST_KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+fn go_field_name(key: &str) -> String {
+    let name: String = words(key).iter()
+        .map(|w| {
+            if GO_INITIALISMS.contains(&w.to_ascii_lowercase().as_str()) {
+                w.to_ascii_uppercase()
+            } else {
+                capitalize(w)
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Field{}", name)
+    } else {
+        name
+    }
+}
+
+/// Make a sanitized field name unique within its struct
+fn unique_field(name: String, used: &mut HashSet<String>) -> String {
+    let mut candidate = name.clone();
+    let mut suffix = 2;
+    while !used.insert(candidate.clone()) {
+        candidate = format!("{}{}", name, suffix);
+        suffix += 1;
+    }
+    candidate
+}
+
+fn is_ts_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+fn rust_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::Any | TypeRef::Union(_) => "serde_json::Value".to_string(),
+        TypeRef::Null => "Option<serde_json::Value>".to_string(),
+        TypeRef::Bool => "bool".to_string(),
+        TypeRef::Integer => "i64".to_string(),
+        TypeRef::Number => "f64".to_string(),
+        TypeRef::String => "String".to_string(),
+        TypeRef::Array(item) => format!("Vec<{}>", rust_type(item)),
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::Nullable(inner) => format!("Option<{}>", rust_type(inner)),
+    }
+}
+
+fn render_rust(root_name: &str, root: &TypeRef, definitions: &[Definition]) -> String {
+    let mut out = String::from("use serde::{Deserialize, Serialize};\n");
+    if !matches!(root, TypeRef::Named(_)) {
+        let _ = write!(out, "\npub type {} = {};\n", root_name, rust_type(root));
+    }
+
+    for definition in definitions {
+        let _ = write!(out, "\n#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct {} {{\n", definition.name);
+        let mut used = HashSet::new();
+        for field in &definition.fields {
+            let name = unique_field(rust_field_name(&field.key), &mut used);
+            let mut ty = rust_type(&field.ty);
+            if field.optional && !ty.starts_with("Option<") {
+                ty = format!("Option<{}>", ty);
+            }
+            if name != field.key {
+                let _ = writeln!(out, "    #[serde(rename = {})]", Value::String(field.key.clone()));
+            }
+            let _ = writeln!(out, "    pub {}: {},", name, ty);
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+fn typescript_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::Any => "unknown".to_string(),
+        TypeRef::Null => "null".to_string(),
+        TypeRef::Bool => "boolean".to_string(),
+        TypeRef::Integer | TypeRef::Number => "number".to_string(),
+        TypeRef::String => "string".to_string(),
+        TypeRef::Array(item) => match item.as_ref() {
+            TypeRef::Union(_) | TypeRef::Nullable(_) => format!("({})[]", typescript_type(item)),
+            _ => format!("{}[]", typescript_type(item)),
+        },
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::Nullable(inner) => format!("{} | null", typescript_type(inner)),
+        TypeRef::Union(variants) => variants.iter().map(typescript_type).collect::<Vec<_>>().join(" | "),
+    }
+}
+
+fn render_typescript(root_name: &str, root: &TypeRef, definitions: &[Definition]) -> String {
+    let mut out = String::new();
+    if !matches!(root, TypeRef::Named(_)) {
+        let _ = writeln!(out, "export type {} = {};", root_name, typescript_type(root));
+    }
+
+    for definition in definitions {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "export interface {} {{", definition.name);
+        for field in &definition.fields {
+            let key = if is_ts_identifier(&field.key) {
+                field.key.clone()
+            } else {
+                Value::String(field.key.clone()).to_string()
+            };
+            let optional = if field.optional { "?" } else { "" };
+            let _ = writeln!(out, "  {}{}: {};", key, optional, typescript_type(&field.ty));
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+fn go_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::Any | TypeRef::Null | TypeRef::Union(_) => "any".to_string(),
+        TypeRef::Bool => "bool".to_string(),
+        TypeRef::Integer => "int64".to_string(),
+        TypeRef::Number => "float64".to_string(),
+        TypeRef::String => "string".to_string(),
+        TypeRef::Array(item) => format!("[]{}", go_type(item)),
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::Nullable(inner) => go_pointer(inner),
+    }
+}
+
+/// Pointer to a type, unless the type can already be nil
+fn go_pointer(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::Any | TypeRef::Null | TypeRef::Union(_) | TypeRef::Array(_) => go_type(ty),
+        TypeRef::Nullable(inner) => go_pointer(inner),
+        _ => format!("*{}", go_type(ty)),
+    }
+}
+
+fn render_go(root_name: &str, root: &TypeRef, definitions: &[Definition]) -> String {
+    let mut out = String::new();
+    if !matches!(root, TypeRef::Named(_)) {
+        let _ = writeln!(out, "type {} {}", root_name, go_type(root));
+    }
+
+    for definition in definitions {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let mut used = HashSet::new();
+        let lines: Vec<(String, String, String)> = definition.fields.iter()
+            .map(|field| {
+                let name = unique_field(go_field_name(&field.key), &mut used);
+                let (ty, omit) = if field.optional {
+                    (go_pointer(&field.ty), ",omitempty")
+                } else {
+                    (go_type(&field.ty), "")
+                };
+                let tag = format!("`json:{}`", Value::String(format!("{}{}", field.key, omit)));
+                (name, ty, tag)
+            })
+            .collect();
+
+        // Align columns the way gofmt does
+        let name_width = lines.iter().map(|(name, _, _)| name.len()).max().unwrap_or(0);
+        let type_width = lines.iter().map(|(_, ty, _)| ty.len()).max().unwrap_or(0);
+        let _ = writeln!(out, "type {} struct {{", definition.name);
+        for (name, ty, tag) in &lines {
+            let _ = writeln!(out, "\t{:name_width$} {:type_width$} {}", name, ty, tag);
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::build_tree;
+    use serde_json::json;
+
+    fn sample() -> JsonTree {
+        build_tree(&json!({
+            "users": [
+                {"id": 1, "firstName": "Ann", "type": "admin", "address": {"city": "Oslo"}},
+                {"id": 2, "firstName": "Bob", "type": "user", "score": 1.5, "address": null}
+            ]
+        }))
+    }
+
+    #[test]
+    fn test_generate_rust_types() {
+        let tree = sample();
+        let code = generate_types(&tree, tree.root_index(), TypeLanguage::Rust);
+
+        assert!(code.starts_with("use serde::{Deserialize, Serialize};\n"));
+        assert!(code.contains("pub struct Root {\n    pub users: Vec<User>,\n}"));
+        assert!(code.contains("    #[serde(rename = \"firstName\")]\n    pub first_name: String,"));
+        assert!(code.contains("    #[serde(rename = \"type\")]\n    pub type_: String,"));
+        assert!(code.contains("    pub score: Option<f64>,"));
+        assert!(code.contains("    pub address: Option<Address>,"));
+        assert!(code.contains("pub struct Address {\n    pub city: String,\n}"));
+        // Outer types come first
+        assert!(code.find("struct Root").unwrap() < code.find("struct User").unwrap());
+    }
+
+    #[test]
+    fn test_generate_typescript_types() {
+        let tree = build_tree(&json!([{"a-b": 1, "tags": ["x", 2]}, {"a-b": 2, "tags": []}]));
+        let code = generate_types(&tree, tree.root_index(), TypeLanguage::TypeScript);
+
+        assert!(code.starts_with("export type Root = RootItem[];\n"));
+        assert!(code.contains("export interface RootItem {\n  \"a-b\": number;\n  tags: (string | number)[];\n}"));
+    }
+
+    #[test]
+    fn test_generate_go_types() {
+        let tree = sample();
+        let users = tree.get_node(tree.root_index()).unwrap().children[0];
+        let code = generate_types(&tree, users, TypeLanguage::Go);
+
+        assert!(code.starts_with("type Users []User\n"));
+        assert!(code.contains("\tID        int64    `json:\"id\"`"));
+        assert!(code.contains("\tScore     *float64 `json:\"score,omitempty\"`"));
+        assert!(code.contains("\tAddress   *Address `json:\"address\"`"));
+    }
+
+    #[test]
+    fn test_field_name_sanitizing() {
+        assert_eq!(rust_field_name("userId"), "user_id");
+        assert_eq!(rust_field_name("2fa"), "field_2fa");
+        assert_eq!(rust_field_name("@type"), "type_");
+        assert_eq!(go_field_name("user_url"), "UserURL");
+        assert_eq!(go_field_name(""), "Field");
+        assert_eq!(singular("Categories"), "Category");
+        assert_eq!(singular("Address"), "AddressItem");
+        assert_eq!(singular("Status"), "StatusItem");
+        assert_eq!(singular("News"), "NewsItem");
+        assert_eq!(singular("Reviews"), "Review");
+        assert_eq!(singular("Strings"), "StringItem");
+    }
+
+    #[test]
+    fn test_reserved_type_names() {
+        let tree = build_tree(&json!({"self": {"a": 1}, "string": {"b": true}}));
+        let code = generate_types(&tree, tree.root_index(), TypeLanguage::Rust);
+        assert!(code.contains("pub struct SelfItem {"));
+        assert!(code.contains("pub struct StringItem {"));
+        assert!(code.contains("pub self_: SelfItem,"));
+        assert!(!code.contains("struct Self ") && !code.contains("struct String "));
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Stores user preferences in ~/.unfold/config.json
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -17,6 +18,25 @@ pub struct Config {
     /// Whether CLI tool has been installed
     #[serde(default)]
     pub cli_installed: bool,
+    /// Pretty-printer settings for formatted copy, export and save
+    #[serde(default)]
+    pub format: FormatOptions,
+    /// Reload the open file as soon as it changes on disk (otherwise ask first)
+    #[serde(default)]
+    pub auto_reload: bool,
+    /// Reopen the previous session's tabs at launch
+    #[serde(default = "default_true")]
+    pub restore_session: bool,
+    /// Recently opened files, most recent first
+    #[serde(default)]
+    pub recent_files: Vec<RecentFile>,
+    /// Navigate the tree with vim keys (hjkl, gg, G, zc/zo)
+    #[serde(default)]
+    pub vim_keys: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Default for Config {
@@ -24,57 +44,137 @@ impl Default for Config {
         Config {
             theme: AppTheme::Dark,
             cli_installed: false,
+            format: FormatOptions::default(),
+            auto_reload: false,
+            restore_session: true,
+            recent_files: Vec::new(),
+            vim_keys: false,
         }
     }
 }
 
+/// Most entries kept in the recent files list
+pub const MAX_RECENT_FILES: usize = 10;
+
+/// File in the config directory holding the config (~/.unfold/config.json)
+const CONFIG_FILE: &str = "config.json";
+
+/// An entry in the recent files list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentFile {
+    pub path: PathBuf,
+    /// Size of the file in bytes when it was last opened
+    pub size: u64,
+    /// When the file was last opened, in seconds since the Unix epoch
+    pub opened_at: u64,
+}
+
+impl RecentFile {
+    /// "just now", "5 min ago", "3 hours ago", "2 days ago"
+    pub fn opened_ago(&self, now: u64) -> String {
+        let elapsed = now.saturating_sub(self.opened_at);
+        match elapsed {
+            0..60 => "just now".to_string(),
+            60..3600 => format!("{} min ago", elapsed / 60),
+            3600..7200 => "1 hour ago".to_string(),
+            7200..86400 => format!("{} hours ago", elapsed / 3600),
+            86400..172800 => "yesterday".to_string(),
+            _ => format!("{} days ago", elapsed / 86400),
+        }
+    }
+}
+
+/// How formatted JSON is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatOptions {
+    /// Spaces per indentation level (ignored when `use_tabs` is set)
+    pub indent_width: usize,
+    /// Indent with one tab per level
+    pub use_tabs: bool,
+    /// Write object members sorted by key instead of in source order
+    pub sort_keys: bool,
+    /// Escape every non-ASCII character as `\uXXXX`
+    pub ascii_only: bool,
+    /// Keep arrays of scalars on one line when they fit in `line_width`
+    pub compact_arrays: bool,
+    /// Maximum line length for compacted arrays
+    pub line_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent_width: 2,
+            use_tabs: false,
+            sort_keys: false,
+            ascii_only: false,
+            compact_arrays: false,
+            line_width: 80,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Whitespace for one indentation level
+    pub fn indent_unit(&self) -> String {
+        if self.use_tabs { "\t".to_string() } else { " ".repeat(self.indent_width) }
+    }
+}
+
 impl Config {
     /// Get the config directory path (~/.unfold)
     pub fn config_dir() -> Option<PathBuf> {
         dirs::home_dir().map(|home| home.join(".unfold"))
     }
 
-    /// Get the config file path (~/.unfold/config.json)
-    pub fn config_path() -> Option<PathBuf> {
-        Self::config_dir().map(|dir| dir.join("config.json"))
+    /// Move `path` to the front of the recent files list, dropping older
+    /// entries beyond [`MAX_RECENT_FILES`]
+    pub fn add_recent_file(&mut self, path: PathBuf, size: u64, opened_at: u64) {
+        self.recent_files.retain(|recent| recent.path != path);
+        self.recent_files.insert(0, RecentFile { path, size, opened_at });
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+
+    /// Remove recent files that no longer exist; returns whether any were removed
+    pub fn prune_recent_files(&mut self) -> bool {
+        let before = self.recent_files.len();
+        self.recent_files.retain(|recent| recent.path.is_file());
+        self.recent_files.len() != before
     }
 
     /// Load config from file, or return default if not found
     pub fn load() -> Self {
-        let Some(path) = Self::config_path() else {
-            return Config::default();
-        };
-
-        match fs::read_to_string(&path) {
-            Ok(contents) => {
-                serde_json::from_str(&contents).unwrap_or_default()
-            }
-            Err(_) => Config::default(),
-        }
+        load_json(CONFIG_FILE)
     }
 
     /// Save config to file
     pub fn save(&self) -> Result<(), String> {
-        let dir = Self::config_dir()
-            .ok_or_else(|| "Could not determine home directory".to_string())?;
-
-        // Create config directory if it doesn't exist
-        if !dir.exists() {
-            fs::create_dir_all(&dir)
-                .map_err(|e| format!("Failed to create config directory: {}", e))?;
-        }
-
-        let path = Self::config_path()
-            .ok_or_else(|| "Could not determine config path".to_string())?;
-
-        let json = serde_json::to_string_pretty(self)
-            .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        save_json(CONFIG_FILE, "config", self)
+    }
+}
 
-        fs::write(&path, json)
-            .map_err(|e| format!("Failed to write config: {}", e))?;
+/// Read a JSON file in the config directory, or the default value if it is
+/// missing or can't be parsed
+pub fn load_json<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    Config::config_dir()
+        .and_then(|dir| fs::read_to_string(dir.join(file_name)).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
 
-        Ok(())
-    }
+/// Write `value` as JSON to a file in the config directory, creating the
+/// directory if needed. `what` names the data in error messages.
+pub fn save_json<T: Serialize>(file_name: &str, what: &str, value: &T) -> Result<(), String> {
+    let dir = Config::config_dir()
+        .ok_or_else(|| "Could not determine home directory".to_string())?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", what, e))?;
+    fs::write(dir.join(file_name), json)
+        .map_err(|e| format!("Failed to write {}: {}", what, e))
 }
 
 #[cfg(test)]
@@ -93,6 +193,11 @@ mod tests {
         let config = Config {
             theme: AppTheme::Light,
             cli_installed: true,
+            format: FormatOptions { use_tabs: true, ..FormatOptions::default() },
+            auto_reload: true,
+            restore_session: false,
+            recent_files: Vec::new(),
+            vim_keys: true,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -100,5 +205,48 @@ mod tests {
 
         assert_eq!(parsed.theme, AppTheme::Light);
         assert!(parsed.cli_installed);
+        assert!(parsed.format.use_tabs);
+        assert!(parsed.auto_reload);
+        assert!(!parsed.restore_session);
+        assert!(parsed.vim_keys);
+    }
+
+    #[test]
+    fn test_config_missing_format_options() {
+        // Configs written before formatter options existed still load
+        let parsed: Config = serde_json::from_str(r#"{"theme": "Light", "format": {"indent_width": 4}}"#).unwrap();
+        assert_eq!(parsed.format, FormatOptions { indent_width: 4, ..FormatOptions::default() });
+
+        let parsed: Config = serde_json::from_str(r#"{"cli_installed": true}"#).unwrap();
+        assert_eq!(parsed.format, FormatOptions::default());
+        assert!(parsed.restore_session);
+    }
+
+    #[test]
+    fn test_recent_files_are_deduplicated_and_capped() {
+        let mut config = Config::default();
+        for i in 0..12 {
+            config.add_recent_file(PathBuf::from(format!("/data/{}.json", i)), 100, i);
+        }
+        assert_eq!(config.recent_files.len(), MAX_RECENT_FILES);
+        assert_eq!(config.recent_files[0].path, PathBuf::from("/data/11.json"));
+
+        // Reopening moves the entry to the front instead of duplicating it
+        config.add_recent_file(PathBuf::from("/data/5.json"), 200, 20);
+        assert_eq!(config.recent_files.len(), MAX_RECENT_FILES);
+        assert_eq!(config.recent_files[0], RecentFile { path: PathBuf::from("/data/5.json"), size: 200, opened_at: 20 });
+        assert_eq!(config.recent_files.iter().filter(|r| r.path.ends_with("5.json")).count(), 1);
+
+        assert!(config.prune_recent_files());
+        assert!(config.recent_files.is_empty());
+    }
+
+    #[test]
+    fn test_opened_ago() {
+        let recent = RecentFile { path: PathBuf::new(), size: 0, opened_at: 1_000_000 };
+        assert_eq!(recent.opened_ago(1_000_030), "just now");
+        assert_eq!(recent.opened_ago(1_000_000 + 5 * 60), "5 min ago");
+        assert_eq!(recent.opened_ago(1_000_000 + 3 * 3600), "3 hours ago");
+        assert_eq!(recent.opened_ago(1_000_000 + 3 * 86400), "3 days ago");
     }
 }
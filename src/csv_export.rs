@@ -0,0 +1,273 @@
+//! CSV / TSV export of arrays of objects.
+//!
+//! Nested objects are flattened into dotted column names (`address.city`);
+//! arrays stay in a single cell as minified JSON. Columns are identified by
+//! their key path, so a literal `"a.b"` key and a nested `a` → `b` stay
+//! separate (the second gets a numbered header). Fields are quoted following
+//! RFC 4180 and records end with CRLF.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::json_export::{node_to_value, number_to_value};
+use crate::parser::{JsonTree, JsonValue};
+use crate::table::is_tabular;
+
+/// Field separator of the exported file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    Comma,
+    Tab,
+}
+
+impl Delimiter {
+    pub fn as_char(&self) -> char {
+        match self {
+            Delimiter::Comma => ',',
+            Delimiter::Tab => '\t',
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Delimiter::Comma => "CSV",
+            Delimiter::Tab => "TSV",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Delimiter::Comma => "csv",
+            Delimiter::Tab => "tsv",
+        }
+    }
+}
+
+/// One output column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    /// Keys leading from an element to the cell
+    pub path: Vec<String>,
+    /// Header text: the dotted path, numbered when it collides with another column
+    pub header: String,
+}
+
+/// State of the export dialog: which columns to write and in what order
+#[derive(Debug, Clone)]
+pub struct CsvExportDialog {
+    pub node_index: usize,
+    pub delimiter: Delimiter,
+    /// Flattened columns with their "include" flag, in output order
+    pub columns: Vec<(Column, bool)>,
+}
+
+impl CsvExportDialog {
+    /// Open the dialog for a tabular node with every column included
+    pub fn new(tree: &JsonTree, node_index: usize, delimiter: Delimiter) -> Option<Self> {
+        if !is_tabular(tree, node_index) {
+            return None;
+        }
+        let columns = flatten_columns(tree, node_index).into_iter().map(|c| (c, true)).collect();
+        Some(CsvExportDialog { node_index, delimiter, columns })
+    }
+
+    /// Swap a column with its neighbour (`up` towards the first column)
+    pub fn move_column(&mut self, index: usize, up: bool) {
+        let other = if up { index.checked_sub(1) } else { Some(index + 1) };
+        if let Some(other) = other
+            && other < self.columns.len()
+            && index < self.columns.len()
+        {
+            self.columns.swap(index, other);
+        }
+    }
+
+    pub fn toggle_column(&mut self, index: usize) {
+        if let Some((_, included)) = self.columns.get_mut(index) {
+            *included = !*included;
+        }
+    }
+
+    /// Render the selected columns of the node
+    pub fn export(&self, tree: &JsonTree) -> String {
+        let columns: Vec<Column> = self.columns.iter()
+            .filter(|(_, included)| *included)
+            .map(|(column, _)| column.clone())
+            .collect();
+        to_delimited(tree, self.node_index, &columns, self.delimiter)
+    }
+}
+
+/// Union of the flattened columns of all elements, in first-seen order
+pub fn flatten_columns(tree: &JsonTree, node_index: usize) -> Vec<Column> {
+    let mut columns = Vec::new();
+    let Some(node) = tree.get_node(node_index) else {
+        return columns;
+    };
+    let mut seen_paths = HashSet::new();
+    let mut headers = HashSet::new();
+    for &element in &node.children {
+        for (path, _) in flatten_row(tree, element) {
+            if seen_paths.contains(&path) {
+                continue;
+            }
+            let dotted = path.join(".");
+            let mut header = dotted.clone();
+            let mut suffix = 2;
+            while !headers.insert(header.clone()) {
+                header = format!("{} ({})", dotted, suffix);
+                suffix += 1;
+            }
+            seen_paths.insert(path.clone());
+            columns.push(Column { path, header });
+        }
+    }
+    columns
+}
+
+/// Flattened `(key path, node)` pairs of one element
+fn flatten_row(tree: &JsonTree, element: usize) -> Vec<(Vec<String>, usize)> {
+    fn walk(tree: &JsonTree, index: usize, prefix: &mut Vec<String>, out: &mut Vec<(Vec<String>, usize)>) {
+        let Some(node) = tree.get_node(index) else {
+            return;
+        };
+        for &child in &node.children {
+            let Some(child_node) = tree.get_node(child) else {
+                continue;
+            };
+            prefix.push(child_node.key.clone().unwrap_or_default());
+            if matches!(child_node.value, JsonValue::Object) && !child_node.children.is_empty() {
+                walk(tree, child, prefix, out);
+            } else {
+                out.push((prefix.clone(), child));
+            }
+            prefix.pop();
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(tree, element, &mut Vec::new(), &mut out);
+    out
+}
+
+/// Write the elements of an array as delimited text with a header record
+pub fn to_delimited(tree: &JsonTree, node_index: usize, columns: &[Column], delimiter: Delimiter) -> String {
+    let separator = delimiter.as_char().to_string();
+    let mut out = String::new();
+
+    let header: Vec<String> = columns.iter().map(|c| quote_field(&c.header, delimiter)).collect();
+    out.push_str(&header.join(&separator));
+    out.push_str("\r\n");
+
+    let Some(node) = tree.get_node(node_index) else {
+        return out;
+    };
+    for &element in &node.children {
+        let cells: HashMap<Vec<String>, usize> = flatten_row(tree, element).into_iter().collect();
+        let record: Vec<String> = columns.iter()
+            .map(|column| match cells.get(&column.path) {
+                Some(&cell) => quote_field(&field_text(tree, cell), delimiter),
+                None => String::new(),
+            })
+            .collect();
+        out.push_str(&record.join(&separator));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Cell text: strings unquoted, `null` empty, containers as minified JSON
+fn field_text(tree: &JsonTree, index: usize) -> String {
+    match tree.get_node(index).map(|n| &n.value) {
+        Some(JsonValue::String(s)) => s.clone(),
+        Some(JsonValue::Number(n)) => number_to_value(*n).to_string(),
+        Some(JsonValue::Bool(b)) => b.to_string(),
+        Some(JsonValue::Null) | None => String::new(),
+        Some(JsonValue::Array | JsonValue::Object) => {
+            serde_json::to_string(&node_to_value(tree, index)).unwrap_or_default()
+        }
+    }
+}
+
+/// Quote a field if it contains the delimiter, a quote or a line break (RFC 4180)
+fn quote_field(field: &str, delimiter: Delimiter) -> String {
+    if field.contains([delimiter.as_char(), '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::build_tree;
+    use serde_json::json;
+
+    fn sample() -> JsonTree {
+        build_tree(&json!([
+            {"name": "Ann", "address": {"city": "Oslo", "geo": {"lat": 1.5}}, "tags": ["a", "b"]},
+            {"name": "Bob \"B\", Jr.", "note": "line\nbreak", "address": null, "id": 7}
+        ]))
+    }
+
+    /// The columns of the root array with these headers, in this order
+    fn columns(tree: &JsonTree, headers: &[&str]) -> Vec<Column> {
+        let all = flatten_columns(tree, tree.root_index());
+        headers.iter().map(|h| all.iter().find(|c| c.header == *h).unwrap().clone()).collect()
+    }
+
+    #[test]
+    fn test_flatten_columns() {
+        let tree = sample();
+        let headers: Vec<String> = flatten_columns(&tree, tree.root_index()).into_iter().map(|c| c.header).collect();
+        assert_eq!(headers, ["name", "address.city", "address.geo.lat", "tags", "note", "address", "id"]);
+    }
+
+    #[test]
+    fn test_dotted_keys_get_their_own_column() {
+        let tree = build_tree(&json!([{"a.b": 1, "a": {"b": 2}}, {"a.b": 3}]));
+        let all = flatten_columns(&tree, tree.root_index());
+        assert_eq!(all[0], Column { path: vec!["a.b".to_string()], header: "a.b".to_string() });
+        assert_eq!(all[1], Column { path: vec!["a".to_string(), "b".to_string()], header: "a.b (2)".to_string() });
+        assert_eq!(to_delimited(&tree, tree.root_index(), &all, Delimiter::Comma), "a.b,a.b (2)\r\n1,2\r\n3,\r\n");
+    }
+
+    #[test]
+    fn test_nested_cells_are_valid_json() {
+        let tree = build_tree(&json!([{"list": [{"k\"e\\y": 1}]}]));
+        let tsv = to_delimited(&tree, tree.root_index(), &columns(&tree, &["list"]), Delimiter::Tab);
+        let cell = tsv.lines().nth(1).unwrap();
+        let cell = cell.trim_matches('"').replace("\"\"", "\"");
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&cell).unwrap(), json!([{"k\"e\\y": 1}]));
+    }
+
+    #[test]
+    fn test_csv_quoting_follows_rfc_4180() {
+        let tree = sample();
+        let columns = columns(&tree, &["name", "address.city", "tags", "note", "id"]);
+        let csv = to_delimited(&tree, tree.root_index(), &columns, Delimiter::Comma);
+
+        assert_eq!(
+            csv,
+            "name,address.city,tags,note,id\r\n\
+             Ann,Oslo,\"[\"\"a\"\",\"\"b\"\"]\",,\r\n\
+             \"Bob \"\"B\"\", Jr.\",,,\"line\nbreak\",7\r\n"
+        );
+    }
+
+    #[test]
+    fn test_tsv_and_column_selection() {
+        let tree = sample();
+        let mut dialog = CsvExportDialog::new(&tree, tree.root_index(), Delimiter::Tab).unwrap();
+        // Keep only name and id, then move id in front of name
+        for i in 1..=5 {
+            dialog.toggle_column(i);
+        }
+        for at in (1..=6).rev() {
+            dialog.move_column(at, true);
+        }
+        dialog.move_column(0, true);
+
+        assert_eq!(dialog.export(&tree), "id\tname\r\n\tAnn\r\n7\t\"Bob \"\"B\"\", Jr.\"\r\n");
+    }
+}
@@ -0,0 +1,820 @@
+//! Structural diff between two JSON trees.
+//!
+//! Walks two `JsonTree`s side by side and builds a single merged tree in which
+//! every differing node is tagged with the kind of change it represents.
+//! Object members are matched by key; array elements are matched according to
+//! an `ArrayMatching` strategy.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use iced::Color;
+
+use crate::parser::{JsonNode, JsonTree, JsonValue};
+use crate::theme::ThemeColors;
+
+/// Above this many comparisons the LCS table gets too expensive, and we fall
+/// back to treating the sequences as unordered (no "moved" detection).
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// The kind of change a merged node represents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Only present in the right document
+    Added,
+    /// Only present in the left document
+    Removed,
+    /// Present in both, but with a different value or type
+    Changed,
+    /// Present in both with the same value, but at a different position
+    Moved,
+}
+
+impl DiffKind {
+    /// Single-character gutter marker shown in front of the row
+    pub fn marker(&self) -> &'static str {
+        match self {
+            DiffKind::Added => "+",
+            DiffKind::Removed => "−",
+            DiffKind::Changed => "~",
+            DiffKind::Moved => "↕",
+        }
+    }
+
+    /// Get the color for this kind of change given a theme
+    pub fn color(&self, colors: &ThemeColors) -> Color {
+        match self {
+            DiffKind::Added => colors.diff_added,
+            DiffKind::Removed => colors.diff_removed,
+            DiffKind::Changed => colors.diff_changed,
+            DiffKind::Moved => colors.diff_moved,
+        }
+    }
+}
+
+/// How elements of two arrays are paired up before they are compared
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ArrayMatching {
+    /// Element `i` on the left is compared with element `i` on the right
+    #[default]
+    Index,
+    /// Equal elements are matched wherever they are (detects reordering)
+    Value,
+    /// Object elements are matched by the value of the given field (e.g. "id")
+    Key(String),
+}
+
+/// Result of comparing two trees
+#[derive(Debug)]
+pub struct DiffResult {
+    /// Merged tree containing the union of both documents
+    pub tree: JsonTree,
+    /// Change kind for every node of `tree` that differs
+    pub kinds: HashMap<usize, DiffKind>,
+    /// Previous value display for `Changed` nodes
+    pub old_values: HashMap<usize, String>,
+    /// Root node of every difference, in document (pre-order) order
+    pub changes: Vec<usize>,
+}
+
+/// Compare-mode state: both source documents plus the current diff.
+///
+/// The merged tree itself lives in `App::tree` so that the regular tree view,
+/// search and copy actions keep working on it.
+#[derive(Debug)]
+pub struct CompareSession {
+    pub left_path: PathBuf,
+    pub right_path: PathBuf,
+    pub left: JsonTree,
+    pub right: JsonTree,
+    pub matching: ArrayMatching,
+    /// Text of the "match by key" input in the compare bar
+    pub match_key_input: String,
+    pub kinds: HashMap<usize, DiffKind>,
+    pub old_values: HashMap<usize, String>,
+    pub changes: Vec<usize>,
+    /// Position in `changes` of the current difference
+    pub change_index: Option<usize>,
+}
+
+impl CompareSession {
+    /// Start a compare session; returns the session and the merged tree
+    pub fn new(left_path: PathBuf, left: JsonTree, right_path: PathBuf, right: JsonTree) -> (Self, JsonTree) {
+        let mut session = CompareSession {
+            left_path,
+            right_path,
+            left,
+            right,
+            matching: ArrayMatching::default(),
+            match_key_input: String::new(),
+            kinds: HashMap::new(),
+            old_values: HashMap::new(),
+            changes: Vec::new(),
+            change_index: None,
+        };
+        let tree = session.recompute();
+        (session, tree)
+    }
+
+    /// Re-run the diff (e.g. after the matching strategy changed)
+    pub fn recompute(&mut self) -> JsonTree {
+        let result = diff_trees(&self.left, &self.right, &self.matching);
+        self.kinds = result.kinds;
+        self.old_values = result.old_values;
+        self.changes = result.changes;
+        self.change_index = None;
+        result.tree
+    }
+
+    /// Count the differences of a given kind
+    pub fn count(&self, kind: DiffKind) -> usize {
+        count_kind(&self.changes, &self.kinds, kind)
+    }
+}
+
+fn count_kind(changes: &[usize], kinds: &HashMap<usize, DiffKind>, kind: DiffKind) -> usize {
+    changes.iter().filter(|idx| kinds.get(idx) == Some(&kind)).count()
+}
+
+/// Compare two trees and build the merged diff tree
+pub fn diff_trees(left: &JsonTree, right: &JsonTree, matching: &ArrayMatching) -> DiffResult {
+    let mut merger = Merger {
+        left,
+        right,
+        left_hashes: subtree_hashes(left),
+        right_hashes: subtree_hashes(right),
+        matching,
+        out: JsonTree::new(),
+        kinds: HashMap::new(),
+        old_values: HashMap::new(),
+        change_roots: HashSet::new(),
+    };
+
+    let root = merger.merge(Some(left.root_index()), Some(right.root_index()), None, 0);
+    merger.out.set_root(root);
+
+    let mut changes = Vec::new();
+    collect_changes(&merger.out, root, &merger.change_roots, &mut changes);
+
+    DiffResult {
+        tree: merger.out,
+        kinds: merger.kinds,
+        old_values: merger.old_values,
+        changes,
+    }
+}
+
+/// Walk the merged tree in pre-order and collect change roots
+fn collect_changes(tree: &JsonTree, index: usize, roots: &HashSet<usize>, out: &mut Vec<usize>) {
+    if roots.contains(&index) {
+        out.push(index);
+    }
+    if let Some(node) = tree.get_node(index) {
+        for &child in &node.children {
+            collect_changes(tree, child, roots, out);
+        }
+    }
+}
+
+/// Compute a structural hash for every node of a tree.
+///
+/// Object hashes are independent of member order, so `{"a":1,"b":2}` and
+/// `{"b":2,"a":1}` hash the same; array hashes are order-sensitive.
+fn subtree_hashes(tree: &JsonTree) -> Vec<u64> {
+    let mut hashes = vec![0; tree.node_count()];
+    if tree.node_count() > 0 {
+        hash_node(tree, tree.root_index(), &mut hashes);
+    }
+    hashes
+}
+
+fn hash_node(tree: &JsonTree, index: usize, hashes: &mut [u64]) -> u64 {
+    let Some(node) = tree.get_node(index) else {
+        return 0;
+    };
+
+    let mut hasher = DefaultHasher::new();
+    match &node.value {
+        JsonValue::Null => 0u8.hash(&mut hasher),
+        JsonValue::Bool(b) => (1u8, b).hash(&mut hasher),
+        JsonValue::Number(n) => {
+            // Normalise -0.0 so that it hashes like 0.0
+            let n = if *n == 0.0 { 0.0 } else { *n };
+            (2u8, n.to_bits()).hash(&mut hasher);
+        }
+        JsonValue::String(s) => (3u8, s).hash(&mut hasher),
+        JsonValue::Array => {
+            4u8.hash(&mut hasher);
+            for &child in &node.children {
+                hash_node(tree, child, hashes).hash(&mut hasher);
+            }
+        }
+        JsonValue::Object => {
+            5u8.hash(&mut hasher);
+            let mut members: u64 = 0;
+            for &child in &node.children {
+                let child_hash = hash_node(tree, child, hashes);
+                let mut entry = DefaultHasher::new();
+                tree.get_node(child).and_then(|c| c.key.as_deref()).hash(&mut entry);
+                child_hash.hash(&mut entry);
+                members = members.wrapping_add(entry.finish());
+            }
+            members.hash(&mut hasher);
+        }
+    }
+
+    let hash = hasher.finish();
+    hashes[index] = hash;
+    hash
+}
+
+/// Short display form of a node's value (used for "was: …" annotations)
+pub fn value_summary(tree: &JsonTree, index: usize) -> String {
+    match tree.get_node(index).map(|n| &n.value) {
+        Some(JsonValue::Null) => "null".to_string(),
+        Some(JsonValue::Bool(b)) => b.to_string(),
+        Some(JsonValue::Number(n)) => n.to_string(),
+        Some(JsonValue::String(s)) => format!("\"{}\"", s),
+        Some(JsonValue::Array) => "[...]".to_string(),
+        Some(JsonValue::Object) => "{...}".to_string(),
+        None => String::new(),
+    }
+}
+
+/// Indices of a longest common subsequence of two hash sequences, as
+/// `(left_position, right_position)` pairs. Returns `None` when the inputs are
+/// too large for the quadratic table.
+fn lcs_pairs(left: &[u64], right: &[u64]) -> Option<Vec<(usize, usize)>> {
+    let (n, m) = (left.len(), right.len());
+    if n.saturating_mul(m) > MAX_LCS_CELLS {
+        return None;
+    }
+
+    // table[i][j] = LCS length of left[i..] and right[j..]
+    let mut table = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[at(i, j)] = if left[i] == right[j] {
+                table[at(i + 1, j + 1)] + 1
+            } else {
+                table[at(i + 1, j)].max(table[at(i, j + 1)])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if left[i] == right[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if table[at(i + 1, j)] >= table[at(i, j + 1)] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    Some(pairs)
+}
+
+/// One entry of a merged child list
+struct Pairing {
+    left: Option<usize>,
+    right: Option<usize>,
+    /// Matched elements that are not part of the common ordering
+    moved: bool,
+}
+
+/// Order matched/unmatched children for output: the right-hand order is kept,
+/// and each left-only child is placed right after its nearest preceding
+/// matched sibling.
+fn order_pairings(
+    left_len: usize,
+    right_len: usize,
+    matches: &[(usize, usize)],
+    moved: &HashSet<usize>,
+) -> Vec<Pairing> {
+    let mut right_to_left: Vec<Option<usize>> = vec![None; right_len];
+    let mut left_to_right: Vec<Option<usize>> = vec![None; left_len];
+    for &(l, r) in matches {
+        right_to_left[r] = Some(l);
+        left_to_right[l] = Some(r);
+    }
+
+    // slots[k] holds removed children emitted before right child k
+    let mut slots: Vec<Vec<usize>> = vec![Vec::new(); right_len + 1];
+    let mut anchor_slot = 0;
+    for (l, partner) in left_to_right.iter().enumerate() {
+        match partner {
+            Some(r) => anchor_slot = r + 1,
+            None => slots[anchor_slot].push(l),
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(left_len.max(right_len));
+    for (r, slot) in slots.iter().enumerate() {
+        ordered.extend(slot.iter().map(|&l| Pairing { left: Some(l), right: None, moved: false }));
+        if r < right_len {
+            ordered.push(Pairing {
+                left: right_to_left[r],
+                right: Some(r),
+                moved: moved.contains(&r),
+            });
+        }
+    }
+    ordered
+}
+
+/// Of all matched pairs, find the right positions that fall outside the
+/// longest in-order run of matches, i.e. the elements that were moved.
+fn moved_positions(matches: &[(usize, usize)]) -> HashSet<usize> {
+    let mut by_left: Vec<(usize, usize)> = matches.to_vec();
+    by_left.sort_unstable();
+    let left_seq: Vec<u64> = by_left.iter().map(|&(_, r)| r as u64).collect();
+    let mut right_seq = left_seq.clone();
+    right_seq.sort_unstable();
+
+    match lcs_pairs(&left_seq, &right_seq) {
+        Some(kept) => {
+            let kept: HashSet<usize> = kept.iter().map(|&(i, _)| by_left[i].1).collect();
+            by_left
+                .iter()
+                .map(|&(_, r)| r)
+                .filter(|r| !kept.contains(r))
+                .collect()
+        }
+        None => HashSet::new(),
+    }
+}
+
+struct Merger<'a> {
+    left: &'a JsonTree,
+    right: &'a JsonTree,
+    left_hashes: Vec<u64>,
+    right_hashes: Vec<u64>,
+    matching: &'a ArrayMatching,
+    out: JsonTree,
+    kinds: HashMap<usize, DiffKind>,
+    old_values: HashMap<usize, String>,
+    change_roots: HashSet<usize>,
+}
+
+impl Merger<'_> {
+    /// Merge a left node and/or a right node into the output tree
+    fn merge(
+        &mut self,
+        left: Option<usize>,
+        right: Option<usize>,
+        key: Option<String>,
+        depth: usize,
+    ) -> usize {
+        match (left, right) {
+            (Some(l), None) => self.copy_marked(Side::Left, l, key, depth, DiffKind::Removed),
+            (None, Some(r)) => self.copy_marked(Side::Right, r, key, depth, DiffKind::Added),
+            (Some(l), Some(r)) => self.merge_pair(l, r, key, depth),
+            (None, None) => unreachable!("merge called without any node"),
+        }
+    }
+
+    fn merge_pair(&mut self, l: usize, r: usize, key: Option<String>, depth: usize) -> usize {
+        if self.subtrees_equal(l, r) {
+            return self.copy(Side::Right, r, key, depth, None);
+        }
+
+        let (Some(left_node), Some(right_node)) = (self.left.get_node(l), self.right.get_node(r)) else {
+            return self.copy(Side::Right, r, key, depth, None);
+        };
+
+        match (&left_node.value, &right_node.value) {
+            (JsonValue::Object, JsonValue::Object) => self.merge_object(left_node, right_node, key, depth),
+            (JsonValue::Array, JsonValue::Array) => self.merge_array(left_node, right_node, key, depth),
+            _ => {
+                let index = self.copy(Side::Right, r, key, depth, None);
+                self.mark(index, DiffKind::Changed);
+                self.old_values.insert(index, value_summary(self.left, l));
+                index
+            }
+        }
+    }
+
+    fn merge_object(
+        &mut self,
+        left_node: &JsonNode,
+        right_node: &JsonNode,
+        key: Option<String>,
+        depth: usize,
+    ) -> usize {
+        let left_keys: HashMap<&str, usize> = left_node
+            .children
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, &c)| self.left.get_node(c).and_then(|n| n.key.as_deref()).map(|k| (k, pos)))
+            .collect();
+
+        let matches: Vec<(usize, usize)> = right_node
+            .children
+            .iter()
+            .enumerate()
+            .filter_map(|(r_pos, &c)| {
+                let k = self.right.get_node(c)?.key.as_deref()?;
+                left_keys.get(k).map(|&l_pos| (l_pos, r_pos))
+            })
+            .collect();
+
+        let moved = moved_positions(&matches);
+        let pairings = order_pairings(left_node.children.len(), right_node.children.len(), &matches, &moved);
+
+        let mut children = Vec::with_capacity(pairings.len());
+        for pairing in pairings {
+            let l = pairing.left.map(|p| left_node.children[p]);
+            let r = pairing.right.map(|p| right_node.children[p]);
+            let child_key = r
+                .and_then(|i| self.right.get_node(i))
+                .or_else(|| l.and_then(|i| self.left.get_node(i)))
+                .and_then(|n| n.key.clone());
+            let child = self.merge(l, r, child_key, depth + 1);
+            if pairing.moved && !self.kinds.contains_key(&child) {
+                self.mark(child, DiffKind::Moved);
+            }
+            children.push(child);
+        }
+
+        self.push_container(JsonValue::Object, key, depth, children)
+    }
+
+    fn merge_array(
+        &mut self,
+        left_node: &JsonNode,
+        right_node: &JsonNode,
+        key: Option<String>,
+        depth: usize,
+    ) -> usize {
+        let (left_len, right_len) = (left_node.children.len(), right_node.children.len());
+
+        let (matches, moved) = match self.matching {
+            ArrayMatching::Index => {
+                let matches: Vec<(usize, usize)> = (0..left_len.min(right_len)).map(|i| (i, i)).collect();
+                (matches, HashSet::new())
+            }
+            ArrayMatching::Value => {
+                let left_seq: Vec<u64> = left_node.children.iter().map(|&c| self.left_hashes[c]).collect();
+                let right_seq: Vec<u64> = right_node.children.iter().map(|&c| self.right_hashes[c]).collect();
+                self.match_by_identity(&left_seq, &right_seq)
+            }
+            ArrayMatching::Key(field) => {
+                let left_seq: Vec<u64> = left_node
+                    .children
+                    .iter()
+                    .map(|&c| element_identity(self.left, c, field, self.left_hashes[c]))
+                    .collect();
+                let right_seq: Vec<u64> = right_node
+                    .children
+                    .iter()
+                    .map(|&c| element_identity(self.right, c, field, self.right_hashes[c]))
+                    .collect();
+                self.match_by_identity(&left_seq, &right_seq)
+            }
+        };
+
+        let pairings = order_pairings(left_len, right_len, &matches, &moved);
+
+        let mut children = Vec::with_capacity(pairings.len());
+        for pairing in pairings {
+            let l = pairing.left.map(|p| left_node.children[p]);
+            let r = pairing.right.map(|p| right_node.children[p]);
+            let position = pairing.right.or(pairing.left).unwrap_or(0);
+            let child = self.merge(l, r, Some(format!("[{}]", position)), depth + 1);
+            if pairing.moved && !self.kinds.contains_key(&child) {
+                self.mark(child, DiffKind::Moved);
+            }
+            children.push(child);
+        }
+
+        self.push_container(JsonValue::Array, key, depth, children)
+    }
+
+    /// Match elements with equal identities: first the longest in-order run,
+    /// then any remaining equal pairs (which are reported as moved).
+    fn match_by_identity(&self, left_seq: &[u64], right_seq: &[u64]) -> (Vec<(usize, usize)>, HashSet<usize>) {
+        let mut matches = lcs_pairs(left_seq, right_seq).unwrap_or_default();
+        let mut left_used: HashSet<usize> = matches.iter().map(|&(l, _)| l).collect();
+        let right_used: HashSet<usize> = matches.iter().map(|&(_, r)| r).collect();
+
+        let mut unused_left: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (l, &id) in left_seq.iter().enumerate().rev() {
+            if !left_used.contains(&l) {
+                unused_left.entry(id).or_default().push(l);
+            }
+        }
+
+        let mut moved = HashSet::new();
+        for (r, id) in right_seq.iter().enumerate() {
+            if right_used.contains(&r) {
+                continue;
+            }
+            if let Some(l) = unused_left.get_mut(id).and_then(|v| v.pop()) {
+                left_used.insert(l);
+                matches.push((l, r));
+                moved.insert(r);
+            }
+        }
+
+        (matches, moved)
+    }
+
+    fn subtrees_equal(&self, l: usize, r: usize) -> bool {
+        self.left_hashes.get(l) == self.right_hashes.get(r) && nodes_equal(self.left, l, self.right, r)
+    }
+
+    fn push_container(&mut self, value: JsonValue, key: Option<String>, depth: usize, children: Vec<usize>) -> usize {
+        self.out.add_node(JsonNode {
+            key,
+            value,
+            depth,
+            children,
+            expanded: false,
+        })
+    }
+
+    /// Copy a subtree and tag every node in it with `kind`
+    fn copy_marked(&mut self, side: Side, index: usize, key: Option<String>, depth: usize, kind: DiffKind) -> usize {
+        let root = self.copy(side, index, key, depth, Some(kind));
+        self.change_roots.insert(root);
+        root
+    }
+
+    /// Copy a subtree from one side into the output tree
+    fn copy(&mut self, side: Side, index: usize, key: Option<String>, depth: usize, kind: Option<DiffKind>) -> usize {
+        let source = match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+        };
+        let Some(node) = source.get_node(index) else {
+            return self.push_container(JsonValue::Null, key, depth, Vec::new());
+        };
+
+        let children = node
+            .children
+            .iter()
+            .map(|&c| {
+                let child_key = source.get_node(c).and_then(|n| n.key.clone());
+                self.copy(side, c, child_key, depth + 1, kind)
+            })
+            .collect();
+
+        let out_index = self.out.add_node(JsonNode {
+            key,
+            value: node.value.clone(),
+            depth,
+            children,
+            expanded: false,
+        });
+        if let Some(kind) = kind {
+            self.kinds.insert(out_index, kind);
+        }
+        out_index
+    }
+
+    fn mark(&mut self, index: usize, kind: DiffKind) {
+        self.kinds.insert(index, kind);
+        self.change_roots.insert(index);
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// Identity of an array element for key-based matching: the display of the
+/// `field` member when present, otherwise the element's structural hash.
+fn element_identity(tree: &JsonTree, index: usize, field: &str, fallback: u64) -> u64 {
+    let field_value = tree.get_node(index).and_then(|node| {
+        node.children
+            .iter()
+            .find(|&&c| tree.get_node(c).and_then(|n| n.key.as_deref()) == Some(field))
+            .map(|&c| value_summary(tree, c))
+    });
+
+    match field_value {
+        Some(value) => {
+            let mut hasher = DefaultHasher::new();
+            ("key", value).hash(&mut hasher);
+            hasher.finish()
+        }
+        None => fallback,
+    }
+}
+
+/// Deep semantic equality of two subtrees (object member order is ignored)
+fn nodes_equal(a_tree: &JsonTree, a: usize, b_tree: &JsonTree, b: usize) -> bool {
+    let (Some(a_node), Some(b_node)) = (a_tree.get_node(a), b_tree.get_node(b)) else {
+        return false;
+    };
+
+    match (&a_node.value, &b_node.value) {
+        (JsonValue::Array, JsonValue::Array) => {
+            a_node.children.len() == b_node.children.len()
+                && a_node
+                    .children
+                    .iter()
+                    .zip(&b_node.children)
+                    .all(|(&x, &y)| nodes_equal(a_tree, x, b_tree, y))
+        }
+        (JsonValue::Object, JsonValue::Object) => {
+            if a_node.children.len() != b_node.children.len() {
+                return false;
+            }
+            let b_members: HashMap<&str, usize> = b_node
+                .children
+                .iter()
+                .filter_map(|&c| b_tree.get_node(c).and_then(|n| n.key.as_deref()).map(|k| (k, c)))
+                .collect();
+            a_node.children.iter().all(|&c| {
+                a_tree
+                    .get_node(c)
+                    .and_then(|n| n.key.as_deref())
+                    .and_then(|k| b_members.get(k))
+                    .is_some_and(|&other| nodes_equal(a_tree, c, b_tree, other))
+            })
+        }
+        (a_value, b_value) => a_value == b_value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::build_tree;
+    use serde_json::json;
+
+    /// Count the differences of a given kind in a result
+    fn count(result: &DiffResult, kind: DiffKind) -> usize {
+        count_kind(&result.changes, &result.kinds, kind)
+    }
+
+    /// Find a merged node by its key, searching the whole tree
+    fn find_key(tree: &JsonTree, key: &str) -> usize {
+        (0..tree.node_count())
+            .find(|&i| tree.get_node(i).and_then(|n| n.key.as_deref()) == Some(key))
+            .unwrap_or_else(|| panic!("key {} not found", key))
+    }
+
+    #[test]
+    fn test_identical_documents_have_no_changes() {
+        let left = build_tree(&json!({"a": 1, "b": [1, 2, {"c": null}]}));
+        let right = build_tree(&json!({"b": [1, 2, {"c": null}], "a": 1}));
+
+        let result = diff_trees(&left, &right, &ArrayMatching::Index);
+        assert!(result.changes.is_empty());
+        assert!(result.kinds.is_empty());
+    }
+
+    #[test]
+    fn test_added_removed_changed_keys() {
+        let left = build_tree(&json!({"keep": 1, "gone": true, "edit": "old"}));
+        let right = build_tree(&json!({"keep": 1, "edit": "new", "fresh": null}));
+
+        let result = diff_trees(&left, &right, &ArrayMatching::Index);
+
+        assert_eq!(result.kinds.get(&find_key(&result.tree, "gone")), Some(&DiffKind::Removed));
+        assert_eq!(result.kinds.get(&find_key(&result.tree, "fresh")), Some(&DiffKind::Added));
+        let edit = find_key(&result.tree, "edit");
+        assert_eq!(result.kinds.get(&edit), Some(&DiffKind::Changed));
+        assert_eq!(result.old_values.get(&edit).map(String::as_str), Some("\"old\""));
+        assert!(!result.kinds.contains_key(&find_key(&result.tree, "keep")));
+        assert_eq!(result.changes.len(), 3);
+    }
+
+    #[test]
+    fn test_removed_key_keeps_its_position() {
+        let left = build_tree(&json!({"a": 1, "b": 2, "c": 3}));
+        let right = build_tree(&json!({"a": 1, "c": 3}));
+
+        let result = diff_trees(&left, &right, &ArrayMatching::Index);
+        let root = result.tree.root().unwrap();
+        let keys: Vec<&str> = root
+            .children
+            .iter()
+            .filter_map(|&c| result.tree.get_node(c).and_then(|n| n.key.as_deref()))
+            .collect();
+        assert_eq!(keys, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_moved_object_keys() {
+        let left = build_tree(&json!({"a": 1, "b": 2, "c": 3}));
+        let right = build_tree(&json!({"b": 2, "c": 3, "a": 1}));
+
+        let result = diff_trees(&left, &right, &ArrayMatching::Index);
+        // Same members, different order: objects compare equal semantically
+        assert!(result.changes.is_empty());
+
+        let right = build_tree(&json!({"b": 2, "c": 4, "a": 1}));
+        let result = diff_trees(&left, &right, &ArrayMatching::Index);
+        assert_eq!(result.kinds.get(&find_key(&result.tree, "a")), Some(&DiffKind::Moved));
+        assert_eq!(result.kinds.get(&find_key(&result.tree, "c")), Some(&DiffKind::Changed));
+    }
+
+    #[test]
+    fn test_moved_keys_in_parsed_documents() {
+        // Files are parsed with serde_json, so member order must survive parsing
+        let parse = |text: &str| build_tree(&serde_json::from_str(text).unwrap());
+        let left = parse(r#"{"name": "x", "tags": {"z": 1, "y": 2}}"#);
+        let right = parse(r#"{"name": "x", "tags": {"y": 2, "z": 1, "w": 3}}"#);
+
+        let result = diff_trees(&left, &right, &ArrayMatching::Index);
+        assert_eq!(result.kinds.get(&find_key(&result.tree, "z")), Some(&DiffKind::Moved));
+        assert_eq!(result.kinds.get(&find_key(&result.tree, "w")), Some(&DiffKind::Added));
+        assert_eq!(result.kinds.get(&find_key(&result.tree, "y")), None);
+    }
+
+    #[test]
+    fn test_array_index_matching() {
+        let left = build_tree(&json!([1, 2, 3]));
+        let right = build_tree(&json!([0, 1, 2, 3]));
+
+        // By index, every position changes and one is added
+        let result = diff_trees(&left, &right, &ArrayMatching::Index);
+        assert_eq!(count(&result, DiffKind::Changed), 3);
+        assert_eq!(count(&result, DiffKind::Added), 1);
+    }
+
+    #[test]
+    fn test_array_value_matching() {
+        let left = build_tree(&json!([1, 2, 3]));
+        let right = build_tree(&json!([0, 1, 2, 3]));
+
+        // By value, only the inserted element differs
+        let result = diff_trees(&left, &right, &ArrayMatching::Value);
+        assert_eq!(result.changes.len(), 1);
+        assert_eq!(count(&result, DiffKind::Added), 1);
+
+        let left = build_tree(&json!(["a", "b", "c"]));
+        let right = build_tree(&json!(["c", "a", "b"]));
+        let result = diff_trees(&left, &right, &ArrayMatching::Value);
+        assert_eq!(result.changes.len(), 1);
+        assert_eq!(count(&result, DiffKind::Moved), 1);
+    }
+
+    #[test]
+    fn test_array_key_matching() {
+        let left = build_tree(&json!([
+            {"id": 1, "name": "alpha"},
+            {"id": 2, "name": "beta"}
+        ]));
+        let right = build_tree(&json!([
+            {"id": 2, "name": "beta"},
+            {"id": 1, "name": "ALPHA"},
+            {"id": 3, "name": "gamma"}
+        ]));
+
+        let result = diff_trees(&left, &right, &ArrayMatching::Key("id".to_string()));
+        assert_eq!(count(&result, DiffKind::Added), 1);
+        assert_eq!(count(&result, DiffKind::Moved), 1);
+        assert_eq!(count(&result, DiffKind::Changed), 1);
+        assert_eq!(count(&result, DiffKind::Removed), 0);
+    }
+
+    #[test]
+    fn test_type_change_is_changed() {
+        let left = build_tree(&json!({"v": {"nested": true}}));
+        let right = build_tree(&json!({"v": "flat"}));
+
+        let result = diff_trees(&left, &right, &ArrayMatching::Index);
+        let v = find_key(&result.tree, "v");
+        assert_eq!(result.kinds.get(&v), Some(&DiffKind::Changed));
+        assert_eq!(result.old_values.get(&v).map(String::as_str), Some("{...}"));
+    }
+
+    #[test]
+    fn test_changes_are_in_document_order() {
+        let left = build_tree(&json!({"a": 1, "b": {"x": 1}, "c": 1}));
+        let right = build_tree(&json!({"a": 2, "b": {"x": 2}, "c": 2}));
+
+        let result = diff_trees(&left, &right, &ArrayMatching::Index);
+        let keys: Vec<&str> = result
+            .changes
+            .iter()
+            .filter_map(|&i| result.tree.get_node(i).and_then(|n| n.key.as_deref()))
+            .collect();
+        assert_eq!(keys, vec!["a", "x", "c"]);
+    }
+
+    #[test]
+    fn test_removed_subtree_is_fully_marked() {
+        let left = build_tree(&json!({"gone": {"a": [1, 2]}}));
+        let right = build_tree(&json!({}));
+
+        let result = diff_trees(&left, &right, &ArrayMatching::Index);
+        assert_eq!(result.changes.len(), 1);
+        // "gone" object, "a" array and its two elements
+        assert_eq!(result.kinds.len(), 4);
+        assert!(result.kinds.values().all(|k| *k == DiffKind::Removed));
+    }
+}
@@ -0,0 +1,228 @@
+//! Per-document state: one `Document` per tab.
+//!
+//! Everything that belongs to an open file lives here — the tree, its
+//! flattened rows, search, selection, scroll position and the modes layered
+//! on top of it (compare, patch preview, schema, table). Window-wide state
+//! such as the theme, dialogs and the config stays on `App`.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::bookmarks::Bookmark;
+use crate::diff::CompareSession;
+use crate::flat_row::FlatRow;
+use crate::history::NavHistory;
+use crate::jsonl::LogFollower;
+use crate::parse_error::ParseError;
+use crate::parser::JsonTree;
+use crate::patch::PatchPreview;
+use crate::schema::SchemaSession;
+use crate::search::{self, SearchFilter};
+use crate::stats::DocumentStats;
+use crate::table::TableView;
+
+/// An open document and its view state
+#[derive(Debug)]
+pub struct Document {
+    pub tree: Option<JsonTree>,
+    pub status: String,
+    pub current_file: Option<PathBuf>,
+    /// Tab label for a document that only exists in memory (e.g. a generated schema)
+    pub untitled_name: Option<String>,
+    /// Time taken to load and parse the file
+    pub load_time: Option<Duration>,
+    /// Flattened rows for virtual scrolling (rebuilt when tree changes)
+    pub flat_rows: Vec<FlatRow>,
+    /// Current scroll offset in pixels (for virtual scrolling)
+    pub scroll_offset: f32,
+    // Search state
+    pub search_query: String,
+    pub search_results: Vec<usize>,
+    pub search_result_index: Option<usize>,
+    pub search_matches: HashSet<usize>,
+    pub search_case_sensitive: bool,
+    pub search_use_regex: bool,
+    pub search_filter: SearchFilter,
+    /// JSON Pointer of the node whose descendants are searched (all nodes when
+    /// `None`, which is also how a scope on the root is kept)
+    pub search_scope: Option<String>,
+    pub search_regex_error: Option<String>,
+    /// Currently selected node (for copy, path display, etc.)
+    pub selected_node: Option<usize>,
+    /// Parse error details (for better error display)
+    pub parse_error: Option<ParseError>,
+    /// Compare mode: Some while `tree` holds a merged diff of two files
+    pub compare: Option<CompareSession>,
+    /// Patch preview: Some after a patch was applied to the document
+    pub patch_preview: Option<PatchPreview>,
+    /// Attached JSON Schema and its validation results
+    pub schema: Option<SchemaSession>,
+    /// Statistics for the document (computed while the panel is shown)
+    pub stats: Option<DocumentStats>,
+    /// Grid view replacing the tree for an array of objects
+    pub table: Option<TableView>,
+    /// Modification time and size of the open file when it was (re)loaded
+    pub loaded_stamp: Option<(SystemTime, u64)>,
+    /// The open file changed on disk and the user hasn't reloaded yet
+    pub file_changed_on_disk: bool,
+    /// Reader for lines appended to the open JSON Lines file
+    pub follower: Option<LogFollower>,
+    /// Follow mode: append new JSON Lines instead of offering a reload
+    pub following: bool,
+    /// Lines were appended since validation and statistics last ran; they
+    /// are caught up on a timer rather than after every append
+    pub follow_analysis_pending: bool,
+    /// Bookmarks of the open file, in the order they were added
+    pub bookmarks: Vec<Bookmark>,
+    /// Node index -> position in `bookmarks`, for bookmarks found in the tree
+    pub bookmark_nodes: HashMap<usize, usize>,
+    /// A label was edited but not written to disk yet
+    pub bookmarks_unsaved: bool,
+    /// Visited nodes for Back/Forward
+    pub history: NavHistory,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Document {
+            tree: None,
+            status: String::from("No file loaded"),
+            current_file: None,
+            untitled_name: None,
+            load_time: None,
+            flat_rows: Vec::new(),
+            scroll_offset: 0.0,
+            search_query: String::new(),
+            search_results: Vec::new(),
+            search_result_index: None,
+            search_matches: HashSet::new(),
+            search_case_sensitive: false,
+            search_use_regex: false,
+            search_filter: SearchFilter::default(),
+            search_scope: None,
+            search_regex_error: None,
+            selected_node: None,
+            parse_error: None,
+            compare: None,
+            patch_preview: None,
+            schema: None,
+            stats: None,
+            table: None,
+            loaded_stamp: None,
+            file_changed_on_disk: false,
+            follower: None,
+            following: false,
+            follow_analysis_pending: false,
+            bookmarks: Vec::new(),
+            bookmark_nodes: HashMap::new(),
+            bookmarks_unsaved: false,
+            history: NavHistory::default(),
+        }
+    }
+}
+
+impl Document {
+    /// Nothing loaded and no error to show: the tab shows the welcome screen
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_none() && self.parse_error.is_none()
+    }
+
+    /// Tab label: the file name, both names in compare mode, the name of an
+    /// in-memory document, or "Untitled"
+    pub fn title(&self) -> String {
+        let name = |path: &PathBuf| path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        if let Some(compare) = &self.compare {
+            return format!("{} ↔ {}", name(&compare.left_path), name(&compare.right_path));
+        }
+        match &self.current_file {
+            Some(path) => name(path),
+            None if self.parse_error.is_some() => "Error".to_string(),
+            None if let Some(name) = &self.untitled_name => name.clone(),
+            None => "Untitled".to_string(),
+        }
+    }
+
+    /// Run the search over the whole tree or the scoped subtree. A scope
+    /// that no longer exists (after a reload or patch) is dropped, and so is
+    /// one on the root, which covers the whole tree anyway.
+    pub fn find_matches(&mut self) -> (Vec<usize>, Option<String>) {
+        let Some(tree) = &self.tree else {
+            return (Vec::new(), None);
+        };
+        let scope = self.search_scope.as_deref()
+            .and_then(|pointer| tree.resolve_pointer(pointer))
+            .filter(|&scope| scope != tree.root_index());
+        if scope.is_none() {
+            self.search_scope = None;
+        }
+        let (query, case_sensitive, use_regex, filter) =
+            (&self.search_query, self.search_case_sensitive, self.search_use_regex, self.search_filter);
+        match scope {
+            Some(scope) => search::search_subtree(tree, scope, query, case_sensitive, use_regex, filter),
+            None => search::search_nodes(tree, query, case_sensitive, use_regex, filter),
+        }
+    }
+}
+
+/// Where the tab at `index` ends up after the tab at `from` moved to `to`
+pub fn index_after_move(index: usize, from: usize, to: usize) -> usize {
+    if index == from {
+        to
+    } else if from < index && index <= to {
+        index - 1
+    } else if to <= index && index < from {
+        index + 1
+    } else {
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_after_move() {
+        // Tabs [a, b, c, d]: move a to the end -> [b, c, d, a]
+        assert_eq!(index_after_move(0, 0, 3), 3);
+        assert_eq!(index_after_move(1, 0, 3), 0);
+        assert_eq!(index_after_move(3, 0, 3), 2);
+        // Move d to the front -> [d, a, b, c]
+        assert_eq!(index_after_move(0, 3, 0), 1);
+        assert_eq!(index_after_move(2, 3, 0), 3);
+        // Tabs outside the moved range stay put
+        assert_eq!(index_after_move(3, 0, 1), 3);
+        assert_eq!(index_after_move(0, 2, 1), 0);
+    }
+
+    #[test]
+    fn test_title() {
+        let mut doc = Document::default();
+        assert!(doc.is_empty());
+        assert_eq!(doc.title(), "Untitled");
+
+        doc.untitled_name = Some("users.schema.json".to_string());
+        assert_eq!(doc.title(), "users.schema.json");
+
+        doc.current_file = Some(PathBuf::from("/tmp/data/users.json"));
+        assert_eq!(doc.title(), "users.json");
+    }
+
+    #[test]
+    fn test_root_scope_searches_everything() {
+        let mut doc = Document {
+            tree: Some(crate::parser::build_tree(&serde_json::json!({"a": {"x": 1}, "b": {"x": 2}}))),
+            search_query: "x".to_string(),
+            search_scope: Some("/a".to_string()),
+            ..Document::default()
+        };
+        assert_eq!(doc.find_matches().0.len(), 1);
+
+        doc.search_scope = Some(String::new());
+        assert_eq!(doc.find_matches().0.len(), 2);
+        assert_eq!(doc.search_scope, None);
+    }
+}
@@ -0,0 +1,461 @@
+//! YAML, TOML and XML serializers for any subtree.
+//!
+//! Every JSON value can be written as YAML. TOML has no `null` and needs a
+//! table at the top level; XML needs keys that are valid element names and
+//! cannot carry most control characters. Those cases are reported as errors
+//! naming the JSON Pointer of the offending value instead of being papered over.
+
+use serde_json::{Map, Value};
+
+use crate::json_export::node_to_value;
+use crate::parser::{escape_token, JsonTree};
+
+/// Non-JSON output formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFormat {
+    Yaml,
+    Toml,
+    Xml,
+}
+
+impl TextFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextFormat::Yaml => "YAML",
+            TextFormat::Toml => "TOML",
+            TextFormat::Xml => "XML",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TextFormat::Yaml => "yaml",
+            TextFormat::Toml => "toml",
+            TextFormat::Xml => "xml",
+        }
+    }
+}
+
+/// Serialize a node and its children in the given format
+pub fn node_to_format(tree: &JsonTree, node_index: usize, format: TextFormat) -> Result<String, String> {
+    let value = node_to_value(tree, node_index);
+    match format {
+        TextFormat::Yaml => Ok(to_yaml(&value)),
+        TextFormat::Toml => to_toml(&value),
+        TextFormat::Xml => {
+            let name = tree.get_node(node_index)
+                .and_then(|n| n.key.as_deref())
+                .filter(|key| is_xml_name(key))
+                .unwrap_or("root");
+            to_xml(&value, name)
+        }
+    }
+}
+
+/// JSON Pointer shown in error messages ("/" for the selected value itself)
+fn location(pointer: &str) -> &str {
+    if pointer.is_empty() { "/" } else { pointer }
+}
+
+// ============================================================================
+// YAML
+// ============================================================================
+
+fn to_yaml(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Array(items) if !items.is_empty() => yaml_sequence(items, 0, &mut out),
+        Value::Object(map) if !map.is_empty() => yaml_mapping(map, 0, &mut out),
+        scalar => {
+            out.push_str(&yaml_scalar(scalar));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn yaml_mapping(map: &Map<String, Value>, indent: usize, out: &mut String) {
+    let pad = " ".repeat(indent);
+    for (key, value) in map {
+        out.push_str(&pad);
+        out.push_str(&yaml_string(key));
+        out.push(':');
+        yaml_block_value(value, indent + 2, out);
+    }
+}
+
+fn yaml_sequence(items: &[Value], indent: usize, out: &mut String) {
+    let pad = " ".repeat(indent);
+    for item in items {
+        out.push_str(&pad);
+        out.push('-');
+        match item {
+            // A nested mapping starts on the dash line: "- key: value"
+            Value::Object(map) if !map.is_empty() => {
+                let mut nested = String::new();
+                yaml_mapping(map, indent + 2, &mut nested);
+                out.push(' ');
+                out.push_str(&nested[indent + 2..]);
+            }
+            _ => yaml_block_value(item, indent + 2, out),
+        }
+    }
+}
+
+/// Write what follows "key:" or "-": a scalar on the same line or a nested block
+fn yaml_block_value(value: &Value, indent: usize, out: &mut String) {
+    match value {
+        Value::Array(items) if !items.is_empty() => {
+            out.push('\n');
+            yaml_sequence(items, indent, out);
+        }
+        Value::Object(map) if !map.is_empty() => {
+            out.push('\n');
+            yaml_mapping(map, indent, out);
+        }
+        scalar => {
+            out.push(' ');
+            out.push_str(&yaml_scalar(scalar));
+            out.push('\n');
+        }
+    }
+}
+
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(_) => value.to_string(),
+        Value::String(s) => yaml_string(s),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+    }
+}
+
+/// Plain scalar when unambiguous, otherwise a double-quoted (JSON-escaped) string
+fn yaml_string(s: &str) -> String {
+    /// Plain scalars that YAML 1.1 or 1.2 would read as something other than a string
+    const RESERVED: &[&str] = &["null", "~", "true", "false", "yes", "no", "on", "off", "y", "n"];
+
+    let looks_like_number = s.parse::<f64>().is_ok()
+        || s.starts_with("0x")
+        || s.starts_with("0o")
+        || matches!(s.to_ascii_lowercase().as_str(), ".inf" | "-.inf" | "+.inf" | ".nan");
+    let plain = !s.is_empty()
+        && !s.starts_with(|c: char| c.is_whitespace() || "-?:,[]{}#&*!|>'\"%@`".contains(c))
+        && !s.ends_with(|c: char| c.is_whitespace() || c == ':')
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.chars().any(|c| c.is_control())
+        && !RESERVED.contains(&s.to_ascii_lowercase().as_str())
+        && !looks_like_number;
+
+    if plain { s.to_string() } else { Value::String(s.to_string()).to_string() }
+}
+
+// ============================================================================
+// TOML
+// ============================================================================
+
+fn to_toml(value: &Value) -> Result<String, String> {
+    let Value::Object(map) = value else {
+        return Err(format!(
+            "a TOML document must be a table, but the selected value is {}",
+            kind(value)
+        ));
+    };
+    let mut out = String::new();
+    toml_table(map, &mut Vec::new(), "", &mut out)?;
+    Ok(out)
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// Arrays whose elements are all tables are written as `[[name]]` sections
+fn is_array_of_tables(value: &Value) -> bool {
+    matches!(value, Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_object))
+}
+
+/// Write the body of a table: plain key/values first, then sub-tables
+fn toml_table(map: &Map<String, Value>, path: &mut Vec<String>, pointer: &str, out: &mut String) -> Result<(), String> {
+    for (key, value) in map {
+        if value.is_object() || is_array_of_tables(value) {
+            continue;
+        }
+        let child_pointer = format!("{}/{}", pointer, escape_token(key));
+        out.push_str(&format!("{} = {}\n", toml_key(key), toml_inline(value, &child_pointer)?));
+    }
+
+    for (key, value) in map {
+        let child_pointer = format!("{}/{}", pointer, escape_token(key));
+        path.push(toml_key(key));
+        match value {
+            Value::Object(table) => {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                out.push_str(&format!("[{}]\n", path.join(".")));
+                toml_table(table, path, &child_pointer, out)?;
+            }
+            Value::Array(items) if is_array_of_tables(value) => {
+                for (i, item) in items.iter().enumerate() {
+                    if !out.is_empty() {
+                        out.push('\n');
+                    }
+                    out.push_str(&format!("[[{}]]\n", path.join(".")));
+                    if let Value::Object(table) = item {
+                        toml_table(table, path, &format!("{}/{}", child_pointer, i), out)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+        path.pop();
+    }
+    Ok(())
+}
+
+/// A value on the right-hand side of `key =`
+fn toml_inline(value: &Value, pointer: &str) -> Result<String, String> {
+    match value {
+        Value::Null => Err(format!("TOML has no null value (at {})", location(pointer))),
+        Value::Bool(b) => Ok(b.to_string()),
+        Value::Number(_) => Ok(value.to_string()),
+        // JSON string escapes are valid TOML basic-string escapes
+        Value::String(s) => Ok(Value::String(s.clone()).to_string()),
+        Value::Array(items) => {
+            let parts: Result<Vec<String>, String> = items.iter().enumerate()
+                .map(|(i, item)| toml_inline(item, &format!("{}/{}", pointer, i)))
+                .collect();
+            Ok(format!("[{}]", parts?.join(", ")))
+        }
+        Value::Object(map) if map.is_empty() => Ok("{}".to_string()),
+        Value::Object(map) => {
+            let parts: Result<Vec<String>, String> = map.iter()
+                .map(|(key, item)| {
+                    let child = format!("{}/{}", pointer, escape_token(key));
+                    Ok(format!("{} = {}", toml_key(key), toml_inline(item, &child)?))
+                })
+                .collect();
+            Ok(format!("{{ {} }}", parts?.join(", ")))
+        }
+    }
+}
+
+/// Bare key when possible, otherwise a quoted key
+fn toml_key(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+// ============================================================================
+// XML
+// ============================================================================
+
+fn to_xml(value: &Value, root_name: &str) -> Result<String, String> {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    match value {
+        // A bare array needs a wrapper element so the document has one root
+        Value::Array(items) => {
+            out.push_str(&format!("<{}>\n", root_name));
+            for (i, item) in items.iter().enumerate() {
+                xml_element("item", item, 1, &format!("/{}", i), &mut out)?;
+            }
+            out.push_str(&format!("</{}>\n", root_name));
+        }
+        _ => xml_element(root_name, value, 0, "", &mut out)?,
+    }
+    Ok(out)
+}
+
+fn xml_element(name: &str, value: &Value, depth: usize, pointer: &str, out: &mut String) -> Result<(), String> {
+    let pad = "  ".repeat(depth);
+    match value {
+        Value::Null => out.push_str(&format!("{}<{}/>\n", pad, name)),
+        Value::Bool(_) | Value::Number(_) => out.push_str(&format!("{}<{}>{}</{}>\n", pad, name, value, name)),
+        Value::String(s) => {
+            out.push_str(&format!("{}<{}>{}</{}>\n", pad, name, xml_escape(s, pointer)?, name));
+        }
+        Value::Array(items) if items.is_empty() => out.push_str(&format!("{}<{}/>\n", pad, name)),
+        Value::Array(items) => {
+            // Array members repeat the element; nested arrays get an <item> per element
+            for (i, item) in items.iter().enumerate() {
+                let item_pointer = format!("{}/{}", pointer, i);
+                match item {
+                    Value::Array(inner) => {
+                        out.push_str(&format!("{}<{}>\n", pad, name));
+                        for (j, element) in inner.iter().enumerate() {
+                            xml_element("item", element, depth + 1, &format!("{}/{}", item_pointer, j), out)?;
+                        }
+                        out.push_str(&format!("{}</{}>\n", pad, name));
+                    }
+                    _ => xml_element(name, item, depth, &item_pointer, out)?,
+                }
+            }
+        }
+        Value::Object(map) if map.is_empty() => out.push_str(&format!("{}<{}/>\n", pad, name)),
+        Value::Object(map) => {
+            out.push_str(&format!("{}<{}>\n", pad, name));
+            for (key, child) in map {
+                let child_pointer = format!("{}/{}", pointer, escape_token(key));
+                if !is_xml_name(key) {
+                    return Err(format!(
+                        "key {} is not a valid XML element name (at {})",
+                        Value::String(key.clone()),
+                        location(&child_pointer)
+                    ));
+                }
+                xml_element(key, child, depth + 1, &child_pointer, out)?;
+            }
+            out.push_str(&format!("{}</{}>\n", pad, name));
+        }
+    }
+    Ok(())
+}
+
+/// Element names: a letter or `_`, then letters, digits, `-`, `.` or `_`
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '-' || c == '.' || c == '_')
+}
+
+fn xml_escape(s: &str, pointer: &str) -> Result<String, String> {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {
+                return Err(format!(
+                    "control character U+{:04X} cannot appear in XML 1.0 (at {})",
+                    c as u32,
+                    location(pointer)
+                ));
+            }
+            c => escaped.push(c),
+        }
+    }
+    Ok(escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::build_tree;
+    use serde_json::json;
+
+    fn convert(value: Value, format: TextFormat) -> Result<String, String> {
+        let tree = build_tree(&value);
+        node_to_format(&tree, tree.root_index(), format)
+    }
+
+    #[test]
+    fn test_yaml() {
+        let yaml = convert(json!({
+            "name": "unfold",
+            "version": "1.0",
+            "tags": ["json", "yes", ""],
+            "items": [{"id": 1, "ok": true}, [], null],
+            "nested": {"empty": {}, "note": "a: b"}
+        }), TextFormat::Yaml).unwrap();
+
+        assert_eq!(yaml, "\
+name: unfold
+version: \"1.0\"
+tags:
+  - json
+  - \"yes\"
+  - \"\"
+items:
+  - id: 1
+    ok: true
+  - []
+  - null
+nested:
+  empty: {}
+  note: \"a: b\"
+");
+        assert_eq!(convert(json!("plain"), TextFormat::Yaml).unwrap(), "plain\n");
+    }
+
+    #[test]
+    fn test_toml() {
+        let toml = convert(json!({
+            "title": "x",
+            "owner": {"name": "a b", "dob": 1979},
+            "servers": [{"ip": "10.0.0.1", "meta": {"rack": 2}}, {"ip": "10.0.0.2"}],
+            "ports": [8000, 8001],
+            "mixed": [{"a": 1}, 2],
+            "odd key": true
+        }), TextFormat::Toml).unwrap();
+
+        assert_eq!(toml, "\
+title = \"x\"
+ports = [8000, 8001]
+mixed = [{ a = 1 }, 2]
+\"odd key\" = true
+
+[owner]
+name = \"a b\"
+dob = 1979
+
+[[servers]]
+ip = \"10.0.0.1\"
+
+[servers.meta]
+rack = 2
+
+[[servers]]
+ip = \"10.0.0.2\"
+");
+    }
+
+    #[test]
+    fn test_toml_errors() {
+        assert_eq!(
+            convert(json!([1]), TextFormat::Toml).unwrap_err(),
+            "a TOML document must be a table, but the selected value is an array"
+        );
+        assert_eq!(
+            convert(json!({"a": {"b": [1, null]}}), TextFormat::Toml).unwrap_err(),
+            "TOML has no null value (at /a/b/1)"
+        );
+    }
+
+    #[test]
+    fn test_xml() {
+        let xml = convert(json!({"book": {"title": "A & B", "authors": ["x", "y"], "isbn": null}}), TextFormat::Xml).unwrap();
+        assert_eq!(xml, "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<root>
+  <book>
+    <title>A &amp; B</title>
+    <authors>x</authors>
+    <authors>y</authors>
+    <isbn/>
+  </book>
+</root>
+");
+
+        assert_eq!(
+            convert(json!({"list": [{"1st": 1}]}), TextFormat::Xml).unwrap_err(),
+            "key \"1st\" is not a valid XML element name (at /list/0/1st)"
+        );
+        assert!(convert(json!(["a\u{1}"]), TextFormat::Xml).unwrap_err().contains("U+0001"));
+    }
+}
@@ -0,0 +1,206 @@
+//! "Go to path": jump to a node by typing its path.
+//!
+//! Three notations are accepted:
+//! - JSON Pointer: `/users/0/name`
+//! - JSONPath: `$.users[0].name`, `$['odd key']`
+//! - Dotted: `users.0.name`, `users[0].name`
+//!
+//! While typing, the children of the node the path leads to are offered as
+//! completions.
+
+use crate::parser::{escape_token, unescape_token, JsonTree, JsonValue};
+
+/// Most completions offered at once
+const MAX_COMPLETIONS: usize = 50;
+
+/// State of the go-to-path dialog
+#[derive(Debug, Clone, Default)]
+pub struct GotoPathDialog {
+    pub input: String,
+    /// Complete inputs offered for the current text
+    pub suggestions: Vec<String>,
+    /// Why the last submitted path didn't resolve
+    pub error: Option<String>,
+}
+
+impl GotoPathDialog {
+    /// Update the text and recompute the completions
+    pub fn set_input(&mut self, tree: &JsonTree, input: String) {
+        self.suggestions = completions(tree, &input);
+        self.error = None;
+        self.input = input;
+    }
+}
+
+/// Split a path in any of the accepted notations into its segments
+pub fn parse_path(input: &str) -> Result<Vec<String>, String> {
+    let input = input.trim();
+    if let Some(pointer) = input.strip_prefix('/') {
+        return Ok(pointer.split('/').map(unescape_token).collect());
+    }
+    let input = input.strip_prefix('$').unwrap_or(input);
+
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if !current.is_empty() {
+                    segments.push(std::mem::take(&mut current));
+                }
+            }
+            '[' => {
+                if !current.is_empty() {
+                    segments.push(std::mem::take(&mut current));
+                }
+                let mut segment = String::new();
+                match chars.peek() {
+                    Some(&quote @ ('\'' | '"')) => {
+                        chars.next();
+                        loop {
+                            match chars.next() {
+                                Some('\\') => segment.extend(chars.next()),
+                                Some(c) if c == quote => break,
+                                Some(c) => segment.push(c),
+                                None => return Err("Unterminated quoted key".to_string()),
+                            }
+                        }
+                        if chars.next() != Some(']') {
+                            return Err("Expected ] after quoted key".to_string());
+                        }
+                    }
+                    _ => loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some(c) => segment.push(c),
+                            None => return Err("Missing ]".to_string()),
+                        }
+                    },
+                }
+                segments.push(segment);
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        segments.push(current);
+    }
+    Ok(segments)
+}
+
+/// The child of `node_index` named by `segment`: a key for objects, an index for arrays
+fn child_by_segment(tree: &JsonTree, node_index: usize, segment: &str) -> Option<usize> {
+    let node = tree.get_node(node_index)?;
+    match node.value {
+        JsonValue::Array => segment.parse::<usize>().ok().and_then(|i| node.children.get(i).copied()),
+        JsonValue::Object => node.children.iter()
+            .copied()
+            .find(|&child| tree.get_node(child).and_then(|c| c.key.as_deref()) == Some(segment)),
+        _ => None,
+    }
+}
+
+/// Resolve a path to a node index
+pub fn resolve_path(tree: &JsonTree, input: &str) -> Result<usize, String> {
+    let mut node_index = tree.root_index();
+    for segment in parse_path(input)? {
+        node_index = child_by_segment(tree, node_index, &segment)
+            .ok_or_else(|| format!("No \"{}\" in {}", segment, display_pointer(&tree.pointer_to_node(node_index))))?;
+    }
+    Ok(node_index)
+}
+
+fn display_pointer(pointer: &str) -> &str {
+    if pointer.is_empty() { "/" } else { pointer }
+}
+
+/// Completed inputs for the children of the node `input` leads to whose
+/// names start with the partially typed last segment
+pub fn completions(tree: &JsonTree, input: &str) -> Vec<String> {
+    let pointer_syntax = input.starts_with('/');
+    let split = if pointer_syntax {
+        input.rfind('/')
+    } else {
+        input.rfind(['.', '['])
+    };
+    let (base, partial) = match split {
+        Some(position) => (&input[..position], &input[position + 1..]),
+        None if input.starts_with('$') => ("$", &input[1..]),
+        None => ("", input),
+    };
+    let partial = partial.trim_matches(['\'', '"', ']']).to_lowercase();
+
+    let Ok(parent) = resolve_path(tree, base) else {
+        return Vec::new();
+    };
+    let Some(node) = tree.get_node(parent) else {
+        return Vec::new();
+    };
+
+    let is_array = matches!(node.value, JsonValue::Array);
+    node.children.iter()
+        .enumerate()
+        .filter_map(|(i, &child)| {
+            let name = if is_array { i.to_string() } else { tree.get_node(child)?.key.clone()? };
+            if !name.to_lowercase().starts_with(&partial) {
+                return None;
+            }
+            Some(if pointer_syntax {
+                format!("{}/{}", base, escape_token(&name))
+            } else if is_array {
+                format!("{}[{}]", base, name)
+            } else if name.is_empty() || name.contains(['.', '[', ']', '\'', '"', ' ']) {
+                format!("{}['{}']", base, name.replace('\\', "\\\\").replace('\'', "\\'"))
+            } else if base.is_empty() {
+                name
+            } else {
+                format!("{}.{}", base, name)
+            })
+        })
+        .take(MAX_COMPLETIONS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::build_tree;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_path_notations() {
+        let expected = ["users", "0", "name"];
+        assert_eq!(parse_path("/users/0/name").unwrap(), expected);
+        assert_eq!(parse_path("$.users[0].name").unwrap(), expected);
+        assert_eq!(parse_path("users.0.name").unwrap(), expected);
+        assert_eq!(parse_path("users[0]['name']").unwrap(), expected);
+
+        assert_eq!(parse_path("$['a.b'][\"c\\\"d\"]").unwrap(), ["a.b", "c\"d"]);
+        assert_eq!(parse_path("/a~1b/c~0d").unwrap(), ["a/b", "c~d"]);
+        assert!(parse_path("$['open").is_err());
+        assert!(parse_path("$").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_resolve_path() {
+        let tree = build_tree(&json!({"users": [{"name": "a"}, {"name": "b"}], "a.b": 1}));
+        let expected = tree.resolve_pointer("/users/1/name").unwrap();
+        assert_eq!(resolve_path(&tree, "$.users[1].name"), Ok(expected));
+        assert_eq!(resolve_path(&tree, "users.1.name"), Ok(expected));
+        assert_eq!(resolve_path(&tree, "$['a.b']"), tree.resolve_pointer("/a.b").ok_or_else(String::new));
+        assert_eq!(resolve_path(&tree, "users.5"), Err("No \"5\" in /users".to_string()));
+    }
+
+    #[test]
+    fn test_completions() {
+        let tree = build_tree(&json!({"users": [{"name": "a"}, {"name": "b"}], "user id": 1, "count": 2}));
+        assert_eq!(completions(&tree, "us"), ["users", "['user id']"]);
+        assert_eq!(completions(&tree, "$.c"), ["$.count"]);
+        assert_eq!(completions(&tree, "users["), ["users[0]", "users[1]"]);
+        assert_eq!(completions(&tree, "users[1].n"), ["users[1].name"]);
+        assert_eq!(completions(&tree, "/users/0/"), ["/users/0/name"]);
+        assert_eq!(completions(&tree, "/co"), ["/count"]);
+        assert!(completions(&tree, "missing.").is_empty());
+    }
+}
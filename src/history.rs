@@ -0,0 +1,112 @@
+//! Back/forward navigation between visited nodes.
+//!
+//! Like a browser history: each jump adds an entry and drops anything ahead
+//! of the current position. Entries are JSON Pointers so they survive reloads.
+
+/// Most entries kept per document
+pub const MAX_HISTORY: usize = 100;
+
+/// Visited nodes of one document
+#[derive(Debug, Default)]
+pub struct NavHistory {
+    entries: Vec<String>,
+    /// Index of the current entry (meaningless while `entries` is empty)
+    position: usize,
+}
+
+impl NavHistory {
+    /// Record a move from `from` to `to`. `from` is added first if the
+    /// selection got there without being recorded (e.g. by expanding a node).
+    pub fn visit(&mut self, from: Option<String>, to: String) {
+        if let Some(from) = from {
+            self.push(from);
+        }
+        self.push(to);
+    }
+
+    fn push(&mut self, pointer: String) {
+        if self.current() == Some(pointer.as_str()) {
+            return;
+        }
+        if !self.entries.is_empty() {
+            self.entries.truncate(self.position + 1);
+        }
+        self.entries.push(pointer);
+        if self.entries.len() > MAX_HISTORY {
+            self.entries.remove(0);
+        }
+        self.position = self.entries.len() - 1;
+    }
+
+    fn current(&self) -> Option<&str> {
+        self.entries.get(self.position).map(String::as_str)
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        self.position > 0
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        self.position + 1 < self.entries.len()
+    }
+
+    /// Step back and return the entry to show
+    pub fn back(&mut self) -> Option<&str> {
+        if !self.can_go_back() {
+            return None;
+        }
+        self.position -= 1;
+        self.current()
+    }
+
+    /// Step forward and return the entry to show
+    pub fn forward(&mut self) -> Option<&str> {
+        if !self.can_go_forward() {
+            return None;
+        }
+        self.position += 1;
+        self.current()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_back_and_forward() {
+        let mut history = NavHistory::default();
+        assert!(!history.can_go_back());
+
+        history.visit(None, "/a".to_string());
+        history.visit(Some("/a".to_string()), "/b".to_string());
+        // Selection moved to /c without being recorded, then jumped to /d
+        history.visit(Some("/c".to_string()), "/d".to_string());
+
+        assert_eq!(history.back(), Some("/c"));
+        assert_eq!(history.back(), Some("/b"));
+        assert_eq!(history.forward(), Some("/c"));
+
+        // A new jump drops the forward entries
+        history.visit(Some("/c".to_string()), "/e".to_string());
+        assert!(!history.can_go_forward());
+        assert_eq!(history.back(), Some("/c"));
+        assert_eq!(history.back(), Some("/b"));
+        assert_eq!(history.back(), Some("/a"));
+        assert_eq!(history.back(), None);
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut history = NavHistory::default();
+        for i in 0..MAX_HISTORY + 10 {
+            history.visit(None, format!("/{}", i));
+        }
+        let mut steps = 0;
+        while history.back().is_some() {
+            steps += 1;
+        }
+        assert_eq!(steps, MAX_HISTORY - 1);
+        assert_eq!(history.current(), Some("/10"));
+    }
+}
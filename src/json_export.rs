@@ -2,7 +2,10 @@
 //!
 //! Provides functions to convert tree nodes back to JSON strings.
 
-use crate::parser::{JsonTree, JsonValue};
+use crate::config::FormatOptions;
+use crate::parser::{build_tree, JsonTree, JsonValue};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
 
 /// Format a node's value for copying to clipboard
 /// For primitives: just the value
@@ -55,7 +58,7 @@ fn node_to_json_string_internal(tree: &JsonTree, node_index: usize, minified: bo
                         tree.get_node(child_idx).map(|child| {
                             let key = child.key.as_deref().unwrap_or("");
                             let value = node_to_json_string_internal(tree, child_idx, minified);
-                            format!("\"{}\"{}{}", key, kv_sep, value)
+                            format!("\"{}\"{}{}", escape_json_string(key), kv_sep, value)
                         })
                     })
                     .collect();
@@ -67,6 +70,253 @@ fn node_to_json_string_internal(tree: &JsonTree, node_index: usize, minified: bo
     }
 }
 
+/// Convert a node and its children to a pretty-printed JSON string
+pub fn node_to_json_string_formatted(tree: &JsonTree, node_index: usize, options: &FormatOptions) -> String {
+    let mut out = String::new();
+    write_formatted(tree, node_index, 0, options, &mut out);
+    out
+}
+
+/// Pretty-print a `serde_json::Value` (used for patches and other generated documents)
+pub fn value_to_json_string_formatted(value: &Value, options: &FormatOptions) -> String {
+    let tree = build_tree(value);
+    node_to_json_string_formatted(&tree, tree.root_index(), options)
+}
+
+fn write_formatted(tree: &JsonTree, node_index: usize, level: usize, options: &FormatOptions, out: &mut String) {
+    let Some(node) = tree.get_node(node_index) else {
+        return;
+    };
+
+    match &node.value {
+        JsonValue::Null => out.push_str("null"),
+        JsonValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        JsonValue::Number(n) => out.push_str(&number_to_value(*n).to_string()),
+        JsonValue::String(s) => write_escaped_string(s, options.ascii_only, out),
+        JsonValue::Array | JsonValue::Object if node.children.is_empty() => {
+            out.push_str(if matches!(node.value, JsonValue::Array) { "[]" } else { "{}" });
+        }
+        JsonValue::Array => {
+            if options.compact_arrays
+                && let Some(line) = compact_array(tree, &node.children, options)
+            {
+                // Width of what is already on the current line
+                let column = out.rsplit('\n').next().map_or(0, |l| l.chars().count());
+                if column + line.chars().count() <= options.line_width {
+                    out.push_str(&line);
+                    return;
+                }
+            }
+
+            let indent = options.indent_unit();
+            out.push('[');
+            for (i, &child) in node.children.iter().enumerate() {
+                out.push_str(if i > 0 { ",\n" } else { "\n" });
+                out.push_str(&indent.repeat(level + 1));
+                write_formatted(tree, child, level + 1, options, out);
+            }
+            out.push('\n');
+            out.push_str(&indent.repeat(level));
+            out.push(']');
+        }
+        JsonValue::Object => {
+            let mut members: Vec<(&str, usize)> = node.children.iter()
+                .filter_map(|&c| tree.get_node(c).map(|child| (child.key.as_deref().unwrap_or(""), c)))
+                .collect();
+            if options.sort_keys {
+                members.sort_by_key(|&(key, _)| key);
+            }
+
+            let indent = options.indent_unit();
+            out.push('{');
+            for (i, (key, child)) in members.into_iter().enumerate() {
+                out.push_str(if i > 0 { ",\n" } else { "\n" });
+                out.push_str(&indent.repeat(level + 1));
+                write_escaped_string(key, options.ascii_only, out);
+                out.push_str(": ");
+                write_formatted(tree, child, level + 1, options, out);
+            }
+            out.push('\n');
+            out.push_str(&indent.repeat(level));
+            out.push('}');
+        }
+    }
+}
+
+/// Single-line rendering of an array whose elements are all scalars
+fn compact_array(tree: &JsonTree, children: &[usize], options: &FormatOptions) -> Option<String> {
+    let mut line = String::from("[");
+    for (i, &child) in children.iter().enumerate() {
+        if tree.get_node(child)?.is_expandable() {
+            return None;
+        }
+        if i > 0 {
+            line.push_str(", ");
+        }
+        write_formatted(tree, child, 0, options, &mut line);
+        // Give up early on arrays that can never fit
+        if line.len() > options.line_width * 4 {
+            return None;
+        }
+    }
+    line.push(']');
+    Some(line)
+}
+
+/// Convert a node and its children back into a `serde_json::Value`
+pub fn node_to_value(tree: &JsonTree, node_index: usize) -> Value {
+    let Some(node) = tree.get_node(node_index) else {
+        return Value::Null;
+    };
+
+    match &node.value {
+        JsonValue::Null => Value::Null,
+        JsonValue::Bool(b) => Value::Bool(*b),
+        JsonValue::Number(n) => number_to_value(*n),
+        JsonValue::String(s) => Value::String(s.clone()),
+        JsonValue::Array => Value::Array(
+            node.children.iter().map(|&child| node_to_value(tree, child)).collect(),
+        ),
+        JsonValue::Object => {
+            let mut map = Map::new();
+            for &child_idx in &node.children {
+                if let Some(child) = tree.get_node(child_idx) {
+                    let key = child.key.clone().unwrap_or_default();
+                    map.insert(key, node_to_value(tree, child_idx));
+                }
+            }
+            Value::Object(map)
+        }
+    }
+}
+
+/// Numbers are stored as f64; keep integral values as JSON integers
+pub fn number_to_value(n: f64) -> Value {
+    /// Largest integer an f64 represents exactly (2^53)
+    const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+    if n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGER {
+        Value::from(n as i64)
+    } else {
+        serde_json::Number::from_f64(n).map(Value::Number).unwrap_or(Value::Null)
+    }
+}
+
+/// Serialize a node as RFC 8785 (JCS) canonical JSON: no whitespace, object
+/// members sorted by UTF-16 code units, ECMAScript number formatting and
+/// only the escapes JSON requires.
+pub fn node_to_canonical_json(tree: &JsonTree, node_index: usize) -> String {
+    let mut out = String::new();
+    write_canonical(tree, node_index, &mut out);
+    out
+}
+
+/// Hex SHA-256 of a node's canonical JSON, so equal values hash equally
+/// however they were formatted
+pub fn node_sha256(tree: &JsonTree, node_index: usize) -> String {
+    format!("{:x}", Sha256::digest(node_to_canonical_json(tree, node_index).as_bytes()))
+}
+
+fn write_canonical(tree: &JsonTree, node_index: usize, out: &mut String) {
+    let Some(node) = tree.get_node(node_index) else {
+        return;
+    };
+
+    match &node.value {
+        JsonValue::Null => out.push_str("null"),
+        JsonValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        JsonValue::Number(n) => out.push_str(&canonical_number(*n)),
+        JsonValue::String(s) => write_escaped_string(s, false, out),
+        JsonValue::Array => {
+            out.push('[');
+            for (i, &child) in node.children.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(tree, child, out);
+            }
+            out.push(']');
+        }
+        JsonValue::Object => {
+            let mut members: Vec<(&str, usize)> = node.children.iter()
+                .filter_map(|&c| tree.get_node(c).map(|child| (child.key.as_deref().unwrap_or(""), c)))
+                .collect();
+            // UTF-16 order differs from byte order for characters above U+FFFF
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            out.push('{');
+            for (i, (key, child)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_escaped_string(key, false, out);
+                out.push(':');
+                write_canonical(tree, child, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+/// Format a number the way ECMAScript's `Number.prototype.toString` does
+fn canonical_number(n: f64) -> String {
+    if n == 0.0 || !n.is_finite() {
+        // -0 serializes as 0; NaN and infinities cannot come out of a JSON parser
+        return "0".to_string();
+    }
+
+    // Rust's `{:e}` yields the shortest round-trip digits, e.g. "1.2345e6"
+    let scientific = format!("{:e}", n.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    // Decimal point position: value = 0.digits × 10^point
+    let point = exponent.parse::<i32>().unwrap_or(0) + 1;
+
+    let body = if k <= point && point <= 21 {
+        format!("{}{}", digits, "0".repeat((point - k) as usize))
+    } else if 0 < point && point <= 21 {
+        format!("{}.{}", &digits[..point as usize], &digits[point as usize..])
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{}", "0".repeat((-point) as usize), digits)
+    } else {
+        let exponent = point - 1;
+        let sign = if exponent < 0 { '-' } else { '+' };
+        if k == 1 {
+            format!("{}e{}{}", digits, sign, exponent.abs())
+        } else {
+            format!("{}.{}e{}{}", &digits[..1], &digits[1..], sign, exponent.abs())
+        }
+    };
+
+    if n < 0.0 { format!("-{}", body) } else { body }
+}
+
+/// Quote a string escaping only `"`, `\` and control characters, plus every
+/// non-ASCII character (as UTF-16 `\uXXXX` units) when `ascii_only` is set
+fn write_escaped_string(s: &str, ascii_only: bool, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c if ascii_only && !c.is_ascii() => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    out.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
 /// Escape special characters in a JSON string
 fn escape_json_string(s: &str) -> String {
     s.replace('\\', "\\\\")
@@ -248,6 +498,14 @@ mod tests {
         assert!(minified.contains("value"));
     }
 
+    #[test]
+    fn test_node_to_value_round_trip() {
+        let value = json!({"int": 42, "float": 1.5, "neg": -3, "list": [true, null, "x"], "obj": {}});
+        let tree = build_tree(&value);
+
+        assert_eq!(node_to_value(&tree, tree.root_index()), value);
+    }
+
     #[test]
     fn test_empty_object_and_array() {
         let empty_obj = json!({});
@@ -259,4 +517,121 @@ mod tests {
         assert_eq!(node_to_json_string_minified(&obj_tree, obj_tree.root_index()), "{}");
         assert_eq!(node_to_json_string_minified(&arr_tree, arr_tree.root_index()), "[]");
     }
+
+    #[test]
+    fn test_canonical_json_rfc_8785_example() {
+        // Example from RFC 8785 section 3.2.3
+        let input = r#"{
+            "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+            "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+            "literals": [null, true, false]
+        }"#;
+        let tree = build_tree(&serde_json::from_str(input).unwrap());
+
+        assert_eq!(
+            node_to_canonical_json(&tree, tree.root_index()),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        );
+    }
+
+    #[test]
+    fn test_canonical_numbers_parse_exactly() {
+        // Needs serde_json's float_roundtrip; the default parser reads these
+        // one ULP off and the canonical form (and hash) would change
+        let tree = build_tree(&serde_json::from_str("[333333333.33333329, 0.1, 1.7976931348623157e308]").unwrap());
+        assert_eq!(
+            node_to_canonical_json(&tree, tree.root_index()),
+            "[333333333.3333333,0.1,1.7976931348623157e+308]"
+        );
+    }
+
+    #[test]
+    fn test_node_sha256() {
+        let tree = build_tree(&serde_json::from_str(r#"{ "b": null, "a": [1.0, "x"] }"#).unwrap());
+        assert_eq!(node_to_canonical_json(&tree, tree.root_index()), r#"{"a":[1,"x"],"b":null}"#);
+        assert_eq!(node_sha256(&tree, tree.root_index()), "854ef06dc57f5dfed10206344ab2d02e0b6c84b0e19436703a5afd0f1f9f2687");
+    }
+
+    #[test]
+    fn test_canonical_key_order_is_utf16() {
+        // RFC 8785 section 3.2.3 sorting example
+        let value = json!({"\u{20ac}": 1, "\r": 2, "\u{fb33}": 3, "1": 4, "\u{1f600}": 5, "\u{80}": 6, "\u{f6}": 7});
+        let tree = build_tree(&value);
+
+        assert_eq!(
+            node_to_canonical_json(&tree, tree.root_index()),
+            "{\"\\r\":2,\"1\":4,\"\u{80}\":6,\"\u{f6}\":7,\"\u{20ac}\":1,\"\u{1f600}\":5,\"\u{fb33}\":3}"
+        );
+    }
+
+    #[test]
+    fn test_canonical_numbers() {
+        let cases = [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (1e21, "1e+21"),
+            (1e20, "100000000000000000000"),
+            (123456789012345680000.0, "123456789012345680000"),
+            (-1.5, "-1.5"),
+            (0.000001, "0.000001"),
+            (0.0000001, "1e-7"),
+            (9007199254740992.0, "9007199254740992"),
+            (5e-324, "5e-324"),
+            (1.7976931348623157e308, "1.7976931348623157e+308"),
+        ];
+        for (number, expected) in cases {
+            assert_eq!(canonical_number(number), expected, "{}", number);
+        }
+    }
+
+    #[test]
+    fn test_formatted_default_matches_serde_pretty() {
+        let value = json!({"a": [1, 2.5, {"b": null}], "c": {}, "d": [], "e": "x\ny"});
+        let tree = build_tree(&value);
+
+        assert_eq!(
+            node_to_json_string_formatted(&tree, tree.root_index(), &FormatOptions::default()),
+            serde_json::to_string_pretty(&value).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_formatted_options() {
+        let value = json!({"name": "café", "list": [1, 2, 3], "long": ["aaaaaaaaaa", "bbbbbbbbbb", "cccccccccc"]});
+        let tree = build_tree(&value);
+        let options = FormatOptions {
+            use_tabs: true,
+            ascii_only: true,
+            compact_arrays: true,
+            line_width: 30,
+            ..FormatOptions::default()
+        };
+
+        assert_eq!(
+            node_to_json_string_formatted(&tree, tree.root_index(), &options),
+            "{\n\t\"name\": \"caf\\u00e9\",\n\t\"list\": [1, 2, 3],\n\t\"long\": [\n\t\t\"aaaaaaaaaa\",\n\t\t\"bbbbbbbbbb\",\n\t\t\"cccccccccc\"\n\t]\n}"
+        );
+
+        let four = FormatOptions { indent_width: 4, ..FormatOptions::default() };
+        assert_eq!(value_to_json_string_formatted(&json!({"k": [true]}), &four), "{\n    \"k\": [\n        true\n    ]\n}");
+    }
+
+    #[test]
+    fn test_formatted_sort_keys() {
+        // Keys keep their source order unless sorting is asked for
+        let tree = build_tree(&serde_json::from_str(r#"{"b": 2, "a": 1}"#).unwrap());
+        let root = tree.root_index();
+
+        let unsorted = node_to_json_string_formatted(&tree, root, &FormatOptions::default());
+        let sorted = node_to_json_string_formatted(&tree, root, &FormatOptions { sort_keys: true, ..FormatOptions::default() });
+        assert_eq!(unsorted, "{\n  \"b\": 2,\n  \"a\": 1\n}");
+        assert_eq!(sorted, "{\n  \"a\": 1,\n  \"b\": 2\n}");
+    }
+
+    #[test]
+    fn test_ascii_only_escapes_surrogate_pairs() {
+        let tree = build_tree(&json!("😀"));
+        let options = FormatOptions { ascii_only: true, ..FormatOptions::default() };
+        assert_eq!(node_to_json_string_formatted(&tree, tree.root_index(), &options), "\"\\ud83d\\ude00\"");
+    }
 }
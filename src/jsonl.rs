@@ -0,0 +1,155 @@
+//! JSON Lines (NDJSON) documents and follow mode.
+//!
+//! A JSON Lines file is shown as an array with one element per line. In
+//! follow mode only the bytes appended since the last read are parsed, like
+//! `tail -f`; a line that is still being written stays buffered until its
+//! newline arrives.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+/// Whether a file should be read as JSON Lines, judged by its extension
+pub fn is_json_lines(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| matches!(ext.as_str(), "jsonl" | "ndjson" | "ldjson"))
+}
+
+/// Parse every line of a JSON Lines document, skipping blank lines.
+///
+/// Returns the values and the number of bytes consumed. With `following`, an
+/// unterminated last line is left out even if it parses, since the writer may
+/// not be done with it (`12` may become `12345`); follow mode picks it up once
+/// its newline arrives. Otherwise it is parsed like any other line. Errors carry the 1-based line number and the line itself.
+pub fn parse_lines(contents: &str, following: bool) -> Result<(Vec<Value>, usize), (usize, String, serde_json::Error)> {
+    let mut values = Vec::new();
+    let mut consumed = 0;
+    for (number, line) in contents.split_inclusive('\n').enumerate() {
+        let terminated = line.ends_with('\n');
+        if following && !terminated {
+            break;
+        }
+        let text = line.trim_end_matches(['\n', '\r']);
+        if !text.trim().is_empty() {
+            match serde_json::from_str(text) {
+                Ok(value) => values.push(value),
+                Err(e) => return Err((number + 1, text.to_string(), e)),
+            }
+        }
+        consumed += line.len();
+    }
+    Ok((values, consumed))
+}
+
+/// What a follow-mode read found
+#[derive(Debug)]
+pub enum FollowUpdate {
+    /// Newly completed lines; lines that failed to parse are only counted
+    Appended { values: Vec<Value>, invalid: usize },
+    /// The file got shorter (truncated or rotated) and must be reloaded
+    Truncated,
+}
+
+/// Reads what was appended to a JSON Lines file since the last read
+#[derive(Debug)]
+pub struct LogFollower {
+    path: PathBuf,
+    /// Bytes of the file read so far
+    offset: u64,
+    /// Start of a line whose newline hasn't arrived yet
+    partial: Vec<u8>,
+}
+
+impl LogFollower {
+    /// Continue reading `path` from byte `offset`
+    pub fn new(path: PathBuf, offset: u64) -> Self {
+        LogFollower { path, offset, partial: Vec::new() }
+    }
+
+    /// Read and parse everything appended since the last call
+    pub fn read_new(&mut self) -> std::io::Result<FollowUpdate> {
+        let mut file = File::open(&self.path)?;
+        if file.metadata()?.len() < self.offset {
+            return Ok(FollowUpdate::Truncated);
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        self.offset += bytes.len() as u64;
+
+        let (values, invalid) = self.push_bytes(&bytes);
+        Ok(FollowUpdate::Appended { values, invalid })
+    }
+
+    /// Split buffered and new bytes into complete lines and parse them
+    fn push_bytes(&mut self, bytes: &[u8]) -> (Vec<Value>, usize) {
+        self.partial.extend_from_slice(bytes);
+        let Some(last_newline) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return (Vec::new(), 0);
+        };
+        let rest = self.partial.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+
+        let mut values = Vec::new();
+        let mut invalid = 0;
+        for line in complete.split(|&b| b == b'\n') {
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            match serde_json::from_slice(line) {
+                Ok(value) => values.push(value),
+                Err(_) => invalid += 1,
+            }
+        }
+        (values, invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_lines() {
+        let (values, consumed) = parse_lines("{\"a\":1}\r\n\n[2]\n{\"par", true).unwrap();
+        assert_eq!(values, [json!({"a": 1}), json!([2])]);
+        assert_eq!(consumed, 14);
+
+        // A number cut off mid-write parses, but must wait for its newline
+        let (values, consumed) = parse_lines("1\n12", true).unwrap();
+        assert_eq!(values, [json!(1)]);
+        assert_eq!(consumed, 2);
+
+        // Outside follow mode a broken last line is an error too
+        let (line, text, _) = parse_lines("{\"a\":1}\r\n\n[2]\n{\"par", false).unwrap_err();
+        assert_eq!((line, text.as_str()), (4, "{\"par"));
+
+        // A complete last line doesn't need a newline
+        let (values, consumed) = parse_lines("1\n2", false).unwrap();
+        assert_eq!(values, [json!(1), json!(2)]);
+        assert_eq!(consumed, 3);
+
+        let (line, text, _) = parse_lines("1\n{oops}\n3\n", true).unwrap_err();
+        assert_eq!((line, text.as_str()), (2, "{oops}"));
+    }
+
+    #[test]
+    fn test_follower_buffers_partial_lines() {
+        let mut follower = LogFollower::new(PathBuf::new(), 0);
+
+        let (values, invalid) = follower.push_bytes(b"{\"n\":1}\n{\"n\":");
+        assert_eq!(values, [json!({"n": 1})]);
+        assert_eq!(invalid, 0);
+
+        let (values, invalid) = follower.push_bytes(b"2}\nnot json\n\n");
+        assert_eq!(values, [json!({"n": 2})]);
+        assert_eq!(invalid, 1);
+
+        assert_eq!(follower.push_bytes(b"3").0.len(), 0);
+        assert_eq!(follower.push_bytes(b"\n").0, [json!(3)]);
+    }
+}
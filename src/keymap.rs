@@ -0,0 +1,536 @@
+//! Keyboard shortcuts as named commands.
+//!
+//! Every shortcut runs a [`Command`]. The defaults are built in; entries in
+//! ~/.unfold/keybindings.json replace the keys of the commands they name:
+//!
+//! ```json
+//! { "copy_value": "cmd+k", "next_result": ["enter", "f3"], "toggle_theme": [] }
+//! ```
+//!
+//! `cmd` is Command on macOS and Ctrl elsewhere. Problems in the file
+//! (unknown commands, bad keys, two commands on one key) are collected at
+//! load time and shown in the keyboard shortcuts overlay, together with keys
+//! that are taken by the fixed shortcuts (Cmd+1–9 and the vim keys).
+
+use iced::keyboard::{Key, Modifiers, key::Named};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::message::Message;
+use crate::tree_nav::TreeMove;
+
+/// Groups of commands in the shortcuts overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    File,
+    Edit,
+    Navigate,
+    Search,
+    View,
+}
+
+impl Section {
+    pub const ALL: [Section; 5] = [Section::File, Section::Edit, Section::Navigate, Section::Search, Section::View];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Section::File => "File",
+            Section::Edit => "Edit",
+            Section::Navigate => "Navigate",
+            Section::Search => "Search",
+            Section::View => "View",
+        }
+    }
+}
+
+/// An action that can be bound to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    OpenFile,
+    OpenInNewTab,
+    CloseTab,
+    NextTab,
+    PreviousTab,
+    CompareWithFile,
+    SaveAs,
+    ReloadFile,
+    OpenExternal,
+    Quit,
+    CopyValue,
+    CopyKey,
+    CopyPath,
+    ToggleBookmark,
+    NextBookmark,
+    PreviousBookmark,
+    Back,
+    Forward,
+    GoToPath,
+    CompletePath,
+    SelectPrevious,
+    SelectNext,
+    CollapseOrParent,
+    ExpandOrChild,
+    SelectFirst,
+    SelectLast,
+    PageUp,
+    PageDown,
+    ExpandAll,
+    FocusSearch,
+    SearchInSelection,
+    NextResult,
+    PreviousResult,
+    Dismiss,
+    ToggleTheme,
+    BookmarksPanel,
+    Palette,
+    Help,
+}
+
+impl Command {
+    pub const ALL: [Command; 38] = [
+        Command::OpenFile, Command::OpenInNewTab, Command::CloseTab, Command::NextTab,
+        Command::PreviousTab, Command::CompareWithFile, Command::SaveAs, Command::ReloadFile,
+        Command::OpenExternal, Command::Quit, Command::CopyValue, Command::CopyKey,
+        Command::CopyPath, Command::ToggleBookmark, Command::NextBookmark, Command::PreviousBookmark,
+        Command::Back, Command::Forward, Command::GoToPath, Command::CompletePath,
+        Command::SelectPrevious, Command::SelectNext, Command::CollapseOrParent, Command::ExpandOrChild,
+        Command::SelectFirst, Command::SelectLast, Command::PageUp, Command::PageDown,
+        Command::ExpandAll, Command::FocusSearch, Command::SearchInSelection, Command::NextResult,
+        Command::PreviousResult, Command::Dismiss, Command::ToggleTheme, Command::BookmarksPanel,
+        Command::Palette, Command::Help,
+    ];
+
+    /// Name in keybindings.json, section, description and default keys
+    fn info(self) -> (&'static str, Section, &'static str, &'static [&'static str]) {
+        match self {
+            Command::OpenFile => ("open_file", Section::File, "Open file", &["cmd+o"]),
+            Command::OpenInNewTab => ("open_in_new_tab", Section::File, "Open in new tab", &["cmd+n"]),
+            Command::CloseTab => ("close_tab", Section::File, "Close tab", &["cmd+w"]),
+            Command::NextTab => ("next_tab", Section::File, "Next tab", &["ctrl+tab"]),
+            Command::PreviousTab => ("previous_tab", Section::File, "Previous tab", &["ctrl+shift+tab"]),
+            Command::CompareWithFile => ("compare_with_file", Section::File, "Compare with file", &["cmd+shift+d"]),
+            Command::SaveAs => ("save_as", Section::File, "Save document as", &["cmd+shift+s"]),
+            Command::ReloadFile => ("reload_file", Section::File, "Reload file", &["cmd+r"]),
+            Command::OpenExternal => ("open_external", Section::File, "Open in external editor", &["cmd+shift+e"]),
+            Command::Quit => ("quit", Section::File, "Quit", &["cmd+q"]),
+            Command::CopyValue => ("copy_value", Section::Edit, "Copy selected value", &["cmd+c"]),
+            Command::CopyKey => ("copy_key", Section::Edit, "Copy key name", &["cmd+shift+c"]),
+            Command::CopyPath => ("copy_path", Section::Edit, "Copy node path", &["cmd+alt+c"]),
+            Command::ToggleBookmark => ("toggle_bookmark", Section::Edit, "Toggle bookmark", &["cmd+b"]),
+            Command::NextBookmark => ("next_bookmark", Section::Edit, "Next bookmark", &["f2"]),
+            Command::PreviousBookmark => ("previous_bookmark", Section::Edit, "Previous bookmark", &["shift+f2"]),
+            Command::Back => ("back", Section::Navigate, "Back", &["cmd+[", "alt+left"]),
+            Command::Forward => ("forward", Section::Navigate, "Forward", &["cmd+]", "alt+right"]),
+            Command::GoToPath => ("go_to_path", Section::Navigate, "Go to path", &["cmd+l"]),
+            Command::CompletePath => ("complete_path", Section::Navigate, "Take the first suggestion in Go to path", &["tab"]),
+            Command::SelectPrevious => ("select_previous", Section::Navigate, "Previous row", &["up"]),
+            Command::SelectNext => ("select_next", Section::Navigate, "Next row", &["down"]),
+            Command::CollapseOrParent => ("collapse", Section::Navigate, "Collapse, or go to parent", &["left"]),
+            Command::ExpandOrChild => ("expand", Section::Navigate, "Expand, or go to first child", &["right"]),
+            Command::SelectFirst => ("select_first", Section::Navigate, "First row", &["home"]),
+            Command::SelectLast => ("select_last", Section::Navigate, "Last row", &["end"]),
+            Command::PageUp => ("page_up", Section::Navigate, "Page up", &["pageup"]),
+            Command::PageDown => ("page_down", Section::Navigate, "Page down", &["pagedown"]),
+            Command::ExpandAll => ("expand_all", Section::Navigate, "Expand all children", &["*"]),
+            Command::FocusSearch => ("focus_search", Section::Search, "Focus search", &["cmd+f"]),
+            Command::SearchInSelection => ("search_in_selection", Section::Search, "Search in selected node", &["cmd+shift+f"]),
+            Command::NextResult => ("next_result", Section::Search, "Next result", &["enter", "cmd+g"]),
+            Command::PreviousResult => ("previous_result", Section::Search, "Previous result", &["shift+enter", "cmd+shift+g"]),
+            Command::Dismiss => ("dismiss", Section::Search, "Close dialog, or clear search", &["escape"]),
+            Command::ToggleTheme => ("toggle_theme", Section::View, "Toggle theme", &["cmd+t"]),
+            Command::BookmarksPanel => ("bookmarks_panel", Section::View, "Bookmarks sidebar", &["cmd+shift+b"]),
+            Command::Palette => ("command_palette", Section::View, "Command palette", &["cmd+shift+p"]),
+            Command::Help => ("help", Section::View, "Toggle this help", &["cmd+/", "cmd+?"]),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        self.info().0
+    }
+
+    pub fn section(self) -> Section {
+        self.info().1
+    }
+
+    pub fn description(self) -> &'static str {
+        self.info().2
+    }
+
+    /// The message that runs the command
+    pub fn message(self) -> Message {
+        match self {
+            Command::OpenFile => Message::OpenFileDialog,
+            Command::OpenInNewTab => Message::OpenFileInNewTab,
+            Command::CloseTab => Message::CloseActiveTab,
+            Command::NextTab => Message::CycleTab(true),
+            Command::PreviousTab => Message::CycleTab(false),
+            Command::CompareWithFile => Message::CompareWithFile,
+            Command::SaveAs => Message::SaveDocumentAs,
+            Command::ReloadFile => Message::ReloadFile,
+            Command::OpenExternal => Message::OpenInExternalEditor,
+            Command::Quit => Message::Quit,
+            Command::CopyValue => Message::CopySelectedValue,
+            Command::CopyKey => Message::CopySelectedName,
+            Command::CopyPath => Message::CopySelectedPath,
+            Command::ToggleBookmark => Message::ToggleBookmark,
+            Command::NextBookmark => Message::NextBookmark,
+            Command::PreviousBookmark => Message::PrevBookmark,
+            Command::Back => Message::NavigateBack,
+            Command::Forward => Message::NavigateForward,
+            Command::GoToPath => Message::ToggleGotoPath,
+            Command::CompletePath => Message::CompleteGotoPath,
+            Command::SelectPrevious => Message::MoveSelection(TreeMove::Up),
+            Command::SelectNext => Message::MoveSelection(TreeMove::Down),
+            Command::CollapseOrParent => Message::MoveSelection(TreeMove::Left),
+            Command::ExpandOrChild => Message::MoveSelection(TreeMove::Right),
+            Command::SelectFirst => Message::MoveSelection(TreeMove::First),
+            Command::SelectLast => Message::MoveSelection(TreeMove::Last),
+            Command::PageUp => Message::MoveSelection(TreeMove::PageUp),
+            Command::PageDown => Message::MoveSelection(TreeMove::PageDown),
+            Command::ExpandAll => Message::ExpandAllChildren,
+            Command::FocusSearch => Message::FocusSearch,
+            Command::SearchInSelection => Message::SearchInSelection,
+            Command::NextResult => Message::SearchNext,
+            Command::PreviousResult => Message::SearchPrev,
+            Command::Dismiss => Message::Dismiss,
+            Command::ToggleTheme => Message::ToggleTheme,
+            Command::BookmarksPanel => Message::ToggleBookmarksPanel,
+            Command::Palette => Message::ToggleCommandPalette,
+            Command::Help => Message::ToggleHelp,
+        }
+    }
+}
+
+/// Named keys that can be bound, by their name in keybindings.json
+const NAMED_KEYS: [(&str, Named); 26] = [
+    ("up", Named::ArrowUp), ("down", Named::ArrowDown), ("left", Named::ArrowLeft), ("right", Named::ArrowRight),
+    ("home", Named::Home), ("end", Named::End), ("pageup", Named::PageUp), ("pagedown", Named::PageDown),
+    ("enter", Named::Enter), ("tab", Named::Tab), ("space", Named::Space), ("escape", Named::Escape),
+    ("backspace", Named::Backspace), ("delete", Named::Delete),
+    ("f1", Named::F1), ("f2", Named::F2), ("f3", Named::F3), ("f4", Named::F4), ("f5", Named::F5),
+    ("f6", Named::F6), ("f7", Named::F7), ("f8", Named::F8), ("f9", Named::F9), ("f10", Named::F10),
+    ("f11", Named::F11), ("f12", Named::F12),
+];
+
+/// A key with modifiers, e.g. `cmd+shift+c`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    /// A single character, or one of [`NAMED_KEYS`]
+    pub key: String,
+    /// Command on macOS, Ctrl elsewhere (either one matches)
+    pub cmd: bool,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    /// Parse `cmd+shift+c`, `alt+left`, `f2`, `*`, ...
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim().to_lowercase();
+        let (modifiers, key) = match text.strip_suffix("++") {
+            Some(modifiers) => (modifiers, "+"),
+            None => text.rsplit_once('+').unwrap_or(("", text.as_str())),
+        };
+
+        let mut binding = KeyBinding { key: String::new(), cmd: false, ctrl: false, shift: false, alt: false };
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            match modifier {
+                "cmd" | "command" => binding.cmd = true,
+                "ctrl" | "control" => binding.ctrl = true,
+                "shift" => binding.shift = true,
+                "alt" | "option" => binding.alt = true,
+                _ => return Err(format!("Unknown modifier \"{}\" in \"{}\"", modifier, text)),
+            }
+        }
+
+        let key = match key {
+            "arrowup" => "up",
+            "arrowdown" => "down",
+            "arrowleft" => "left",
+            "arrowright" => "right",
+            "return" => "enter",
+            "pgup" => "pageup",
+            "pgdn" => "pagedown",
+            "esc" => "escape",
+            key => key,
+        };
+        if key.chars().count() != 1 && !NAMED_KEYS.iter().any(|(name, _)| *name == key) {
+            return Err(format!("Unknown key \"{}\" in \"{}\"", key, text));
+        }
+        binding.key = key.to_string();
+        Ok(binding)
+    }
+
+    /// Whether a key press triggers this binding
+    pub fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        let cmd_or_ctrl = modifiers.command() || modifiers.control();
+        let name = match key.as_ref() {
+            Key::Character(c) => c.to_lowercase(),
+            Key::Named(named) => match NAMED_KEYS.iter().find(|(_, n)| *n == named) {
+                Some((name, _)) => name.to_string(),
+                None => return false,
+            },
+            Key::Unidentified => return false,
+        };
+        name == self.key
+            && modifiers.shift() == self.shift
+            && modifiers.alt() == self.alt
+            && if self.ctrl { modifiers.control() } else if self.cmd { cmd_or_ctrl } else { !cmd_or_ctrl }
+    }
+
+    /// Whether both bindings can be triggered by the same key press
+    fn overlaps(&self, other: &KeyBinding) -> bool {
+        self.key == other.key
+            && self.shift == other.shift
+            && self.alt == other.alt
+            && (self.cmd || self.ctrl) == (other.cmd || other.ctrl)
+    }
+
+    /// How the binding is shown in the shortcuts overlay (`⇧⌘C` / `Shift+Ctrl+C`)
+    pub fn label(&self) -> String {
+        let key = match self.key.as_str() {
+            "up" => "↑".to_string(),
+            "down" => "↓".to_string(),
+            "left" => "←".to_string(),
+            "right" => "→".to_string(),
+            "pageup" => "PgUp".to_string(),
+            "pagedown" => "PgDn".to_string(),
+            name if name.chars().count() == 1 => name.to_uppercase(),
+            name => {
+                let mut chars = name.chars();
+                chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+            }
+        };
+        if cfg!(target_os = "macos") {
+            let mut label = String::new();
+            for (on, symbol) in [(self.ctrl, "⌃"), (self.alt, "⌥"), (self.shift, "⇧"), (self.cmd, "⌘")] {
+                if on {
+                    label.push_str(symbol);
+                }
+            }
+            label + &key
+        } else {
+            let mut label = String::new();
+            for (on, name) in [(self.ctrl || self.cmd, "Ctrl+"), (self.alt, "Alt+"), (self.shift, "Shift+")] {
+                if on {
+                    label.push_str(name);
+                }
+            }
+            label + &key
+        }
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (on, name) in [(self.cmd, "cmd+"), (self.ctrl, "ctrl+"), (self.alt, "alt+"), (self.shift, "shift+")] {
+            if on {
+                f.write_str(name)?;
+            }
+        }
+        f.write_str(&self.key)
+    }
+}
+
+/// One key or several for a command in keybindings.json
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// The active shortcuts
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, Command)>,
+    /// Problems found in keybindings.json
+    pub problems: Vec<String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::with_overrides(BTreeMap::new())
+    }
+}
+
+impl Keymap {
+    /// Get the keybindings file path (~/.unfold/keybindings.json)
+    pub fn keybindings_path() -> Option<PathBuf> {
+        Config::config_dir().map(|dir| dir.join("keybindings.json"))
+    }
+
+    /// The defaults with the user's overrides applied
+    pub fn load() -> Self {
+        let Some(contents) = Self::keybindings_path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return Keymap::default();
+        };
+        match serde_json::from_str(&contents) {
+            Ok(overrides) => Keymap::with_overrides(overrides),
+            Err(e) => Keymap {
+                problems: vec![format!("keybindings.json could not be read: {}", e)],
+                ..Keymap::default()
+            },
+        }
+    }
+
+    /// The defaults with `overrides` (command name → keys) applied. When two
+    /// commands share a key, an overridden command wins over a default one,
+    /// otherwise the one listed first.
+    fn with_overrides(overrides: BTreeMap<String, KeyList>) -> Self {
+        let mut problems = Vec::new();
+        let mut keys: Vec<(Command, Vec<KeyBinding>, bool)> = Command::ALL.into_iter()
+            .map(|command| {
+                let defaults = command.info().3.iter().filter_map(|key| KeyBinding::parse(key).ok()).collect();
+                (command, defaults, false)
+            })
+            .collect();
+
+        for (name, list) in overrides {
+            let Some(entry) = keys.iter_mut().find(|(command, _, _)| command.name() == name) else {
+                problems.push(format!("Unknown command \"{}\"", name));
+                continue;
+            };
+            let list = match list {
+                KeyList::One(key) => vec![key],
+                KeyList::Many(keys) => keys,
+            };
+            entry.1 = list.iter()
+                .filter_map(|key| KeyBinding::parse(key).map_err(|e| problems.push(format!("{}: {}", name, e))).ok())
+                .collect();
+            entry.2 = true;
+        }
+
+        let mut bindings: Vec<(KeyBinding, Command)> = Vec::new();
+        for overridden in [true, false] {
+            for (command, command_keys, _) in keys.iter().filter(|entry| entry.2 == overridden) {
+                for binding in command_keys {
+                    match bindings.iter().find(|(existing, _)| existing.overlaps(binding)) {
+                        Some((_, winner)) => problems.push(format!(
+                            "{} is bound to both {} and {}; using {}",
+                            binding, winner.name(), command.name(), winner.name(),
+                        )),
+                        None => bindings.push((binding.clone(), *command)),
+                    }
+                }
+            }
+        }
+
+        Keymap { bindings, problems }
+    }
+
+    /// The command a key press runs
+    pub fn lookup(&self, key: &Key, modifiers: Modifiers) -> Option<Command> {
+        self.bindings.iter()
+            .find(|(binding, _)| binding.matches(key, modifiers))
+            .map(|(_, command)| *command)
+    }
+
+    /// The keys bound to a command, in order
+    pub fn keys_for(&self, command: Command) -> impl Iterator<Item = &KeyBinding> {
+        self.bindings.iter().filter(move |(_, c)| *c == command).map(|(binding, _)| binding)
+    }
+
+    /// Bound keys that never reach their command because a fixed shortcut
+    /// handles them first: Cmd+1–9 pick a tab, and with vim keys on, the
+    /// keys of `h` `j` `k` `l`, `gg` / `G`, `zc` / `zo` move the selection
+    pub fn shadowed(&self, vim_keys: bool) -> Vec<String> {
+        let mut fixed: Vec<(String, &str)> = (1..=9).map(|digit| (format!("cmd+{}", digit), "selecting a tab")).collect();
+        if vim_keys {
+            for key in ["h", "j", "k", "l", "g", "shift+g", "z"] {
+                fixed.push((key.to_string(), "vim keys"));
+            }
+        }
+
+        let mut problems = Vec::new();
+        for (key, used_for) in fixed {
+            let Ok(fixed_binding) = KeyBinding::parse(&key) else {
+                continue;
+            };
+            for (binding, command) in self.bindings.iter().filter(|(binding, _)| binding.overlaps(&fixed_binding)) {
+                problems.push(format!("{} is bound to {} but is used for {}", binding, command.name(), used_for));
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides(json: &str) -> BTreeMap<String, KeyList> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_parse_bindings() {
+        let binding = KeyBinding::parse("Cmd+Shift+C").unwrap();
+        assert!(binding.cmd && binding.shift && !binding.alt);
+        assert_eq!(binding.key, "c");
+        assert_eq!(binding.to_string(), "cmd+shift+c");
+
+        assert_eq!(KeyBinding::parse("alt+ArrowLeft").unwrap().key, "left");
+        assert_eq!(KeyBinding::parse("cmd++").unwrap().key, "+");
+        assert!(KeyBinding::parse("hyper+c").is_err());
+        assert!(KeyBinding::parse("cmd+nope").is_err());
+    }
+
+    #[test]
+    fn test_matches_key_presses() {
+        let copy = KeyBinding::parse("cmd+c").unwrap();
+        let c = Key::Character("c".into());
+        assert!(copy.matches(&c, Modifiers::COMMAND));
+        assert!(!copy.matches(&c, Modifiers::COMMAND | Modifiers::SHIFT));
+        assert!(!copy.matches(&c, Modifiers::empty()));
+
+        let down = KeyBinding::parse("down").unwrap();
+        assert!(down.matches(&Key::Named(Named::ArrowDown), Modifiers::empty()));
+        assert!(!down.matches(&Key::Named(Named::ArrowDown), Modifiers::ALT));
+    }
+
+    #[test]
+    fn test_defaults_are_unique_and_complete() {
+        let keymap = Keymap::default();
+        assert!(keymap.problems.is_empty(), "{:?}", keymap.problems);
+        for command in Command::ALL {
+            assert_eq!(Command::ALL.iter().filter(|c| c.name() == command.name()).count(), 1);
+            assert!(keymap.keys_for(command).next().is_some(), "{} has no key", command.name());
+        }
+        assert_eq!(keymap.lookup(&Key::Named(Named::F2), Modifiers::SHIFT), Some(Command::PreviousBookmark));
+        assert_eq!(keymap.lookup(&Key::Character("?".into()), Modifiers::COMMAND), Some(Command::Help));
+        assert_eq!(keymap.lookup(&Key::Named(Named::Escape), Modifiers::empty()), Some(Command::Dismiss));
+        assert!(keymap.shadowed(true).is_empty(), "{:?}", keymap.shadowed(true));
+    }
+
+    #[test]
+    fn test_shadowed_by_fixed_keys() {
+        let keymap = Keymap::with_overrides(overrides(r#"{"copy_value": "j", "copy_key": "cmd+2"}"#));
+        assert_eq!(keymap.shadowed(false), ["cmd+2 is bound to copy_key but is used for selecting a tab"]);
+        assert_eq!(keymap.shadowed(true).len(), 2);
+    }
+
+    #[test]
+    fn test_overrides_and_conflicts() {
+        let keymap = Keymap::with_overrides(overrides(
+            r#"{"go_to_path": "cmd+b", "toggle_theme": [], "help": ["f1", "cmd+/"], "nonsense": "x", "quit": "cmd+bogus"}"#,
+        ));
+        let b = Key::Character("b".into());
+
+        // The overridden command takes the key from the default one
+        assert_eq!(keymap.lookup(&b, Modifiers::COMMAND), Some(Command::GoToPath));
+        assert_eq!(keymap.keys_for(Command::ToggleBookmark).count(), 0);
+        assert_eq!(keymap.keys_for(Command::ToggleTheme).count(), 0);
+        assert_eq!(keymap.lookup(&Key::Named(Named::F1), Modifiers::empty()), Some(Command::Help));
+
+        assert_eq!(keymap.problems.len(), 3);
+        assert!(keymap.problems.iter().any(|p| p == "cmd+b is bound to both go_to_path and toggle_bookmark; using go_to_path"));
+        assert!(keymap.problems.iter().any(|p| p == "Unknown command \"nonsense\""));
+        assert!(keymap.problems.iter().any(|p| p.starts_with("quit: Unknown key")));
+    }
+}
@@ -12,7 +12,6 @@ use std::sync::{Mutex, OnceLock};
 const NS_APPLICATION_DELEGATE_REPLY_SUCCESS: usize = 0;
 const NS_APPLICATION_DELEGATE_REPLY_FAILURE: usize = 2;
 
-static APP_READY: AtomicBool = AtomicBool::new(false);
 static OPEN_FILE_HANDLER_INSTALLED: AtomicBool = AtomicBool::new(false);
 static PENDING_FILES: OnceLock<Mutex<Vec<PathBuf>>> = OnceLock::new();
 
@@ -43,10 +42,6 @@ pub(crate) fn take_pending_open_files() -> Vec<PathBuf> {
     std::mem::take(&mut *pending)
 }
 
-pub(crate) fn mark_app_ready() {
-    APP_READY.store(true, Ordering::SeqCst);
-}
-
 unsafe fn winit_application_delegate_class() -> Option<&'static Class> {
     unsafe { current_application_delegate_class() }
         .or_else(|| Class::get("WinitApplicationDelegate"))
@@ -174,17 +169,12 @@ fn route_opened_paths(paths: Vec<PathBuf>) {
         return;
     }
 
-    if APP_READY.load(Ordering::SeqCst) {
-        for path in paths {
-            crate::spawn_unfold_process(Some(path));
-        }
-    } else {
-        let mut pending = pending_files()
-            .lock()
-            .expect("pending macOS open-file queue poisoned");
+    // Picked up at startup, or by the app's polling subscription and opened as tabs
+    let mut pending = pending_files()
+        .lock()
+        .expect("pending macOS open-file queue poisoned");
 
-        pending.extend(paths);
-    }
+    pending.extend(paths);
 }
 
 unsafe fn extract_paths_from_url_array(urls: *mut Object) -> Vec<PathBuf> {
@@ -14,21 +14,41 @@ mod parse_error;
 mod search;
 mod json_export;
 mod config;
+mod diff;
+mod patch;
+mod schema;
+mod codegen;
+mod stats;
+mod table;
+mod csv_export;
+mod formats;
+mod jsonl;
+mod watcher;
+mod document;
+mod bookmarks;
+mod history;
+mod breadcrumb;
+mod goto_path;
+mod tree_nav;
+mod keymap;
+mod palette;
+mod session;
 #[cfg(target_os = "macos")]
 mod macos_open;
 
-use iced::widget::{button, column, container, mouse_area, row, scrollable, stack, text, text_input, Space};
+use iced::widget::{button, checkbox, column, container, mouse_area, row, scrollable, stack, text, text_input, tooltip, Space};
 use iced::{Element, Font, Length, Center, Fill, Color, Size, Task, window, Border, Shadow, Subscription, clipboard, Theme, event, Event};
 use iced::border::Radius;
 use iced::advanced::widget::{Id as WidgetId, operate};
 use iced::advanced::widget::operation::scrollable::{scroll_to, AbsoluteOffset};
 use iced::advanced::widget::operation::focusable;
 use iced::keyboard::{self, Key, Modifiers, key::Named};
+use iced::mouse::{self, Interaction};
 use iced::widget::button::Status as ButtonStatus;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use std::time::{Duration, Instant};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
 use std::env;
 use std::process::Command;
 
@@ -40,7 +60,25 @@ use update_check::{UpdateCheckState, fetch_latest_release};
 use flat_row::{FlatRow, ValueType, ROW_HEIGHT, BUFFER_ROWS};
 use parse_error::ParseError;
 use parser::{JsonTree, JsonValue};
-use config::Config;
+use config::{Config, FormatOptions};
+use goto_path::GotoPathDialog;
+use tree_nav::{NavAction, TreeMove};
+use keymap::{Keymap, Section};
+use palette::CommandPalette;
+use search::SearchFilter;
+use diff::{ArrayMatching, CompareSession, DiffKind};
+use patch::{PatchFormat, PatchPreview};
+use schema::SchemaSession;
+use codegen::TypeLanguage;
+use stats::{DocumentStats, HeavySort};
+use watcher::ViewSnapshot;
+use document::Document;
+use bookmarks::Bookmark;
+use session::{Session, SessionTab};
+use jsonl::{FollowUpdate, LogFollower};
+use table::TableView;
+use csv_export::{CsvExportDialog, Delimiter};
+use formats::TextFormat;
 
 /// Install the CLI tool by creating a symlink in /usr/local/bin
 /// Uses osascript on macOS to prompt for admin privileges
@@ -120,10 +158,11 @@ fn install_cli_tool() -> Result<String, String> {
 fn print_help() {
     println!("Unfold - A high-performance JSON viewer\n");
     println!("USAGE:");
-    println!("    unfold [FILE]\n");
+    println!("    unfold [OPTIONS] [FILE]...\n");
     println!("ARGS:");
-    println!("    <FILE>    JSON file to open (optional)\n");
+    println!("    <FILE>...    JSON files to open, one tab each (optional)\n");
     println!("OPTIONS:");
+    println!("    --path <PATH>    Select a node in the first file on open (e.g. users[0].name or /users/0/name)");
     println!("    -h, --help       Print help information");
     println!("    -V, --version    Print version information");
 }
@@ -133,25 +172,39 @@ fn print_version() {
     println!("unfold {}", env!("CARGO_PKG_VERSION"));
 }
 
-pub(crate) fn spawn_unfold_process(file_path: Option<PathBuf>) {
-    if let Ok(exe_path) = env::current_exe() {
-        let mut cmd = Command::new(exe_path);
-        if let Some(path) = file_path {
-            cmd.arg(path);
+/// Every positional argument (skipping `--path` and its value), one per tab
+fn cli_file_arguments(args: &[String]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        if arg == "--path" {
+            rest.next();
+        } else if !arg.starts_with('-') {
+            files.push(PathBuf::from(arg));
         }
-        let _ = cmd.spawn();
     }
+    files
 }
 
-fn cli_file_argument(args: &[String]) -> Option<PathBuf> {
-    args.get(1).map(PathBuf::from)
+/// The node to select from `--path <PATH>` or `--path=<PATH>`
+fn cli_path_argument(args: &[String]) -> Option<String> {
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        if arg == "--path" {
+            return rest.next().cloned();
+        }
+        if let Some(path) = arg.strip_prefix("--path=") {
+            return Some(path.to_string());
+        }
+    }
+    None
 }
 
 #[cfg(target_os = "macos")]
 fn initial_open_paths(args: &[String]) -> Vec<PathBuf> {
     let pending_paths = macos_open::take_pending_open_files();
     if pending_paths.is_empty() {
-        cli_file_argument(args).into_iter().collect()
+        cli_file_arguments(args)
     } else {
         pending_paths
     }
@@ -159,7 +212,71 @@ fn initial_open_paths(args: &[String]) -> Vec<PathBuf> {
 
 #[cfg(not(target_os = "macos"))]
 fn initial_open_paths(args: &[String]) -> Vec<PathBuf> {
-    cli_file_argument(args).into_iter().collect()
+    cli_file_arguments(args)
+}
+
+/// Read and parse a JSON file into a tree
+fn load_tree(path: &Path) -> Result<JsonTree, ParseError> {
+    load_document(path, false).map(|(tree, _)| tree)
+}
+
+/// Read and parse a file, returning the tree and how many bytes of the file
+/// it covers (see [`parse_document`])
+fn load_document(path: &Path, following: bool) -> Result<(JsonTree, usize), ParseError> {
+    let filename = path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let contents = fs::read_to_string(path).map_err(|e| ParseError {
+        message: e.to_string(),
+        line: None,
+        column: None,
+        context_line: None,
+        filename: filename.clone(),
+    })?;
+
+    let (json_value, consumed) = parse_document(path, &contents, &filename, following)?;
+    Ok((parser::build_tree(&json_value), consumed))
+}
+
+/// Parse file contents as JSON, or as JSON Lines (an array with one element
+/// per line) for `.jsonl` / `.ndjson` files. Also returns the number of bytes
+/// parsed, which is short of the whole file when a JSON Lines file being
+/// followed ends in a line that is still being written.
+fn parse_document(path: &Path, contents: &str, filename: &str, following: bool) -> Result<(serde_json::Value, usize), ParseError> {
+    if jsonl::is_json_lines(path) {
+        let (values, consumed) = jsonl::parse_lines(contents, following).map_err(|(line, text, e)| ParseError {
+            line: Some(line),
+            ..ParseError::from_serde_error(&e, &text, filename)
+        })?;
+        Ok((serde_json::Value::Array(values), consumed))
+    } else {
+        let value = serde_json::from_str::<serde_json::Value>(contents)
+            .map_err(|e| ParseError::from_serde_error(&e, contents, filename))?;
+        Ok((value, contents.len()))
+    }
+}
+
+/// Modification time and size of a file, used to tell real changes from
+/// spurious watcher events
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Current time in seconds since the Unix epoch
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Step a result cursor forward or backward, wrapping around at both ends
+fn step_result_index(current: Option<usize>, len: usize, forward: bool) -> usize {
+    match (current, forward) {
+        (Some(i), true) => (i + 1) % len,
+        (None, true) => 0,
+        (Some(0), false) | (None, false) => len - 1,
+        (Some(i), false) => i - 1,
+    }
 }
 
 fn split_initial_open_paths(mut paths: Vec<PathBuf>) -> (Option<PathBuf>, Vec<PathBuf>) {
@@ -210,19 +327,17 @@ pub fn main() -> iced::Result {
     iced::application(App::boot, App::update, App::view)
         .window(window::Settings {
             icon,
+            // Closing goes through `Message::Quit` so the session gets saved
+            exit_on_close_request: false,
             ..Default::default()
         })
         .window_size((900.0, 700.0))
         .resizable(true)
         .title(|app: &App| {
-            match &app.current_file {
-                Some(path) => {
-                    let filename = path.file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_else(|| "unknown".to_string());
-                    format!("{} - Unfold", filename)
-                }
-                None => String::from("Unfold - JSON Viewer")
+            if app.doc.current_file.is_none() && app.doc.compare.is_none() {
+                String::from("Unfold - JSON Viewer")
+            } else {
+                format!("{} - Unfold", app.doc.title())
             }
         })
         .subscription(App::subscription)
@@ -231,39 +346,24 @@ pub fn main() -> iced::Result {
 
 /// The application state (Model)
 struct App {
-    tree: Option<JsonTree>,
-    status: String,
-    current_file: Option<PathBuf>,
-    #[allow(dead_code)]
-    preferences: Preferences,
+    /// The document shown in the active tab
+    doc: Document,
+    /// All tabs in display order; the active slot holds a placeholder while
+    /// its document is swapped out into `doc`
+    tabs: Vec<Document>,
+    active_tab: usize,
+    /// Tab being dragged to a new position
+    dragging_tab: Option<usize>,
     /// Current theme (dark/light)
     theme: AppTheme,
-    /// Time taken to load and parse the file
-    load_time: Option<Duration>,
-    /// Flattened rows for virtual scrolling (rebuilt when tree changes)
-    flat_rows: Vec<FlatRow>,
     /// Viewport height in pixels (updated on resize)
     viewport_height: f32,
-    /// Current scroll offset in pixels (for virtual scrolling)
-    scroll_offset: f32,
-    // Search state
-    search_query: String,
-    search_results: Vec<usize>,
-    search_result_index: Option<usize>,
-    search_matches: HashSet<usize>,
-    search_case_sensitive: bool,
-    search_use_regex: bool,
-    search_regex_error: Option<String>,
     /// Scrollable ID for programmatic scrolling
     tree_scrollable_id: WidgetId,
     /// Search input ID for programmatic focus
     search_input_id: WidgetId,
     /// Track current keyboard modifiers (for Shift+Enter in search input)
     current_modifiers: Modifiers,
-    /// Currently selected node (for copy, path display, etc.)
-    selected_node: Option<usize>,
-    /// Parse error details (for better error display)
-    parse_error: Option<ParseError>,
     /// Show help overlay with keyboard shortcuts
     show_help: bool,
     /// Context menu state: Some((node_index, x, y)) when visible
@@ -276,85 +376,95 @@ struct App {
     cli_install_result: Option<(bool, String)>,
     /// Persistent user configuration
     config: Config,
-}
-
-/// User-configurable display preferences (for future use)
-#[allow(dead_code)]
-#[derive(Debug, Clone)]
-struct Preferences {
-    indent_size: usize,
-    show_tree_lines: bool,
-}
-
-impl Default for Preferences {
-    fn default() -> Self {
-        Preferences {
-            indent_size: 2,
-            show_tree_lines: true,
-        }
-    }
+    /// Show the statistics panel (computed per document)
+    show_stats: bool,
+    /// Show the bookmarks sidebar
+    show_bookmarks: bool,
+    /// Open breadcrumb dropdown: (node whose children are listed, x offset)
+    breadcrumb_menu: Option<(usize, f32)>,
+    /// Open go-to-path dialog
+    goto_path: Option<GotoPathDialog>,
+    /// Go-to-path input ID for programmatic focus
+    goto_path_input_id: WidgetId,
+    /// First key of a two-key vim command (`g` or `z`)
+    pending_vim_key: Option<char>,
+    /// Active keyboard shortcuts (defaults plus ~/.unfold/keybindings.json)
+    keymap: Keymap,
+    /// Open command palette
+    command_palette: Option<CommandPalette>,
+    /// Command palette input ID for programmatic focus
+    command_palette_input_id: WidgetId,
+    table_scrollable_id: WidgetId,
+    /// Open CSV/TSV export dialog
+    csv_export: Option<CsvExportDialog>,
+    /// Formatting options dialog is open
+    show_format_options: bool,
 }
 
 impl App {
     /// Initialize the application (called once at startup)
     fn boot() -> (Self, Task<Message>) {
         // Load persistent config
-        let config = Config::load();
+        let mut config = Config::load();
+        if config.prune_recent_files() {
+            let _ = config.save();
+        }
 
         #[cfg(target_os = "macos")]
         macos_open::install_open_file_handler();
 
         let app = App {
-            tree: None,
-            status: String::from("No file loaded"),
-            current_file: None,
-            preferences: Preferences::default(),
+            doc: Document::default(),
+            tabs: vec![Document::default()],
+            active_tab: 0,
+            dragging_tab: None,
             theme: config.theme,  // Use saved theme
-            load_time: None,
-            flat_rows: Vec::new(),
             viewport_height: 600.0,
-            scroll_offset: 0.0,
-            search_query: String::new(),
-            search_results: Vec::new(),
-            search_result_index: None,
-            search_matches: HashSet::new(),
-            search_case_sensitive: false,
-            search_use_regex: false,
-            search_regex_error: None,
             tree_scrollable_id: WidgetId::unique(),
             search_input_id: WidgetId::unique(),
             current_modifiers: Modifiers::default(),
-            selected_node: None,
-            parse_error: None,
             show_help: false,
             context_menu_state: None,
             context_submenu: ContextSubmenu::None,
             update_check_state: UpdateCheckState::None,
             cli_install_result: None,
             config,
+            show_stats: false,
+            show_bookmarks: false,
+            breadcrumb_menu: None,
+            goto_path: None,
+            goto_path_input_id: WidgetId::unique(),
+            pending_vim_key: None,
+            keymap: Keymap::load(),
+            command_palette: None,
+            command_palette_input_id: WidgetId::unique(),
+            table_scrollable_id: WidgetId::unique(),
+            csv_export: None,
+            show_format_options: false,
         };
 
         let args: Vec<String> = env::args().collect();
         let (initial_file, extra_files) = split_initial_open_paths(initial_open_paths(&args));
 
-        for extra_file in extra_files {
-            spawn_unfold_process(Some(extra_file));
+        let mut tasks = Vec::new();
+        if let Some(file_path) = initial_file {
+            let open = Task::done(Message::FileSelected(Some(file_path)));
+            tasks.push(match cli_path_argument(&args) {
+                Some(path) => open.chain(Task::done(Message::GotoPath(path))),
+                None => open,
+            });
         }
-
-        #[cfg(target_os = "macos")]
-        {
-            macos_open::mark_app_ready();
-
-            for late_file in macos_open::take_pending_open_files() {
-                spawn_unfold_process(Some(late_file));
-            }
+        if !extra_files.is_empty() {
+            tasks.push(Task::done(Message::OpenInNewTabs(extra_files)));
         }
-
-        if let Some(file_path) = initial_file {
-            (app, Task::done(Message::FileSelected(Some(file_path))))
-        } else {
-            (app, Task::none())
+        if tasks.is_empty() && app.config.restore_session {
+            let mut session = Session::load();
+            session.retain_existing();
+            if !session.tabs.is_empty() {
+                tasks.push(Task::done(Message::RestoreSession(session)));
+            }
         }
+        (app, Task::batch(tasks))
     }
 
     /// Subscription: Listen for keyboard and menu events
@@ -365,7 +475,11 @@ impl App {
             // Keyboard events subscription
             keyboard::listen().filter_map(|event| {
                 match event {
-                    keyboard::Event::KeyPressed { key, modifiers, .. } => {
+                    keyboard::Event::KeyPressed { key, modified_key, modifiers, .. } => {
+                        // `*` and `?` need Shift on most layouts, so match them with Shift already applied
+                        if matches!(modified_key.as_ref(), Key::Character("*" | "?")) {
+                            return Some(Message::KeyPressed(modified_key, modifiers - Modifiers::SHIFT));
+                        }
                         Some(Message::KeyPressed(key, modifiers))
                     }
                     keyboard::Event::ModifiersChanged(modifiers) => {
@@ -374,29 +488,56 @@ impl App {
                     _ => None
                 }
             }),
-            // Window events subscription - listen for file drops
+            // Window events subscription - listen for file drops and the
+            // mouse back/forward buttons
             event::listen().filter_map(|event| {
-                if let Event::Window(window::Event::FileDropped(path)) = event {
-                    Some(Message::FileDropped(path))
-                } else {
-                    None
+                match event {
+                    Event::Window(window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+                    Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Back)) => Some(Message::NavigateBack),
+                    Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Forward)) => Some(Message::NavigateForward),
+                    _ => None,
                 }
             }),
+            window::close_requests().map(|_| Message::Quit),
             // Menu events subscription - poll for native menu events every 50ms
             iced::time::every(std::time::Duration::from_millis(50)).map(|_| {
                 menu::try_receive_menu_event().unwrap_or(Message::NoOp)
             }),
+            // Validation and statistics lag behind follow mode by at most a second
+            if self.doc.follow_analysis_pending {
+                iced::time::every(std::time::Duration::from_secs(1)).map(|_| Message::RefreshFollowAnalysis)
+            } else {
+                Subscription::none()
+            },
+            // File watcher subscriptions - one per open file, restarted when it changes
+            Subscription::batch(
+                self.documents()
+                    .filter(|doc| doc.compare.is_none())
+                    .filter_map(|doc| doc.current_file.clone())
+                    .map(|path| Subscription::run_with(path, watcher::watch)),
+            ),
+            // Files opened from Finder while the app is running become tabs
+            #[cfg(target_os = "macos")]
+            iced::time::every(std::time::Duration::from_millis(200)).map(|_| {
+                let paths = macos_open::take_pending_open_files();
+                if paths.is_empty() { Message::NoOp } else { Message::OpenInNewTabs(paths) }
+            }),
         ])
     }
 
     /// Flatten the tree into a Vec<FlatRow> for virtual scrolling
     fn flatten_visible_nodes(tree: &JsonTree) -> Vec<FlatRow> {
         let mut rows = Vec::new();
+        Self::flatten_root_children(tree, &mut rows, 0);
+        rows
+    }
 
+    /// Flatten the root's children from position `from` onwards
+    fn flatten_root_children(tree: &JsonTree, rows: &mut Vec<FlatRow>, from: usize) {
         if let Some(root) = tree.get_node(tree.root_index()) {
             let root_is_array = matches!(root.value, JsonValue::Array);
             let child_count = root.children.len();
-            for (i, &child_index) in root.children.iter().enumerate() {
+            for (i, &child_index) in root.children.iter().enumerate().skip(from) {
                 let is_last = i == child_count - 1;
                 let child_path = if root_is_array {
                     format!("[{}]", i)
@@ -405,11 +546,30 @@ impl App {
                 } else {
                     String::new()
                 };
-                Self::flatten_node(tree, child_index, &mut rows, "", is_last, false, &child_path);
+                Self::flatten_node(tree, child_index, rows, "", is_last, false, &child_path);
             }
         }
+    }
 
-        rows
+    /// Extend the rows after elements were appended to the root array.
+    ///
+    /// The previously last element is flattened again since its tree lines
+    /// change from "└" to "├".
+    fn append_root_rows(tree: &JsonTree, rows: &mut Vec<FlatRow>, first_new_child: usize) {
+        let Some(root) = tree.get_node(tree.root_index()) else {
+            return;
+        };
+        let from = first_new_child.saturating_sub(1);
+        match root.children.get(from).and_then(|&last| rows.iter().rposition(|r| r.node_index == last)) {
+            Some(row) => {
+                rows.truncate(row);
+                Self::flatten_root_children(tree, rows, from);
+            }
+            None => {
+                rows.clear();
+                Self::flatten_root_children(tree, rows, 0);
+            }
+        }
     }
 
     /// Recursively flatten a single node and its visible children
@@ -516,7 +676,7 @@ impl App {
         highlight_color: Color,
         is_search_row: bool,
     ) -> Vec<Element<'a, Message>> {
-        if !is_search_row || self.search_query.is_empty() {
+        if !is_search_row || self.doc.search_query.is_empty() {
             return vec![
                 text(content.to_string())
                     .font(Font::MONOSPACE)
@@ -528,9 +688,9 @@ impl App {
 
         let segments = search::highlight_segments(
             content,
-            &self.search_query,
-            self.search_case_sensitive,
-            self.search_use_regex,
+            &self.doc.search_query,
+            self.doc.search_case_sensitive,
+            self.doc.search_use_regex,
         );
 
         segments
@@ -547,13 +707,62 @@ impl App {
         let colors = get_theme_colors(self.theme);
         let value_color = flat_row.value_type.color(&colors);
 
-        let is_selected = self.selected_node == Some(flat_row.node_index);
-        let is_match = self.search_matches.contains(&flat_row.node_index);
-        let is_current_result = self.search_result_index
-            .map(|i| self.search_results.get(i) == Some(&flat_row.node_index))
+        let is_selected = self.doc.selected_node == Some(flat_row.node_index);
+        let is_match = self.doc.search_matches.contains(&flat_row.node_index);
+        let is_current_result = self.doc.search_result_index
+            .map(|i| self.doc.search_results.get(i) == Some(&flat_row.node_index))
             .unwrap_or(false);
         let is_search_row = is_match || is_current_result;
 
+        let diff_kind = self.doc.compare.as_ref()
+            .and_then(|c| c.kinds.get(&flat_row.node_index).copied());
+        let is_patched = self.doc.patch_preview.as_ref()
+            .is_some_and(|p| p.affected.contains(&flat_row.node_index));
+        let is_current_change = self.doc.compare.as_ref()
+            .and_then(|c| c.change_index.map(|i| c.changes.get(i) == Some(&flat_row.node_index)))
+            .unwrap_or(false);
+        let old_value: Option<Element<'a, Message>> = self.doc.compare.as_ref()
+            .and_then(|c| c.old_values.get(&flat_row.node_index))
+            .map(|old| {
+                text(format!("  (was {})", old))
+                    .font(Font::MONOSPACE)
+                    .size(13)
+                    .color(colors.diff_removed)
+                    .into()
+            });
+        let schema_badge: Option<Element<'a, Message>> = self.doc.schema.as_ref()
+            .and_then(|schema| schema.by_node.get(&flat_row.node_index).map(|problems| (schema, problems)))
+            .map(|(schema, problems)| {
+                let lines: Vec<Element<'a, Message>> = problems.iter()
+                    .filter_map(|&i| schema.violations.get(i))
+                    .map(|v| text(format!("{}: {}", v.keyword, v.message)).size(12).color(colors.text_primary).into())
+                    .collect();
+                let tip = container(column(lines).spacing(2))
+                    .padding([4, 8])
+                    .style(move |_theme| container::Style {
+                        background: Some(colors.toolbar_bg.into()),
+                        border: Border {
+                            color: colors.error,
+                            width: 1.0,
+                            radius: Radius::from(4.0),
+                        },
+                        ..Default::default()
+                    });
+                tooltip(
+                    text("  ⚠").font(Font::MONOSPACE).size(13).color(colors.error),
+                    tip,
+                    tooltip::Position::Top,
+                )
+                .into()
+            });
+
+        let bookmark_badge: Option<Element<'a, Message>> = self.doc.bookmark_nodes.get(&flat_row.node_index)
+            .and_then(|&position| self.doc.bookmarks.get(position))
+            .map(|bookmark| {
+                let badge = if bookmark.label.is_empty() { "  ★".to_string() } else { format!("  ★ {}", bookmark.label) };
+                text(badge).font(Font::MONOSPACE).size(13).color(colors.boolean).into()
+            });
+
         let node_row: Element<'a, Message> = if flat_row.is_expandable {
             let indicator = if flat_row.is_expanded { "⊟ " } else { "⊞ " };
 
@@ -568,7 +777,7 @@ impl App {
                     &display_key,
                     colors.key,
                     colors.search_highlight_text,
-                    is_search_row,
+                    is_search_row && self.doc.search_filter.keys,
                 ));
                 row_elements.push(
                     text(": ")
@@ -584,9 +793,12 @@ impl App {
                     &flat_row.value_display,
                     value_color,
                     colors.search_highlight_text,
-                    is_search_row,
+                    is_search_row && self.doc.search_filter.searches_type(flat_row.value_type),
                 ));
+                row_elements.extend(old_value);
             }
+            row_elements.extend(schema_badge);
+            row_elements.extend(bookmark_badge);
 
             button(row(row_elements).spacing(0))
                 .on_press(Message::ToggleNode(flat_row.node_index))
@@ -605,7 +817,7 @@ impl App {
                     &display_key,
                     colors.key,
                     colors.search_highlight_text,
-                    is_search_row,
+                    is_search_row && self.doc.search_filter.keys,
                 ));
                 row_elements.push(
                     text(": ")
@@ -620,8 +832,11 @@ impl App {
                 &flat_row.value_display,
                 value_color,
                 colors.search_highlight_text,
-                is_search_row,
+                is_search_row && self.doc.search_filter.searches_type(flat_row.value_type),
             ));
+            row_elements.extend(old_value);
+            row_elements.extend(schema_badge);
+            row_elements.extend(bookmark_badge);
 
             button(row(row_elements).spacing(0))
                 .on_press(Message::SelectNode(flat_row.node_index))
@@ -630,12 +845,30 @@ impl App {
                 .into()
         };
 
-        let background_color = if is_current_result {
+        // In compare mode, every row gets a change-marker gutter so rows stay aligned
+        let node_row: Element<'a, Message> = if self.doc.compare.is_some() {
+            let (marker, marker_color) = match diff_kind {
+                Some(kind) => (kind.marker(), kind.color(&colors)),
+                None => (" ", colors.indicator),
+            };
+            row![
+                text(format!("{} ", marker)).font(Font::MONOSPACE).size(13).color(marker_color),
+                node_row,
+            ].into()
+        } else {
+            node_row
+        };
+
+        let background_color = if is_current_result || is_current_change {
             Some(colors.search_current)
         } else if is_selected {
             Some(colors.selected)
         } else if is_match {
             Some(colors.search_match)
+        } else if let Some(kind) = diff_kind {
+            Some(Color { a: 0.15, ..kind.color(&colors) })
+        } else if is_patched {
+            Some(Color { a: 0.15, ..colors.diff_changed })
         } else if flat_row.row_index % 2 == 1 {
             Some(colors.row_odd)
         } else {
@@ -662,7 +895,7 @@ impl App {
         let node_index = flat_row.node_index;
         let row_index = flat_row.row_index;
         let toolbar_height = 60.0;
-        let y_pos = toolbar_height + (row_index as f32 * ROW_HEIGHT) - self.scroll_offset + ROW_HEIGHT;
+        let y_pos = toolbar_height + (row_index as f32 * ROW_HEIGHT) - self.doc.scroll_offset + ROW_HEIGHT;
         let estimated_depth = flat_row.prefix.len() / 4;
         let x_pos = 50.0 + (estimated_depth as f32 * 15.0);
 
@@ -673,7 +906,7 @@ impl App {
 
     /// Calculate the maximum display width needed for the tree
     fn calculate_max_width(&self) -> f32 {
-        let Some(tree) = &self.tree else {
+        let Some(tree) = &self.doc.tree else {
             return 400.0;
         };
 
@@ -725,6 +958,7 @@ impl App {
                     async {
                         let file = rfd::AsyncFileDialog::new()
                             .add_filter("JSON", &["json"])
+                            .add_filter("JSON Lines", &["jsonl", "ndjson"])
                             .add_filter("All Files", &["*"])
                             .set_title("Open JSON File")
                             .pick_file()
@@ -737,23 +971,36 @@ impl App {
             Message::FileSelected(path_option) => {
                 match path_option {
                     Some(path) => {
+                        self.flush_bookmarks();
                         let start = Instant::now();
+                        let filename = path.file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                            .unwrap_or_else(|| "unknown".to_string());
                         match fs::read_to_string(&path) {
                             Ok(contents) => {
-                                match serde_json::from_str::<serde_json::Value>(&contents) {
-                                    Ok(json_value) => {
+                                match parse_document(&path, &contents, &filename, false) {
+                                    Ok((json_value, consumed)) => {
                                         let tree = parser::build_tree(&json_value);
                                         let elapsed = start.elapsed();
-                                        let filename = path.file_name()
-                                            .map(|n| n.to_string_lossy().to_string())
-                                            .unwrap_or_else(|| "unknown".to_string());
-                                        self.status = format!("✓ {} ({} nodes)", filename, tree.node_count());
-                                        self.tree = Some(tree);
-                                        self.current_file = Some(path);
-                                        self.load_time = Some(elapsed);
-                                        self.parse_error = None;
-
-                                        self.flat_rows = Self::flatten_visible_nodes(self.tree.as_ref().unwrap());
+                                        self.doc.status = format!("✓ {} ({} nodes)", filename, tree.node_count());
+                                        self.doc.tree = Some(tree);
+                                        self.doc.loaded_stamp = file_stamp(&path);
+                                        self.doc.file_changed_on_disk = false;
+                                        self.doc.follower = jsonl::is_json_lines(&path)
+                                            .then(|| LogFollower::new(path.clone(), consumed as u64));
+                                        self.doc.following = false;
+                                        self.remember_recent_file(&path);
+                                        self.doc.bookmarks = bookmarks::load_for(&path);
+                                        self.doc.current_file = Some(path);
+                                        self.doc.load_time = Some(elapsed);
+                                        self.doc.parse_error = None;
+                                        self.doc.compare = None;
+                                        self.doc.patch_preview = None;
+                                        self.doc.search_scope = None;
+                                        self.attach_referenced_schema();
+                                        self.document_changed();
+
+                                        self.doc.flat_rows = Self::flatten_visible_nodes(self.doc.tree.as_ref().unwrap());
 
                                         let new_width = self.calculate_max_width();
                                         return window::latest()
@@ -761,31 +1008,25 @@ impl App {
                                                 window::resize(window_id, Size::new(new_width, 700.0))
                                             });
                                     }
-                                    Err(e) => {
-                                        let filename = path.file_name()
-                                            .map(|n| n.to_string_lossy().to_string())
-                                            .unwrap_or_else(|| "unknown".to_string());
-                                        self.parse_error = Some(ParseError::from_serde_error(&e, &contents, &filename));
-                                        self.status = format!("✗ Parse error in {}", filename);
-                                        self.tree = None;
-                                        self.current_file = None;
+                                    Err(error) => {
+                                        self.doc.parse_error = Some(error);
+                                        self.doc.status = format!("✗ Parse error in {}", filename);
+                                        self.doc.tree = None;
+                                        self.doc.current_file = None;
                                     }
                                 }
                             }
                             Err(e) => {
-                                let filename = path.file_name()
-                                    .map(|n| n.to_string_lossy().to_string())
-                                    .unwrap_or_else(|| "unknown".to_string());
-                                self.parse_error = Some(parse_error::ParseError {
+                                self.doc.parse_error = Some(parse_error::ParseError {
                                     message: e.to_string(),
                                     line: None,
                                     column: None,
                                     context_line: None,
                                     filename,
                                 });
-                                self.status = format!("✗ File error: {}", e);
-                                self.tree = None;
-                                self.current_file = None;
+                                self.doc.status = format!("✗ File error: {}", e);
+                                self.doc.tree = None;
+                                self.doc.current_file = None;
                             }
                         }
                         Task::none()
@@ -797,7 +1038,8 @@ impl App {
                 let is_json = path.extension()
                     .map(|ext| ext.to_string_lossy().to_lowercase())
                     .map(|ext| ext == "json")
-                    .unwrap_or(false);
+                    .unwrap_or(false)
+                    || jsonl::is_json_lines(&path);
 
                 if is_json {
                     self.update(Message::FileSelected(Some(path)))
@@ -805,92 +1047,121 @@ impl App {
                     let filename = path.file_name()
                         .map(|n| n.to_string_lossy().to_string())
                         .unwrap_or_else(|| "unknown".to_string());
-                    self.status = format!("✗ Not a JSON file: {}", filename);
+                    self.doc.status = format!("✗ Not a JSON file: {}", filename);
+                    Task::none()
+                }
+            }
+            Message::FileChangedOnDisk(path) => {
+                // Background tabs are only marked; they catch up when activated
+                for doc in self.tabs.iter_mut() {
+                    if doc.current_file.as_ref() == Some(&path) && file_stamp(&path) != doc.loaded_stamp {
+                        doc.file_changed_on_disk = true;
+                    }
+                }
+                if self.doc.current_file.as_ref() != Some(&path) {
+                    return Task::none();
+                }
+                // Missing while being replaced, or an event that changed nothing
+                let stamp = file_stamp(&path);
+                if stamp.is_none() || stamp == self.doc.loaded_stamp {
+                    return Task::none();
+                }
+                if self.doc.following && self.doc.patch_preview.is_none() {
+                    self.follow_file()
+                } else if self.config.auto_reload && self.doc.patch_preview.is_none() {
+                    self.reload_file()
+                } else {
+                    self.doc.file_changed_on_disk = true;
+                    Task::none()
+                }
+            }
+            Message::ReloadFile => self.reload_file(),
+            Message::RefreshFollowAnalysis => {
+                self.doc.follow_analysis_pending = false;
+                self.revalidate();
+                self.refresh_stats();
+                Task::none()
+            }
+            Message::ToggleFollow => {
+                self.doc.following = !self.doc.following && self.doc.follower.is_some();
+                if self.doc.following {
+                    self.doc.file_changed_on_disk = false;
+                    self.follow_file()
+                } else {
+                    Task::none()
+                }
+            }
+            Message::DismissFileChanged => {
+                self.doc.file_changed_on_disk = false;
+                self.doc.loaded_stamp = self.doc.current_file.as_deref().and_then(file_stamp);
+                Task::none()
+            }
+            Message::SetAutoReload(enabled) => {
+                self.config.auto_reload = enabled;
+                let _ = self.config.save();
+                if enabled && self.doc.file_changed_on_disk {
+                    self.reload_file()
+                } else {
                     Task::none()
                 }
             }
             Message::ToggleNode(index) => {
-                self.selected_node = Some(index);
-                if let Some(tree) = &mut self.tree {
+                self.doc.selected_node = Some(index);
+                if let Some(tree) = &mut self.doc.tree {
                     tree.toggle_expanded(index);
-                    self.flat_rows = Self::flatten_visible_nodes(tree);
+                    self.doc.flat_rows = Self::flatten_visible_nodes(tree);
                 }
                 Task::none()
             }
             Message::Scrolled(viewport) => {
-                self.scroll_offset = viewport.absolute_offset().y;
+                self.doc.scroll_offset = viewport.absolute_offset().y;
                 self.viewport_height = viewport.bounds().height;
                 Task::none()
             }
             Message::SearchQueryChanged(query) => {
-                self.search_query = query;
+                self.doc.search_query = query;
                 self.run_search()
             }
             Message::ToggleCaseSensitive => {
-                self.search_case_sensitive = !self.search_case_sensitive;
+                self.doc.search_case_sensitive = !self.doc.search_case_sensitive;
                 self.run_search()
             }
             Message::ToggleRegex => {
-                self.search_use_regex = !self.search_use_regex;
+                self.doc.search_use_regex = !self.doc.search_use_regex;
                 self.run_search()
             }
-            Message::SearchNext => {
-                if !self.search_results.is_empty() {
-                    let new_index = match self.search_result_index {
-                        Some(i) => (i + 1) % self.search_results.len(),
-                        None => 0,
-                    };
-                    self.search_result_index = Some(new_index);
-
-                    let node_index = self.search_results[new_index];
-                    self.expand_to_node(node_index);
-
-                    if let Some(tree) = &self.tree {
-                        self.flat_rows = Self::flatten_visible_nodes(tree);
-                    }
-
-                    self.scroll_to_node(node_index)
-                } else {
-                    Task::none()
-                }
-            }
-            Message::SearchPrev => {
-                if !self.search_results.is_empty() {
-                    let new_index = match self.search_result_index {
-                        Some(i) => {
-                            if i == 0 {
-                                self.search_results.len() - 1
-                            } else {
-                                i - 1
-                            }
-                        }
-                        None => self.search_results.len() - 1,
-                    };
-                    self.search_result_index = Some(new_index);
-
-                    let node_index = self.search_results[new_index];
-                    self.expand_to_node(node_index);
-
-                    if let Some(tree) = &self.tree {
-                        self.flat_rows = Self::flatten_visible_nodes(tree);
-                    }
-
-                    self.scroll_to_node(node_index)
-                } else {
-                    Task::none()
-                }
+            Message::SetSearchFilter(filter) => {
+                self.doc.search_filter = filter;
+                self.run_search()
             }
+            Message::SearchNext => self.step_results(true),
+            Message::SearchPrev => self.step_results(false),
             Message::ClearSearch => {
-                self.search_query.clear();
-                self.search_results.clear();
-                self.search_result_index = None;
-                self.search_matches.clear();
-                self.search_regex_error = None;
+                self.clear_search();
                 Task::none()
             }
             Message::FocusSearch => {
                 operate(focusable::focus(self.search_input_id.clone()))
             }
+            Message::SearchInSelection => {
+                self.context_menu_state = None;
+                self.context_submenu = ContextSubmenu::None;
+                let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) else {
+                    self.doc.status = "✗ Select an object or array to search in".to_string();
+                    return Task::none();
+                };
+                if !tree.get_node(node_index).is_some_and(|node| node.is_expandable()) {
+                    self.doc.status = "✗ Select an object or array to search in".to_string();
+                    return Task::none();
+                }
+                // The root covers everything, so that is an unscoped search
+                self.doc.search_scope = (node_index != tree.root_index()).then(|| tree.pointer_to_node(node_index));
+                Task::batch([self.run_search(), operate(focusable::focus(self.search_input_id.clone()))])
+            }
+            Message::ClearSearchScope => {
+                self.doc.search_scope = None;
+                self.run_search()
+            }
             Message::ModifiersChanged(modifiers) => {
                 self.current_modifiers = modifiers;
                 Task::none()
@@ -905,88 +1176,190 @@ impl App {
             Message::KeyPressed(key, modifiers) => {
                 let cmd_or_ctrl = modifiers.command() || modifiers.control();
 
-                match key {
-                    Key::Named(Named::Escape) => {
-                        if self.show_help {
-                            self.update(Message::ToggleHelp)
-                        } else if self.context_menu_state.is_some() {
-                            self.update(Message::HideContextMenu)
-                        } else {
-                            self.update(Message::ClearSearch)
-                        }
-                    }
-                    Key::Named(Named::Enter) => {
-                        if modifiers.shift() {
-                            self.update(Message::SearchPrev)
-                        } else {
-                            self.update(Message::SearchNext)
-                        }
-                    }
-                    Key::Character(c) if c.as_str() == "o" && cmd_or_ctrl => {
-                        self.update(Message::OpenFileDialog)
-                    }
-                    Key::Character(c) if c.as_str() == "g" && cmd_or_ctrl => {
-                        if modifiers.shift() {
-                            self.update(Message::SearchPrev)
-                        } else {
-                            self.update(Message::SearchNext)
-                        }
-                    }
-                    Key::Character(c) if c.as_str() == "f" && cmd_or_ctrl => {
-                        self.update(Message::FocusSearch)
-                    }
-                    Key::Character(c) if c.as_str() == "n" && cmd_or_ctrl => {
-                        self.update(Message::OpenFileInNewWindow)
-                    }
-                    Key::Character(c) if c.as_str() == "c" && cmd_or_ctrl && !modifiers.shift() && !modifiers.alt() => {
-                        self.update(Message::CopySelectedValue)
-                    }
-                    Key::Character(c) if c.as_str() == "c" && cmd_or_ctrl && modifiers.shift() && !modifiers.alt() => {
-                        self.update(Message::CopySelectedName)
-                    }
-                    Key::Character(c) if c.as_str() == "c" && cmd_or_ctrl && modifiers.alt() => {
-                        self.update(Message::CopySelectedPath)
-                    }
-                    Key::Character(c) if c.as_str() == "t" && cmd_or_ctrl => {
-                        self.update(Message::ToggleTheme)
+                // The command palette keeps the keyboard while it is open
+                if self.command_palette.is_some() {
+                    return match key {
+                        Key::Named(Named::ArrowDown) => self.update(Message::CommandPaletteMove(true)),
+                        Key::Named(Named::ArrowUp) => self.update(Message::CommandPaletteMove(false)),
+                        ref key => match self.keymap.lookup(key, modifiers) {
+                            Some(keymap::Command::Palette | keymap::Command::Dismiss) => self.update(Message::ToggleCommandPalette),
+                            _ => Task::none(),
+                        },
+                    };
+                }
+
+                // The go-to-path input keeps the keyboard while it is open
+                if self.goto_path.is_some() {
+                    return match self.keymap.lookup(&key, modifiers) {
+                        Some(keymap::Command::GoToPath | keymap::Command::Dismiss) => self.update(Message::ToggleGotoPath),
+                        Some(keymap::Command::CompletePath) => self.update(Message::CompleteGotoPath),
+                        _ => Task::none(),
+                    };
+                }
+
+                if self.config.vim_keys && !cmd_or_ctrl && !modifiers.alt()
+                    && let Key::Character(c) = &key
+                    && let Some(message) = self.vim_key(c.as_str(), modifiers.shift()) {
+                        return self.update(message);
                     }
-                    Key::Character(c) if (c.as_str() == "/" || c.as_str() == "?") && cmd_or_ctrl => {
-                        self.update(Message::ToggleHelp)
+
+                match key {
+                    // Cmd+1..8 pick a tab, Cmd+9 the last one
+                    Key::Character(c) if cmd_or_ctrl && let Ok(digit @ 1..=9) = c.as_str().parse::<usize>() => {
+                        let index = if digit == 9 { self.tabs.len() - 1 } else { digit - 1 };
+                        self.update(Message::SelectTab(index))
                     }
-                    _ => Task::none()
+                    key => match self.keymap.lookup(&key, modifiers) {
+                        Some(command) => self.update(command.message()),
+                        None => Task::none(),
+                    },
                 }
             }
-            Message::OpenFileInNewWindow => {
+            Message::OpenFileInNewTab => {
                 Task::perform(
                     async {
                         let file = rfd::AsyncFileDialog::new()
                             .add_filter("JSON", &["json"])
+                            .add_filter("JSON Lines", &["jsonl", "ndjson"])
                             .add_filter("All Files", &["*"])
-                            .set_title("Open JSON File in New Window")
+                            .set_title("Open JSON File in New Tab")
                             .pick_file()
                             .await;
                         file.map(|f| f.path().to_path_buf())
                     },
-                    Message::FileSelectedForNewWindow,
+                    |path| Message::OpenInNewTabs(path.into_iter().collect()),
                 )
             }
-            Message::FileSelectedForNewWindow(path_option) => {
-                if let Some(file_path) = path_option {
-                    spawn_unfold_process(Some(file_path));
+            Message::OpenInNewTabs(paths) => {
+                let tasks: Vec<Task<Message>> = paths.into_iter()
+                    .map(|path| {
+                        if !self.doc.is_empty() {
+                            self.open_tab();
+                        }
+                        self.update(Message::FileSelected(Some(path)))
+                    })
+                    .collect();
+                Task::batch(tasks)
+            }
+            Message::OpenRecentFile(index) => {
+                let Some(recent) = self.config.recent_files.get(index) else {
+                    return Task::none();
+                };
+                let path = recent.path.clone();
+                if path.is_file() {
+                    return self.update(Message::OpenInNewTabs(vec![path]));
+                }
+                self.config.prune_recent_files();
+                let _ = self.config.save();
+                menu::update_recent_files_menu(&self.config.recent_files);
+                self.doc.status = format!("✗ File no longer exists: {}", path.display());
+                Task::none()
+            }
+            Message::ClearRecentFiles => {
+                self.config.recent_files.clear();
+                let _ = self.config.save();
+                menu::update_recent_files_menu(&self.config.recent_files);
+                Task::none()
+            }
+            Message::NewTab => {
+                self.open_tab();
+                Task::none()
+            }
+            Message::SelectTab(index) => self.activate_tab(index),
+            Message::CycleTab(forward) => {
+                let count = self.tabs.len();
+                let index = if forward { (self.active_tab + 1) % count } else { (self.active_tab + count - 1) % count };
+                self.activate_tab(index)
+            }
+            Message::CloseTab(index) => self.close_tab(index),
+            Message::CloseActiveTab => self.close_tab(self.active_tab),
+            Message::TabDragStart(index) => {
+                self.dragging_tab = Some(index);
+                self.activate_tab(index)
+            }
+            Message::TabDragOver(index) => {
+                if let Some(from) = self.dragging_tab
+                    && from != index
+                {
+                    self.move_tab(from, index);
+                    self.dragging_tab = Some(index);
+                }
+                Task::none()
+            }
+            Message::TabDragEnd => {
+                self.dragging_tab = None;
+                Task::none()
+            }
+            Message::RestoreSession(session) => {
+                let mut tasks = Vec::new();
+                for tab in &session.tabs {
+                    if !self.doc.is_empty() {
+                        self.open_tab();
+                    }
+                    tasks.push(self.update(Message::FileSelected(Some(tab.path.clone()))));
+                    self.restore_view(tab);
+                }
+                if session.active_tab == self.active_tab {
+                    let offset = AbsoluteOffset { x: None, y: Some(self.doc.scroll_offset) };
+                    tasks.push(operate(scroll_to(self.tree_scrollable_id.clone(), offset)));
+                } else {
+                    tasks.push(self.activate_tab(session.active_tab));
+                }
+                Task::batch(tasks)
+            }
+            Message::ToggleRestoreSession => {
+                self.config.restore_session = !self.config.restore_session;
+                let _ = self.config.save();
+                if !self.config.restore_session {
+                    Session::clear();
                 }
                 Task::none()
             }
+            Message::Quit => {
+                self.flush_bookmarks();
+                if self.config.restore_session {
+                    let _ = Session::capture(self.documents(), self.active_tab).save();
+                }
+                iced::exit()
+            }
+            Message::MoveSelection(movement) => {
+                let Some(tree) = &mut self.doc.tree else {
+                    return Task::none();
+                };
+                if self.doc.table.is_some() {
+                    return Task::none();
+                }
+                let page_rows = (self.viewport_height / ROW_HEIGHT) as usize;
+                let node_index = match tree_nav::navigate(tree, &self.doc.flat_rows, self.doc.selected_node, movement, page_rows) {
+                    Some(NavAction::Select(node_index)) => node_index,
+                    Some(NavAction::SetExpanded(node_index, expanded)) => {
+                        tree.set_expanded(node_index, expanded);
+                        self.doc.flat_rows = Self::flatten_visible_nodes(tree);
+                        node_index
+                    }
+                    None => return Task::none(),
+                };
+                self.doc.selected_node = Some(node_index);
+                self.scroll_row_into_view(node_index)
+            }
+            Message::ToggleVimKeys => {
+                self.config.vim_keys = !self.config.vim_keys;
+                self.pending_vim_key = None;
+                let _ = self.config.save();
+                Task::none()
+            }
             Message::SelectNode(node_index) => {
-                if self.selected_node == Some(node_index) {
-                    self.selected_node = None;
+                if self.doc.selected_node == Some(node_index) {
+                    self.doc.selected_node = None;
                 } else {
-                    self.selected_node = Some(node_index);
+                    self.record_navigation(node_index);
+                    self.doc.selected_node = Some(node_index);
                 }
                 Task::none()
             }
             Message::CopySelectedValue => {
                 self.context_menu_state = None;
-                if let (Some(tree), Some(node_index)) = (&self.tree, self.selected_node)
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node)
                     && tree.get_node(node_index).is_some() {
                         let value_string = json_export::format_node_value_for_copy(tree, node_index);
                         return clipboard::write(value_string);
@@ -995,15 +1368,15 @@ impl App {
             }
             Message::CopySelectedPath => {
                 self.context_menu_state = None;
-                if let Some(node_index) = self.selected_node
-                    && let Some(flat_row) = self.flat_rows.iter().find(|r| r.node_index == node_index) {
+                if let Some(node_index) = self.doc.selected_node
+                    && let Some(flat_row) = self.doc.flat_rows.iter().find(|r| r.node_index == node_index) {
                         return clipboard::write(flat_row.path.clone());
                     }
                 Task::none()
             }
             Message::CopySelectedName => {
                 self.context_menu_state = None;
-                if let (Some(tree), Some(node_index)) = (&self.tree, self.selected_node)
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node)
                     && let Some(node) = tree.get_node(node_index)
                         && let Some(key) = &node.key {
                             return clipboard::write(key.clone());
@@ -1058,7 +1431,7 @@ impl App {
             }
             Message::ExportJson => {
                 self.context_menu_state = None;
-                if let (Some(tree), Some(node_index)) = (&self.tree, self.selected_node) {
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
                     let json_string = json_export::node_to_json_string(tree, node_index);
                     Task::perform(
                         async move {
@@ -1079,30 +1452,30 @@ impl App {
             }
             Message::ExpandAllChildren => {
                 self.context_menu_state = None;
-                if let Some(node_index) = self.selected_node {
-                    if let Some(tree) = &mut self.tree {
+                if let Some(node_index) = self.doc.selected_node {
+                    if let Some(tree) = &mut self.doc.tree {
                         Self::set_expanded_recursive(tree, node_index, true);
                     }
-                    if let Some(tree) = &self.tree {
-                        self.flat_rows = Self::flatten_visible_nodes(tree);
+                    if let Some(tree) = &self.doc.tree {
+                        self.doc.flat_rows = Self::flatten_visible_nodes(tree);
                     }
                 }
                 Task::none()
             }
             Message::CollapseAllChildren => {
                 self.context_menu_state = None;
-                if let Some(node_index) = self.selected_node {
-                    if let Some(tree) = &mut self.tree {
+                if let Some(node_index) = self.doc.selected_node {
+                    if let Some(tree) = &mut self.doc.tree {
                         Self::set_expanded_recursive(tree, node_index, false);
                     }
-                    if let Some(tree) = &self.tree {
-                        self.flat_rows = Self::flatten_visible_nodes(tree);
+                    if let Some(tree) = &self.doc.tree {
+                        self.doc.flat_rows = Self::flatten_visible_nodes(tree);
                     }
                 }
                 Task::none()
             }
             Message::OpenInExternalEditor => {
-                if let Some(path) = &self.current_file {
+                if let Some(path) = &self.doc.current_file {
                     #[cfg(target_os = "macos")]
                     {
                         let _ = Command::new("open").arg("-t").arg(path).spawn();
@@ -1121,7 +1494,7 @@ impl App {
                 Task::none()
             }
             Message::ShowContextMenu(node_index, x, y) => {
-                self.selected_node = Some(node_index);
+                self.doc.selected_node = Some(node_index);
                 self.context_menu_state = Some((node_index, x, y));
                 Task::none()
             }
@@ -1137,7 +1510,7 @@ impl App {
             Message::CopyValueMinified => {
                 self.context_menu_state = None;
                 self.context_submenu = ContextSubmenu::None;
-                if let (Some(tree), Some(node_index)) = (&self.tree, self.selected_node) {
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
                     let minified = json_export::node_to_json_string_minified(tree, node_index);
                     return clipboard::write(minified);
                 }
@@ -1146,20 +1519,24 @@ impl App {
             Message::CopyValueFormatted => {
                 self.context_menu_state = None;
                 self.context_submenu = ContextSubmenu::None;
-                if let (Some(tree), Some(node_index)) = (&self.tree, self.selected_node) {
-                    let json = json_export::node_to_json_string(tree, node_index);
-                    if let Ok(value) = serde_json::from_str::<serde_json::Value>(&json)
-                        && let Ok(formatted) = serde_json::to_string_pretty(&value) {
-                            return clipboard::write(formatted);
-                        }
-                    return clipboard::write(json);
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
+                    return clipboard::write(json_export::node_to_json_string_formatted(tree, node_index, &self.config.format));
+                }
+                Task::none()
+            }
+            Message::CopyTypeAs(language) => {
+                self.context_menu_state = None;
+                self.context_submenu = ContextSubmenu::None;
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
+                    self.doc.status = format!("Copied {} types", language.label());
+                    return clipboard::write(codegen::generate_types(tree, node_index, language));
                 }
                 Task::none()
             }
             Message::ExportAsJson => {
                 self.context_menu_state = None;
                 self.context_submenu = ContextSubmenu::None;
-                if let (Some(tree), Some(node_index)) = (&self.tree, self.selected_node) {
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
                     let json_string = json_export::node_to_json_string(tree, node_index);
                     Task::perform(
                         async move {
@@ -1181,7 +1558,7 @@ impl App {
             Message::ExportAsMinifiedJson => {
                 self.context_menu_state = None;
                 self.context_submenu = ContextSubmenu::None;
-                if let (Some(tree), Some(node_index)) = (&self.tree, self.selected_node) {
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
                     let minified = json_export::node_to_json_string_minified(tree, node_index);
                     Task::perform(
                         async move {
@@ -1203,13 +1580,8 @@ impl App {
             Message::ExportAsFormattedJson => {
                 self.context_menu_state = None;
                 self.context_submenu = ContextSubmenu::None;
-                if let (Some(tree), Some(node_index)) = (&self.tree, self.selected_node) {
-                    let json = json_export::node_to_json_string(tree, node_index);
-                    let formatted = if let Ok(value) = serde_json::from_str::<serde_json::Value>(&json) {
-                        serde_json::to_string_pretty(&value).unwrap_or(json)
-                    } else {
-                        json
-                    };
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
+                    let formatted = json_export::node_to_json_string_formatted(tree, node_index, &self.config.format);
                     Task::perform(
                         async move {
                             let file_handle = rfd::AsyncFileDialog::new()
@@ -1227,80 +1599,1275 @@ impl App {
                     Task::none()
                 }
             }
-            Message::InstallCLI => {
-                Task::perform(
-                    async {
-                        install_cli_tool()
-                    },
-                    Message::InstallCLIResult,
-                )
-            }
-            Message::InstallCLIResult(result) => {
-                match result {
-                    Ok(msg) => {
-                        self.status = format!("✓ {}", msg);
-                        self.cli_install_result = Some((true, msg));
-                        // Save CLI installed status to config
-                        self.config.cli_installed = true;
-                        let _ = self.config.save();
-                    }
-                    Err(msg) => {
-                        self.status = format!("✗ {}", msg);
-                        self.cli_install_result = Some((false, msg));
-                    }
+            Message::CopyValueCanonical => {
+                self.context_menu_state = None;
+                self.context_submenu = ContextSubmenu::None;
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
+                    return clipboard::write(json_export::node_to_canonical_json(tree, node_index));
                 }
                 Task::none()
             }
-            Message::DismissCLIDialog => {
-                self.cli_install_result = None;
+            Message::CopySubtreeHash => {
+                self.context_menu_state = None;
+                self.context_submenu = ContextSubmenu::None;
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
+                    let hash = json_export::node_sha256(tree, node_index);
+                    self.doc.status = format!("✓ Copied SHA-256 {}…", &hash[..12]);
+                    return clipboard::write(hash);
+                }
                 Task::none()
             }
-        }
-    }
-
-    /// Recursively set expanded state for a node and all its children
-    fn set_expanded_recursive(tree: &mut JsonTree, node_index: usize, expanded: bool) {
-        tree.set_expanded(node_index, expanded);
-
-        if let Some(node) = tree.get_node(node_index) {
-            let children = node.children.clone();
-            for child_index in children {
-                Self::set_expanded_recursive(tree, child_index, expanded);
+            Message::CopyValueAs(format) => {
+                self.context_menu_state = None;
+                self.context_submenu = ContextSubmenu::None;
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
+                    match formats::node_to_format(tree, node_index, format) {
+                        Ok(converted) => return clipboard::write(converted),
+                        Err(e) => self.doc.status = format!("✗ Cannot convert to {}: {}", format.label(), e),
+                    }
+                }
+                Task::none()
             }
-        }
-    }
-
-    /// Run search with current query and options
-    fn run_search(&mut self) -> Task<Message> {
-        if self.search_query.is_empty() {
-            self.search_results.clear();
-            self.search_result_index = None;
-            self.search_matches.clear();
-            self.search_regex_error = None;
-            return Task::none();
-        }
-
-        if let Some(tree) = &self.tree {
-            let (results, error) = search::search_nodes(
-                tree,
-                &self.search_query,
-                self.search_case_sensitive,
-                self.search_use_regex,
-            );
-
-            self.search_regex_error = error;
-            self.search_results = results;
-            self.search_matches = self.search_results.iter().cloned().collect();
-
-            if !self.search_results.is_empty() {
-                self.search_result_index = Some(0);
-                let target = self.search_results[0];
+            Message::ExportAsCanonicalJson => {
+                self.context_menu_state = None;
+                self.context_submenu = ContextSubmenu::None;
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
+                    let canonical = json_export::node_to_canonical_json(tree, node_index);
+                    Task::perform(
+                        async move {
+                            let file_handle = rfd::AsyncFileDialog::new()
+                                .add_filter("JSON", &["json"])
+                                .set_file_name("export.canonical.json")
+                                .save_file()
+                                .await;
+                            if let Some(handle) = file_handle {
+                                let _ = fs::write(handle.path(), canonical);
+                            }
+                        },
+                        |_| Message::NoOp
+                    )
+                } else {
+                    Task::none()
+                }
+            }
+            Message::ExportAs(format) => {
+                self.context_menu_state = None;
+                self.context_submenu = ContextSubmenu::None;
+                let Some((tree, node_index)) = self.doc.tree.as_ref().zip(self.doc.selected_node) else {
+                    return Task::none();
+                };
+                match formats::node_to_format(tree, node_index, format) {
+                    Ok(converted) => Task::perform(
+                        async move {
+                            let file_handle = rfd::AsyncFileDialog::new()
+                                .add_filter(format.label(), &[format.extension()])
+                                .set_file_name(format!("export.{}", format.extension()))
+                                .save_file()
+                                .await;
+                            if let Some(handle) = file_handle {
+                                let _ = fs::write(handle.path(), converted);
+                            }
+                        },
+                        |_| Message::NoOp
+                    ),
+                    Err(e) => {
+                        self.doc.status = format!("✗ Cannot convert to {}: {}", format.label(), e);
+                        Task::none()
+                    }
+                }
+            }
+            Message::ExportAsDelimited(delimiter) => {
+                self.context_menu_state = None;
+                self.context_submenu = ContextSubmenu::None;
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
+                    self.csv_export = CsvExportDialog::new(tree, node_index, delimiter);
+                }
+                Task::none()
+            }
+            Message::CsvSetDelimiter(delimiter) => {
+                if let Some(dialog) = &mut self.csv_export {
+                    dialog.delimiter = delimiter;
+                }
+                Task::none()
+            }
+            Message::CsvMoveColumn(index, up) => {
+                if let Some(dialog) = &mut self.csv_export {
+                    dialog.move_column(index, up);
+                }
+                Task::none()
+            }
+            Message::CsvToggleColumn(index) => {
+                if let Some(dialog) = &mut self.csv_export {
+                    dialog.toggle_column(index);
+                }
+                Task::none()
+            }
+            Message::CsvExportCancel => {
+                self.csv_export = None;
+                Task::none()
+            }
+            Message::CsvExportConfirm => {
+                let (Some(dialog), Some(tree)) = (self.csv_export.take(), &self.doc.tree) else {
+                    return Task::none();
+                };
+                let contents = dialog.export(tree);
+                let delimiter = dialog.delimiter;
+                Task::perform(
+                    async move {
+                        let file_handle = rfd::AsyncFileDialog::new()
+                            .add_filter(delimiter.label(), &[delimiter.extension()])
+                            .set_file_name(format!("export.{}", delimiter.extension()))
+                            .save_file()
+                            .await;
+                        if let Some(handle) = file_handle {
+                            let _ = fs::write(handle.path(), contents);
+                        }
+                    },
+                    |_| Message::NoOp
+                )
+            }
+            Message::ExportAsSchema => {
+                self.context_menu_state = None;
+                self.context_submenu = ContextSubmenu::None;
+                if let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) {
+                    let schema = schema::infer_schema(tree, node_index);
+                    let schema_string = json_export::value_to_json_string_formatted(&schema, &self.config.format);
+                    Task::perform(
+                        async move {
+                            let file_handle = rfd::AsyncFileDialog::new()
+                                .add_filter("JSON Schema", &["json"])
+                                .set_file_name("schema.json")
+                                .save_file()
+                                .await;
+                            if let Some(handle) = file_handle {
+                                let _ = fs::write(handle.path(), schema_string);
+                            }
+                        },
+                        |_| Message::NoOp
+                    )
+                } else {
+                    Task::none()
+                }
+            }
+            Message::ViewInferredSchema => {
+                self.context_menu_state = None;
+                self.context_submenu = ContextSubmenu::None;
+                let (Some(tree), Some(node_index)) = (&self.doc.tree, self.doc.selected_node) else {
+                    return Task::none();
+                };
+                // Opened as an untitled in-memory document: nothing is written
+                // to disk, so it stays out of recent files and the session
+                let schema = parser::build_tree(&schema::infer_schema(tree, node_index));
+                let stem = self.doc.current_file.as_ref()
+                    .and_then(|p| p.file_stem())
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "document".to_string());
+                self.open_tab();
+                self.doc.status = format!("✓ Generated schema ({} nodes)", schema.node_count());
+                self.doc.flat_rows = Self::flatten_visible_nodes(&schema);
+                self.doc.tree = Some(schema);
+                self.doc.untitled_name = Some(format!("{}.schema.json", stem));
+                self.document_changed();
+                Task::none()
+            }
+            Message::InstallCLI => {
+                Task::perform(
+                    async {
+                        install_cli_tool()
+                    },
+                    Message::InstallCLIResult,
+                )
+            }
+            Message::InstallCLIResult(result) => {
+                match result {
+                    Ok(msg) => {
+                        self.doc.status = format!("✓ {}", msg);
+                        self.cli_install_result = Some((true, msg));
+                        // Save CLI installed status to config
+                        self.config.cli_installed = true;
+                        let _ = self.config.save();
+                    }
+                    Err(msg) => {
+                        self.doc.status = format!("✗ {}", msg);
+                        self.cli_install_result = Some((false, msg));
+                    }
+                }
+                Task::none()
+            }
+            Message::DismissCLIDialog => {
+                self.cli_install_result = None;
+                Task::none()
+            }
+            Message::CompareWithFile => {
+                let has_document = self.doc.current_file.is_some();
+                Task::perform(
+                    async move {
+                        let dialog = rfd::AsyncFileDialog::new()
+                            .add_filter("JSON", &["json"])
+                            .add_filter("All Files", &["*"]);
+                        if has_document {
+                            dialog.set_title("Compare With...")
+                                .pick_file()
+                                .await
+                                .map(|f| vec![f.path().to_path_buf()])
+                                .unwrap_or_default()
+                        } else {
+                            dialog.set_title("Select Two JSON Files to Compare")
+                                .pick_files()
+                                .await
+                                .map(|files| files.iter().map(|f| f.path().to_path_buf()).collect())
+                                .unwrap_or_default()
+                        }
+                    },
+                    Message::CompareFilesSelected,
+                )
+            }
+            Message::CompareFilesSelected(mut paths) => {
+                match (paths.len(), &self.doc.current_file) {
+                    (0, _) => Task::none(),
+                    (1, Some(current)) => {
+                        let left = current.clone();
+                        self.start_compare(left, paths.remove(0))
+                    }
+                    (1, None) => {
+                        self.doc.status = "✗ Select two files to compare".to_string();
+                        Task::none()
+                    }
+                    _ => {
+                        let right = paths.remove(1);
+                        self.start_compare(paths.remove(0), right)
+                    }
+                }
+            }
+            Message::SetArrayMatching(matching) => {
+                if let Some(compare) = &mut self.doc.compare {
+                    if compare.matching == matching {
+                        return Task::none();
+                    }
+                    compare.matching = matching;
+                    self.doc.tree = Some(compare.recompute());
+                    self.doc.selected_node = None;
+                    self.clear_search();
+                    return self.show_compare_result();
+                }
+                Task::none()
+            }
+            Message::CompareMatchKeyChanged(input) => {
+                if let Some(compare) = &mut self.doc.compare {
+                    compare.match_key_input = input;
+                }
+                Task::none()
+            }
+            Message::ExitCompare => {
+                match self.doc.compare.take() {
+                    Some(compare) => self.update(Message::FileSelected(Some(compare.right_path))),
+                    None => Task::none(),
+                }
+            }
+            Message::ApplyPatchFile => {
+                if self.doc.tree.is_none() || self.doc.compare.is_some() {
+                    self.doc.status = "✗ Open a document (outside compare mode) to apply a patch".to_string();
+                    return Task::none();
+                }
+                Task::perform(
+                    async {
+                        let file = rfd::AsyncFileDialog::new()
+                            .add_filter("JSON", &["json"])
+                            .add_filter("All Files", &["*"])
+                            .set_title("Apply JSON Patch / Merge Patch")
+                            .pick_file()
+                            .await;
+                        file.map(|f| f.path().to_path_buf())
+                    },
+                    Message::PatchFileSelected,
+                )
+            }
+            Message::PatchFileSelected(path_option) => {
+                match path_option {
+                    Some(path) => self.apply_patch_file(path),
+                    None => Task::none(),
+                }
+            }
+            Message::RevertPatch => {
+                if let Some(preview) = self.doc.patch_preview.take() {
+                    self.doc.tree = Some(preview.original);
+                    self.doc.selected_node = None;
+                    self.doc.status = "✓ Patches reverted".to_string();
+                    self.document_changed();
+                    if let Some(tree) = &self.doc.tree {
+                        self.doc.flat_rows = Self::flatten_visible_nodes(tree);
+                    }
+                    return self.run_search();
+                }
+                Task::none()
+            }
+            Message::GeneratePatch(format) => {
+                let patch = match (&self.doc.compare, &self.doc.patch_preview, &self.doc.tree) {
+                    (Some(compare), _, _) => patch::generate_patch(&compare.left, &compare.right, format),
+                    (None, Some(preview), Some(tree)) => patch::generate_patch(&preview.original, tree, format),
+                    _ => {
+                        self.doc.status = "✗ Nothing to generate a patch from: apply a patch or compare two files".to_string();
+                        return Task::none();
+                    }
+                };
+                let contents = json_export::value_to_json_string_formatted(&patch, &self.config.format);
+                let file_name = format.file_name();
+                Task::perform(
+                    async move {
+                        let file_handle = rfd::AsyncFileDialog::new()
+                            .add_filter("JSON", &["json"])
+                            .set_file_name(file_name)
+                            .save_file()
+                            .await;
+                        if let Some(handle) = file_handle {
+                            let _ = fs::write(handle.path(), contents);
+                        }
+                    },
+                    |_| Message::NoOp
+                )
+            }
+            Message::SaveDocumentAs => {
+                let Some(tree) = &self.doc.tree else {
+                    return Task::none();
+                };
+                if self.doc.compare.is_some() {
+                    self.doc.status = "✗ Exit compare mode before saving".to_string();
+                    return Task::none();
+                }
+                let contents = json_export::node_to_json_string_formatted(tree, tree.root_index(), &self.config.format);
+                let file_name = self.doc.current_file.as_ref()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+                    .or_else(|| self.doc.untitled_name.clone())
+                    .unwrap_or_else(|| "document.json".to_string());
+                Task::perform(
+                    async move {
+                        let file_handle = rfd::AsyncFileDialog::new()
+                            .add_filter("JSON", &["json"])
+                            .set_file_name(file_name)
+                            .save_file()
+                            .await;
+                        if let Some(handle) = file_handle {
+                            let _ = fs::write(handle.path(), contents);
+                        }
+                    },
+                    |_| Message::NoOp
+                )
+            }
+            Message::AttachSchema => {
+                Task::perform(
+                    async {
+                        let file = rfd::AsyncFileDialog::new()
+                            .add_filter("JSON Schema", &["json"])
+                            .add_filter("All Files", &["*"])
+                            .set_title("Attach JSON Schema")
+                            .pick_file()
+                            .await;
+                        file.map(|f| f.path().to_path_buf())
+                    },
+                    Message::SchemaFileSelected,
+                )
+            }
+            Message::SchemaFileSelected(path_option) => {
+                if let Some(path) = path_option {
+                    self.attach_schema(path);
+                }
+                Task::none()
+            }
+            Message::DetachSchema => {
+                self.doc.schema = None;
+                self.doc.status = "✓ Schema detached".to_string();
+                Task::none()
+            }
+            Message::ToggleProblemsPanel => {
+                if let Some(schema) = &mut self.doc.schema {
+                    schema.show_problems = !schema.show_problems;
+                }
+                Task::none()
+            }
+            Message::ToggleBookmark => {
+                self.context_menu_state = None;
+                let (Some(node_index), Some(tree)) = (self.doc.selected_node, &self.doc.tree) else {
+                    return Task::none();
+                };
+                if self.doc.current_file.is_none() || self.doc.compare.is_some() {
+                    return Task::none();
+                }
+                match self.doc.bookmark_nodes.get(&node_index) {
+                    Some(&position) => {
+                        let removed = self.doc.bookmarks.remove(position);
+                        self.doc.status = format!("✓ Removed bookmark {}", removed.display_name());
+                    }
+                    None => {
+                        let bookmark = Bookmark::new(tree.pointer_to_node(node_index));
+                        self.doc.status = format!("✓ Bookmarked {}", bookmark.display_name());
+                        self.doc.bookmarks.push(bookmark);
+                    }
+                }
+                self.save_bookmarks();
+                Task::none()
+            }
+            Message::NavigateBack => self.navigate_history(false),
+            Message::NavigateForward => self.navigate_history(true),
+            Message::NextBookmark => self.step_bookmark(true),
+            Message::PrevBookmark => self.step_bookmark(false),
+            Message::JumpToBookmark(position) => {
+                let target = self.doc.bookmark_nodes.iter()
+                    .find(|&(_, &p)| p == position)
+                    .map(|(&node, _)| node);
+                match target {
+                    Some(node_index) => self.jump_to_node(node_index),
+                    None => {
+                        if let Some(bookmark) = self.doc.bookmarks.get(position) {
+                            self.doc.status = format!("✗ Bookmarked node no longer exists: {}", bookmark.pointer);
+                        }
+                        Task::none()
+                    }
+                }
+            }
+            Message::RemoveBookmark(position) => {
+                if position < self.doc.bookmarks.len() {
+                    self.doc.bookmarks.remove(position);
+                    self.save_bookmarks();
+                }
+                Task::none()
+            }
+            Message::BookmarkLabelChanged(position, label) => {
+                if let Some(bookmark) = self.doc.bookmarks.get_mut(position) {
+                    bookmark.label = label;
+                    self.doc.bookmarks_unsaved = true;
+                }
+                Task::none()
+            }
+            Message::BookmarkLabelSubmitted => {
+                self.flush_bookmarks();
+                Task::none()
+            }
+            Message::ToggleBookmarksPanel => {
+                self.flush_bookmarks();
+                self.show_bookmarks = !self.show_bookmarks;
+                Task::none()
+            }
+            Message::ToggleStatsPanel => {
+                self.show_stats = !self.show_stats;
+                self.refresh_stats();
+                Task::none()
+            }
+            Message::ToggleFormatOptions => {
+                self.show_format_options = !self.show_format_options;
+                Task::none()
+            }
+            Message::SetFormatOptions(options) => {
+                self.config.format = options;
+                let _ = self.config.save();
+                Task::none()
+            }
+            Message::SetStatsSort(sort) => {
+                if let Some(stats) = &mut self.doc.stats {
+                    stats.sort = sort;
+                }
+                Task::none()
+            }
+            Message::JumpToNode(node_index) => {
+                self.breadcrumb_menu = None;
+                self.jump_to_node(node_index)
+            }
+            Message::ShowBreadcrumbMenu(parent, x) => {
+                self.breadcrumb_menu = match self.breadcrumb_menu {
+                    Some((open, _)) if open == parent => None,
+                    _ => Some((parent, x)),
+                };
+                Task::none()
+            }
+            Message::HideBreadcrumbMenu => {
+                self.breadcrumb_menu = None;
+                Task::none()
+            }
+            Message::ToggleCommandPalette => {
+                if self.command_palette.take().is_some() {
+                    return Task::none();
+                }
+                self.goto_path = None;
+                self.context_menu_state = None;
+                let entries = palette::entries(&self.keymap, &self.config.recent_files);
+                self.command_palette = Some(CommandPalette::new(entries));
+                operate(focusable::focus(self.command_palette_input_id.clone()))
+            }
+            Message::CommandPaletteChanged(query) => {
+                if let Some(palette) = &mut self.command_palette {
+                    palette.set_query(query);
+                }
+                Task::none()
+            }
+            Message::CommandPaletteMove(down) => {
+                if let Some(palette) = &mut self.command_palette {
+                    palette.move_selection(down);
+                }
+                Task::none()
+            }
+            Message::CommandPaletteSubmit => {
+                match &self.command_palette {
+                    Some(palette) => self.update(Message::CommandPaletteRun(palette.selected)),
+                    None => Task::none(),
+                }
+            }
+            Message::CommandPaletteRun(position) => {
+                match self.command_palette.take().and_then(|palette| palette.message(position)) {
+                    Some(message) => self.update(message),
+                    None => Task::none(),
+                }
+            }
+            Message::ToggleGotoPath => {
+                if self.goto_path.take().is_some() {
+                    return Task::none();
+                }
+                self.command_palette = None;
+                let Some(tree) = &self.doc.tree else {
+                    return Task::none();
+                };
+                let mut dialog = GotoPathDialog::default();
+                dialog.set_input(tree, String::new());
+                self.goto_path = Some(dialog);
+                operate(focusable::focus(self.goto_path_input_id.clone()))
+            }
+            Message::GotoPathChanged(input) => {
+                if let (Some(dialog), Some(tree)) = (&mut self.goto_path, &self.doc.tree) {
+                    dialog.set_input(tree, input);
+                }
+                Task::none()
+            }
+            Message::GotoPathComplete(input) => {
+                if let (Some(dialog), Some(tree)) = (&mut self.goto_path, &self.doc.tree) {
+                    dialog.set_input(tree, input);
+                }
+                operate(focusable::focus(self.goto_path_input_id.clone()))
+            }
+            Message::CompleteGotoPath => {
+                match self.goto_path.as_ref().and_then(|dialog| dialog.suggestions.first()) {
+                    Some(first) => self.update(Message::GotoPathComplete(first.clone())),
+                    None => Task::none(),
+                }
+            }
+            Message::Dismiss => {
+                if self.show_help {
+                    self.update(Message::ToggleHelp)
+                } else if self.show_format_options {
+                    self.update(Message::ToggleFormatOptions)
+                } else if self.context_menu_state.is_some() {
+                    self.update(Message::HideContextMenu)
+                } else if self.breadcrumb_menu.is_some() {
+                    self.update(Message::HideBreadcrumbMenu)
+                } else {
+                    self.update(Message::ClearSearch)
+                }
+            }
+            Message::GotoPathSubmit => {
+                let (Some(dialog), Some(tree)) = (&mut self.goto_path, &self.doc.tree) else {
+                    return Task::none();
+                };
+                match goto_path::resolve_path(tree, &dialog.input) {
+                    Ok(node_index) => {
+                        self.goto_path = None;
+                        self.jump_to_node(node_index)
+                    }
+                    Err(e) => {
+                        dialog.error = Some(e);
+                        Task::none()
+                    }
+                }
+            }
+            Message::GotoPath(path) => {
+                let Some(tree) = &self.doc.tree else {
+                    return Task::none();
+                };
+                match goto_path::resolve_path(tree, &path) {
+                    Ok(node_index) => self.jump_to_node(node_index),
+                    Err(e) => {
+                        self.doc.status = format!("✗ {}", e);
+                        Task::none()
+                    }
+                }
+            }
+            Message::ShowTable(node_index) => {
+                self.context_menu_state = None;
+                self.context_submenu = ContextSubmenu::None;
+                self.doc.table = self.doc.tree.as_ref().and_then(|tree| TableView::new(tree, node_index));
+                let offset = AbsoluteOffset { x: Some(0.0), y: Some(0.0) };
+                operate(scroll_to(self.table_scrollable_id.clone(), offset))
+            }
+            Message::CloseTable => {
+                let node_index = self.doc.table.take().map(|t| t.node_index);
+                match node_index {
+                    Some(node_index) => self.jump_to_node(node_index),
+                    None => Task::none(),
+                }
+            }
+            Message::TableScrolled(viewport) => {
+                if let Some(table) = &mut self.doc.table {
+                    table.scroll_offset = viewport.absolute_offset().y;
+                    table.viewport_height = viewport.bounds().height;
+                }
+                Task::none()
+            }
+            Message::SortTable(column) => {
+                if let (Some(table), Some(tree)) = (&mut self.doc.table, &self.doc.tree) {
+                    table.toggle_sort(tree, column);
+                }
+                Task::none()
+            }
+            Message::TableResizeStart(column) => {
+                if let Some(table) = &mut self.doc.table
+                    && let Some(col) = table.columns.get(column)
+                {
+                    table.resizing = Some((column, col.width, None));
+                }
+                Task::none()
+            }
+            Message::TableResizeMove(x) => {
+                if let Some(table) = &mut self.doc.table {
+                    table.drag_resize(x);
+                }
+                Task::none()
+            }
+            Message::TableResizeEnd => {
+                if let Some(table) = &mut self.doc.table {
+                    table.resizing = None;
+                }
+                Task::none()
+            }
+            Message::OpenTableCell(node_index) => {
+                self.doc.table = None;
+                if let Some(tree) = &mut self.doc.tree {
+                    tree.set_expanded(node_index, true);
+                }
+                self.jump_to_node(node_index)
+            }
+            Message::JumpToProblem(problem_index) => {
+                let target = match (&self.doc.schema, &self.doc.tree) {
+                    (Some(schema), Some(tree)) => schema.violations.get(problem_index)
+                        .and_then(|v| parser::resolve_closest(tree, &v.pointer)),
+                    _ => None,
+                };
+                match target {
+                    Some(node_index) => self.jump_to_node(node_index),
+                    None => Task::none(),
+                }
+            }
+        }
+    }
+
+    /// Recursively set expanded state for a node and all its children
+    fn set_expanded_recursive(tree: &mut JsonTree, node_index: usize, expanded: bool) {
+        tree.set_expanded(node_index, expanded);
+
+        if let Some(node) = tree.get_node(node_index) {
+            let children = node.children.clone();
+            for child_index in children {
+                Self::set_expanded_recursive(tree, child_index, expanded);
+            }
+        }
+    }
+
+    /// Reset the search query and all results
+    fn clear_search(&mut self) {
+        self.doc.search_query.clear();
+        self.doc.search_results.clear();
+        self.doc.search_result_index = None;
+        self.doc.search_matches.clear();
+        self.doc.search_regex_error = None;
+    }
+
+    /// Move to the next/previous search result.
+    ///
+    /// In compare mode with an empty search query, this steps through the
+    /// differences instead, so Enter / Cmd+G navigate changes.
+    fn step_results(&mut self, forward: bool) -> Task<Message> {
+        if self.doc.search_query.is_empty()
+            && let Some(compare) = &mut self.doc.compare
+        {
+            if compare.changes.is_empty() {
+                return Task::none();
+            }
+            let new_index = step_result_index(compare.change_index, compare.changes.len(), forward);
+            compare.change_index = Some(new_index);
+            let node_index = compare.changes[new_index];
+            return self.jump_to_node(node_index);
+        }
+
+        if self.doc.search_results.is_empty() {
+            return Task::none();
+        }
+
+        let new_index = step_result_index(self.doc.search_result_index, self.doc.search_results.len(), forward);
+        self.doc.search_result_index = Some(new_index);
+
+        let node_index = self.doc.search_results[new_index];
+        self.jump_to_node(node_index)
+    }
+
+    /// Select a node and reveal it, recording the move for Back/Forward
+    fn jump_to_node(&mut self, node_index: usize) -> Task<Message> {
+        self.record_navigation(node_index);
+        self.doc.selected_node = Some(node_index);
+        self.reveal_node(node_index)
+    }
+
+    /// Add a move from the selected node to `node_index` to the history
+    fn record_navigation(&mut self, node_index: usize) {
+        let Some(tree) = &self.doc.tree else {
+            return;
+        };
+        let from = self.doc.selected_node
+            .filter(|&selected| tree.get_node(selected).is_some())
+            .map(|selected| tree.pointer_to_node(selected));
+        self.doc.history.visit(from, tree.pointer_to_node(node_index));
+    }
+
+    /// Select the previous or next node in the history. Nodes that no longer
+    /// exist fall back to their closest surviving ancestor.
+    fn navigate_history(&mut self, forward: bool) -> Task<Message> {
+        let Some(tree) = &self.doc.tree else {
+            return Task::none();
+        };
+        let pointer = if forward { self.doc.history.forward() } else { self.doc.history.back() };
+        match pointer.and_then(|pointer| parser::resolve_closest(tree, pointer)) {
+            Some(node_index) => {
+                self.doc.selected_node = Some(node_index);
+                self.reveal_node(node_index)
+            }
+            None => Task::none(),
+        }
+    }
+
+    /// Expand a node's ancestors, rebuild the rows and scroll it into view
+    fn reveal_node(&mut self, node_index: usize) -> Task<Message> {
+        self.expand_to_node(node_index);
+
+        if let Some(tree) = &self.doc.tree {
+            self.doc.flat_rows = Self::flatten_visible_nodes(tree);
+        }
+
+        self.scroll_to_node(node_index)
+    }
+
+    /// Load two files and switch to compare mode showing their merged diff
+    fn start_compare(&mut self, left_path: PathBuf, right_path: PathBuf) -> Task<Message> {
+        let start = Instant::now();
+        let (left, right) = match (load_tree(&left_path), load_tree(&right_path)) {
+            (Ok(left), Ok(right)) => (left, right),
+            (Err(e), _) | (_, Err(e)) => {
+                self.doc.status = format!("✗ Cannot compare {}: {}", e.filename, e.message);
+                return Task::none();
+            }
+        };
+
+        let (session, merged) = CompareSession::new(left_path, left, right_path.clone(), right);
+        self.doc.tree = Some(merged);
+        self.doc.current_file = Some(right_path);
+        self.doc.load_time = Some(start.elapsed());
+        self.doc.parse_error = None;
+        self.doc.selected_node = None;
+        self.doc.compare = Some(session);
+        self.doc.patch_preview = None;
+        self.doc.search_scope = None;
+        self.document_changed();
+        self.clear_search();
+        self.show_compare_result()
+    }
+
+    /// Expand the tree to the differences and update the status line
+    fn show_compare_result(&mut self) -> Task<Message> {
+        /// Expanding to every change is costly for huge diffs; beyond this
+        /// only the first change is revealed and the rest via navigation.
+        const MAX_AUTO_EXPANDED_CHANGES: usize = 500;
+
+        let Some(compare) = &self.doc.compare else {
+            return Task::none();
+        };
+        let to_expand: Vec<usize> = if compare.changes.len() <= MAX_AUTO_EXPANDED_CHANGES {
+            compare.changes.clone()
+        } else {
+            compare.changes.iter().take(1).cloned().collect()
+        };
+
+        self.doc.status = if compare.changes.is_empty() {
+            "✓ No differences".to_string()
+        } else {
+            format!(
+                "Δ {} differences ({} added, {} removed, {} changed, {} moved)",
+                compare.changes.len(),
+                compare.count(DiffKind::Added),
+                compare.count(DiffKind::Removed),
+                compare.count(DiffKind::Changed),
+                compare.count(DiffKind::Moved),
+            )
+        };
+
+        if let Some(tree) = &mut self.doc.tree {
+            tree.set_expanded(tree.root_index(), true);
+        }
+        for node_index in to_expand {
+            self.expand_to_node(node_index);
+        }
+        if let Some(tree) = &self.doc.tree {
+            self.doc.flat_rows = Self::flatten_visible_nodes(tree);
+        }
+        Task::none()
+    }
+
+    /// Apply a patch file to the current document and preview the result
+    fn apply_patch_file(&mut self, path: PathBuf) -> Task<Message> {
+        let filename = path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        let Some(tree) = &self.doc.tree else {
+            return Task::none();
+        };
+
+        let patch = match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).map_err(|e| e.to_string()))
+        {
+            Ok(patch) => patch,
+            Err(e) => {
+                self.doc.status = format!("✗ Cannot read patch {}: {}", filename, e);
+                return Task::none();
+            }
+        };
+
+        let outcome = match patch::apply_patch_to_tree(tree, &patch) {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                self.doc.status = format!("✗ Patch {} not applied: {}", filename, e);
+                return Task::none();
+            }
+        };
+
+        let original = self.doc.tree.replace(outcome.tree);
+        let preview = self.doc.patch_preview.get_or_insert_with(|| PatchPreview {
+            original: original.expect("tree checked above"),
+            applied: Vec::new(),
+            affected: HashSet::new(),
+        });
+        preview.applied.push(path);
+        preview.affected = outcome.affected.iter().cloned().collect();
+        self.doc.selected_node = None;
+        self.doc.status = format!(
+            "✓ Applied {} ({} operations, {} nodes affected)",
+            filename,
+            outcome.operations,
+            outcome.affected.len(),
+        );
+        self.document_changed();
+
+        if let Some(tree) = &mut self.doc.tree {
+            tree.set_expanded(tree.root_index(), true);
+        }
+        for &node_index in &outcome.affected {
+            self.expand_to_node(node_index);
+        }
+        if let Some(tree) = &self.doc.tree {
+            self.doc.flat_rows = Self::flatten_visible_nodes(tree);
+        }
+        self.run_search()
+    }
+
+    /// Load a schema file, attach it and validate the current document
+    fn attach_schema(&mut self, path: PathBuf) {
+        let filename = path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        match SchemaSession::load(path) {
+            Ok(mut session) => {
+                session.show_problems = true;
+                self.doc.schema = Some(session);
+                self.revalidate();
+                if let Some(schema) = &self.doc.schema {
+                    self.doc.status = match &schema.error {
+                        Some(e) => format!("✗ Schema {} cannot be used: {}", filename, e),
+                        None => format!("✓ Schema {} attached ({} problems)", filename, schema.violations.len()),
+                    };
+                }
+            }
+            Err(e) => {
+                self.doc.status = format!("✗ Cannot read schema {}: {}", filename, e);
+            }
+        }
+    }
+
+    /// Attach the schema named by the document's `$schema` key, if it is a local file
+    fn attach_referenced_schema(&mut self) {
+        let (Some(tree), Some(path)) = (&self.doc.tree, &self.doc.current_file) else {
+            return;
+        };
+        if let Some(schema_path) = schema::referenced_schema_path(tree, path)
+            && self.doc.schema.as_ref().is_none_or(|s| s.path != schema_path)
+        {
+            self.attach_schema(schema_path);
+        }
+    }
+
+    /// Refresh everything derived from the document after it was replaced
+    fn document_changed(&mut self) {
+        self.doc.table = None;
+        self.doc.follow_analysis_pending = false;
+        self.revalidate();
+        self.refresh_stats();
+        self.refresh_bookmarks();
+    }
+
+    /// Find the bookmarked nodes in the current tree (none in compare mode)
+    fn refresh_bookmarks(&mut self) {
+        self.doc.bookmark_nodes = match (&self.doc.tree, &self.doc.compare) {
+            (Some(tree), None) => bookmarks::resolve(tree, &self.doc.bookmarks),
+            _ => HashMap::new(),
+        };
+    }
+
+    /// Persist the open file's bookmarks after they changed, and share them
+    /// with other tabs showing the same file so neither overwrites the other
+    fn save_bookmarks(&mut self) {
+        self.refresh_bookmarks();
+        self.doc.bookmarks_unsaved = false;
+        let Some(path) = &self.doc.current_file else {
+            return;
+        };
+        if let Err(e) = bookmarks::save_for(path, &self.doc.bookmarks) {
+            self.doc.status = format!("✗ {}", e);
+        }
+
+        for (i, doc) in self.tabs.iter_mut().enumerate() {
+            if i == self.active_tab || !doc.current_file.as_deref().is_some_and(|other| bookmarks::same_file(path, other)) {
+                continue;
+            }
+            doc.bookmarks = self.doc.bookmarks.clone();
+            doc.bookmark_nodes = match (&doc.tree, &doc.compare) {
+                (Some(tree), None) => bookmarks::resolve(tree, &doc.bookmarks),
+                _ => HashMap::new(),
+            };
+        }
+    }
+
+    /// Write label edits that haven't been submitted yet, before the label
+    /// field goes away
+    fn flush_bookmarks(&mut self) {
+        if self.doc.bookmarks_unsaved {
+            self.save_bookmarks();
+        }
+    }
+
+    /// Select the next or previous bookmark (in list order) that exists in the tree
+    fn step_bookmark(&mut self, forward: bool) -> Task<Message> {
+        let mut targets: Vec<(usize, usize)> = self.doc.bookmark_nodes.iter()
+            .map(|(&node, &position)| (position, node))
+            .collect();
+        if targets.is_empty() {
+            return Task::none();
+        }
+        targets.sort_unstable();
+        let current = self.doc.selected_node
+            .and_then(|selected| targets.iter().position(|&(_, node)| node == selected));
+        let (_, node_index) = targets[step_result_index(current, targets.len(), forward)];
+        self.jump_to_node(node_index)
+    }
+
+    /// Re-run schema validation after the document changed.
+    ///
+    /// In compare mode the tree is a merged diff, so there is nothing to validate.
+    fn revalidate(&mut self) {
+        let Some(schema) = &mut self.doc.schema else {
+            return;
+        };
+        match (&self.doc.tree, &self.doc.compare) {
+            (Some(tree), None) => schema.validate(tree),
+            _ => schema.clear(),
+        }
+    }
+
+    /// Recompute document statistics after the document changed.
+    ///
+    /// Large documents take a moment to measure, so this only happens while
+    /// the panel is open.
+    fn refresh_stats(&mut self) {
+        let sort = self.doc.stats.as_ref().map_or(HeavySort::Bytes, |s| s.sort);
+        self.doc.stats = match (&self.doc.tree, &self.doc.compare) {
+            (Some(tree), None) if self.show_stats => {
+                let mut stats = DocumentStats::compute(tree);
+                stats.sort = sort;
+                Some(stats)
+            }
+            _ => None,
+        };
+    }
+
+    /// All open documents in tab order
+    fn documents(&self) -> impl Iterator<Item = &Document> {
+        (0..self.tabs.len()).map(|i| if i == self.active_tab { &self.doc } else { &self.tabs[i] })
+    }
+
+    /// Add an empty tab after the active one and switch to it
+    fn open_tab(&mut self) {
+        let index = self.active_tab + 1;
+        self.tabs.insert(index, Document::default());
+        let _ = self.activate_tab(index);
+    }
+
+    /// Swap the document of tab `index` into `doc` and restore its view
+    fn activate_tab(&mut self, index: usize) -> Task<Message> {
+        if index == self.active_tab || index >= self.tabs.len() {
+            return Task::none();
+        }
+        self.flush_bookmarks();
+        std::mem::swap(&mut self.doc, &mut self.tabs[self.active_tab]);
+        self.active_tab = index;
+        std::mem::swap(&mut self.doc, &mut self.tabs[index]);
+
+        // Node indices in menus and dialogs belong to the previous document
+        self.context_menu_state = None;
+        self.context_submenu = ContextSubmenu::None;
+        self.csv_export = None;
+        self.breadcrumb_menu = None;
+        if self.show_stats && self.doc.stats.is_none() {
+            self.refresh_stats();
+        }
+
+        let offset = AbsoluteOffset { x: None, y: Some(self.doc.scroll_offset) };
+        let scroll = operate(scroll_to(self.tree_scrollable_id.clone(), offset));
+        // Catch up on changes that arrived while the tab was in the background
+        let catch_up = match &self.doc.current_file {
+            Some(path) if self.doc.file_changed_on_disk => self.update(Message::FileChangedOnDisk(path.clone())),
+            _ => Task::none(),
+        };
+        Task::batch([scroll, catch_up])
+    }
+
+    /// Close a tab; closing the last one leaves an empty tab behind
+    fn close_tab(&mut self, index: usize) -> Task<Message> {
+        if index >= self.tabs.len() {
+            return Task::none();
+        }
+        self.flush_bookmarks();
+        if self.tabs.len() == 1 {
+            self.doc = Document::default();
+            self.context_menu_state = None;
+            self.csv_export = None;
+            return Task::none();
+        }
+        if index != self.active_tab {
+            self.tabs.remove(index);
+            if index < self.active_tab {
+                self.active_tab -= 1;
+            }
+            return Task::none();
+        }
+
+        // Drop the active document, then bring in its right neighbour (or left at the end)
+        self.tabs.remove(index);
+        let next = index.min(self.tabs.len() - 1);
+        self.doc = std::mem::take(&mut self.tabs[next]);
+        self.active_tab = next;
+        let offset = AbsoluteOffset { x: None, y: Some(self.doc.scroll_offset) };
+        operate(scroll_to(self.tree_scrollable_id.clone(), offset))
+    }
+
+    /// Put a successfully opened file at the top of the recent files list
+    fn remember_recent_file(&mut self, path: &Path) {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let size = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+        self.config.add_recent_file(path, size, unix_now());
+        let _ = self.config.save();
+        menu::update_recent_files_menu(&self.config.recent_files);
+    }
+
+    /// Apply a restored tab's expansion, selection, search and scroll
+    /// position to the freshly loaded active document
+    fn restore_view(&mut self, tab: &SessionTab) {
+        let Some(tree) = &mut self.doc.tree else {
+            return;
+        };
+        tab.view.restore_expanded(tree);
+        self.doc.selected_node = tab.view.selected(tree);
+        let current_result = tab.view.current_result(tree);
+        self.doc.search_query = tab.search_query.clone();
+        self.doc.search_case_sensitive = tab.search_case_sensitive;
+        self.doc.search_use_regex = tab.search_use_regex;
+        self.doc.search_filter = tab.search_filter;
+        self.doc.search_scope = tab.search_scope.clone();
+        self.doc.scroll_offset = tab.view.scroll_offset;
+        self.refresh_search(current_result);
+        self.doc.flat_rows = Self::flatten_visible_nodes(self.doc.tree.as_ref().unwrap());
+    }
+
+    /// Move a tab to another position, keeping the active document active
+    fn move_tab(&mut self, from: usize, to: usize) {
+        if from >= self.tabs.len() || to >= self.tabs.len() {
+            return;
+        }
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+        self.active_tab = document::index_after_move(self.active_tab, from, to);
+    }
+
+    /// Re-read the open file after it changed on disk.
+    ///
+    /// Expanded nodes, selection, scroll position and the focused search
+    /// result are carried over by path. If the file doesn't parse (usually
+    /// because it is still being written) the current document is kept and
+    /// the next change event tries again.
+    fn reload_file(&mut self) -> Task<Message> {
+        let (Some(path), Some(tree)) = (self.doc.current_file.clone(), &self.doc.tree) else {
+            return Task::none();
+        };
+        if self.doc.compare.is_some() {
+            return Task::none();
+        }
+        let current_result = self.doc.search_result_index.and_then(|i| self.doc.search_results.get(i).copied());
+        let snapshot = ViewSnapshot::capture(tree, self.doc.selected_node, current_result, self.doc.scroll_offset);
+
+        let start = Instant::now();
+        let (mut tree, consumed) = match load_document(&path, self.doc.following) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                self.doc.status = format!("✗ Reload failed: {}", e.message);
+                return Task::none();
+            }
+        };
+        snapshot.restore_expanded(&mut tree);
+        self.doc.selected_node = snapshot.selected(&tree);
+        let current_result = snapshot.current_result(&tree);
+
+        let filename = path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        self.doc.status = format!("✓ Reloaded {} ({} nodes)", filename, tree.node_count());
+        self.doc.tree = Some(tree);
+        self.doc.load_time = Some(start.elapsed());
+        self.doc.loaded_stamp = file_stamp(&path);
+        self.doc.file_changed_on_disk = false;
+        if self.doc.follower.is_some() {
+            self.doc.follower = Some(LogFollower::new(path.clone(), consumed as u64));
+        }
+        self.doc.patch_preview = None;
+        self.document_changed();
+        self.refresh_search(current_result);
+        self.doc.flat_rows = Self::flatten_visible_nodes(self.doc.tree.as_ref().unwrap());
+
+        let offset = AbsoluteOffset { x: None, y: Some(snapshot.scroll_offset) };
+        operate(scroll_to(self.tree_scrollable_id.clone(), offset))
+    }
+
+    /// Append the lines written to the open JSON Lines file since the last read.
+    ///
+    /// Only the new lines are parsed, added to the tree, flattened and
+    /// searched. Validation and statistics cover the whole document, so they
+    /// are left to `RefreshFollowAnalysis`. The view sticks to the bottom if
+    /// it was already there.
+    fn follow_file(&mut self) -> Task<Message> {
+        let (Some(follower), Some(tree)) = (&mut self.doc.follower, &mut self.doc.tree) else {
+            return Task::none();
+        };
+        let (values, invalid) = match follower.read_new() {
+            Ok(FollowUpdate::Appended { values, invalid }) => (values, invalid),
+            Ok(FollowUpdate::Truncated) => return self.reload_file(),
+            Err(e) => {
+                self.doc.status = format!("✗ Follow failed: {}", e);
+                return Task::none();
+            }
+        };
+        self.doc.loaded_stamp = self.doc.current_file.as_deref().and_then(file_stamp);
+        if values.is_empty() && invalid == 0 {
+            return Task::none();
+        }
+
+        let content_height = self.doc.flat_rows.len() as f32 * ROW_HEIGHT;
+        let at_bottom = self.doc.scroll_offset + self.viewport_height >= content_height - ROW_HEIGHT;
+
+        let first_new_node = tree.node_count();
+        let first_new_child = tree.get_node(tree.root_index()).map_or(0, |root| root.children.len());
+        for value in &values {
+            parser::append_root_element(tree, value);
+        }
+        Self::append_root_rows(tree, &mut self.doc.flat_rows, first_new_child);
+
+        if let Some(table) = &mut self.doc.table
+            && table.node_index == tree.root_index()
+        {
+            let new_rows = tree.get_node(table.node_index).map_or(&[][..], |root| &root.children[first_new_child..]);
+            if !table.append_rows(tree, new_rows) {
+                self.doc.table = None;
+            }
+        }
+
+        // New lines are root elements, so they're outside any scope (a scope
+        // on the root itself is kept as no scope)
+        if !self.doc.search_query.is_empty() && self.doc.search_regex_error.is_none() && self.doc.search_scope.is_none() {
+            let (results, _) = search::search_nodes_from(
+                tree,
+                first_new_node,
+                &self.doc.search_query,
+                self.doc.search_case_sensitive,
+                self.doc.search_use_regex,
+                self.doc.search_filter,
+            );
+            self.doc.search_matches.extend(results.iter().copied());
+            self.doc.search_results.extend(results);
+        }
+
+        self.doc.status = if invalid > 0 {
+            format!("✓ {} new lines, {} invalid skipped ({} nodes)", values.len(), invalid, tree.node_count())
+        } else {
+            format!("✓ {} new lines ({} nodes)", values.len(), tree.node_count())
+        };
+        self.doc.follow_analysis_pending = true;
+        self.refresh_bookmarks();
+
+        if at_bottom {
+            let bottom = (self.doc.flat_rows.len() as f32 * ROW_HEIGHT - self.viewport_height).max(0.0);
+            operate(scroll_to(self.tree_scrollable_id.clone(), AbsoluteOffset { x: None, y: Some(bottom) }))
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Re-run the active search on a new tree without moving the view,
+    /// keeping `current_result` focused if it is still a match
+    fn refresh_search(&mut self, current_result: Option<usize>) {
+        if self.doc.tree.is_none() || self.doc.search_query.is_empty() {
+            return;
+        }
+        let (results, error) = self.doc.find_matches();
+        self.doc.search_regex_error = error;
+        self.doc.search_result_index = current_result.and_then(|c| results.iter().position(|&r| r == c));
+        self.doc.search_matches = results.iter().cloned().collect();
+        self.doc.search_results = results;
+    }
+
+    /// Run search with current query and options
+    fn run_search(&mut self) -> Task<Message> {
+        if self.doc.search_query.is_empty() {
+            self.doc.search_results.clear();
+            self.doc.search_result_index = None;
+            self.doc.search_matches.clear();
+            self.doc.search_regex_error = None;
+            return Task::none();
+        }
+
+        if self.doc.tree.is_some() {
+            let (results, error) = self.doc.find_matches();
+
+            self.doc.search_regex_error = error;
+            self.doc.search_results = results;
+            self.doc.search_matches = self.doc.search_results.iter().cloned().collect();
+
+            if !self.doc.search_results.is_empty() {
+                self.doc.search_result_index = Some(0);
+                let target = self.doc.search_results[0];
                 self.expand_to_node(target);
-                self.flat_rows = Self::flatten_visible_nodes(self.tree.as_ref().unwrap());
+                self.doc.flat_rows = Self::flatten_visible_nodes(self.doc.tree.as_ref().unwrap());
                 return self.scroll_to_node(target);
             } else {
-                self.search_result_index = None;
-                self.flat_rows = Self::flatten_visible_nodes(tree);
+                self.doc.search_result_index = None;
+                self.doc.flat_rows = Self::flatten_visible_nodes(self.doc.tree.as_ref().unwrap());
             }
         }
 
@@ -1309,7 +2876,7 @@ impl App {
 
     /// Expand all ancestors of a node to make it visible
     fn expand_to_node(&mut self, target_index: usize) {
-        if let Some(tree) = &mut self.tree {
+        if let Some(tree) = &mut self.doc.tree {
             let path = tree.get_path_to_node(target_index);
             for &node_index in &path {
                 if node_index != target_index {
@@ -1319,9 +2886,53 @@ impl App {
         }
     }
 
+    /// Scroll just enough to bring a node's row into view (keyboard moves)
+    fn scroll_row_into_view(&mut self, node_index: usize) -> Task<Message> {
+        let Some(row_pos) = self.doc.flat_rows.iter().position(|r| r.node_index == node_index) else {
+            return Task::none();
+        };
+        let row_top = row_pos as f32 * ROW_HEIGHT;
+        let scroll_y = if row_top < self.doc.scroll_offset {
+            row_top
+        } else if row_top + ROW_HEIGHT > self.doc.scroll_offset + self.viewport_height {
+            row_top + ROW_HEIGHT - self.viewport_height
+        } else {
+            return Task::none();
+        };
+        // Update right away so held-down keys don't work from a stale offset
+        self.doc.scroll_offset = scroll_y.max(0.0);
+        let offset = AbsoluteOffset { x: None, y: Some(self.doc.scroll_offset) };
+        operate(scroll_to(self.tree_scrollable_id.clone(), offset))
+    }
+
+    /// The message for a key in the vim keymap, if it is one. `g` and `z`
+    /// wait for the second key of `gg`, `zc` and `zo`.
+    fn vim_key(&mut self, key: &str, shift: bool) -> Option<Message> {
+        let movement = match (self.pending_vim_key.take(), key, shift) {
+            (Some('g'), "g", false) => TreeMove::First,
+            (Some('z'), "c", false) => TreeMove::Collapse,
+            (Some('z'), "o", false) => TreeMove::Expand,
+            (_, "g", true) => TreeMove::Last,
+            (_, "j", false) => TreeMove::Down,
+            (_, "k", false) => TreeMove::Up,
+            (_, "h", false) => TreeMove::Left,
+            (_, "l", false) => TreeMove::Right,
+            (_, "g", false) => {
+                self.pending_vim_key = Some('g');
+                return Some(Message::NoOp);
+            }
+            (_, "z", false) => {
+                self.pending_vim_key = Some('z');
+                return Some(Message::NoOp);
+            }
+            _ => return None,
+        };
+        Some(Message::MoveSelection(movement))
+    }
+
     /// Calculate the scroll offset to make a node visible
     fn scroll_to_node(&self, target_index: usize) -> Task<Message> {
-        if let Some(row_pos) = self.flat_rows.iter().position(|r| r.node_index == target_index) {
+        if let Some(row_pos) = self.doc.flat_rows.iter().position(|r| r.node_index == target_index) {
             let target_offset = row_pos as f32 * ROW_HEIGHT;
             let center_offset = self.viewport_height / 2.0;
             let scroll_y = (target_offset - center_offset).max(0.0);
@@ -1334,14 +2945,85 @@ impl App {
         }
     }
 
-    /// Render the UI
-    fn view(&self) -> Element<'_, Message> {
+    /// Render the UI: the tab bar (with more than one tab) above the active
+    /// document, with the command palette over both
+    fn view(&self) -> Element<'_, Message> {
+        let colors = get_theme_colors(self.theme);
+        let document = self.view_document();
+        let content: Element<'_, Message> = if self.tabs.len() > 1 {
+            column![self.render_tab_bar(colors), document].into()
+        } else {
+            document
+        };
+        match &self.command_palette {
+            Some(palette) => stack![content, self.render_command_palette(palette, colors)].into(),
+            None => content,
+        }
+    }
+
+    /// Render the tab strip. Tabs are dragged to reorder them.
+    fn render_tab_bar<'a>(&self, colors: ThemeColors) -> Element<'a, Message> {
+        let tabs: Vec<Element<'a, Message>> = self.documents().enumerate()
+            .map(|(i, doc)| {
+                let active = i == self.active_tab;
+                let marker = if doc.file_changed_on_disk { " •" } else { "" };
+                let label = text(format!("{}{}", doc.title(), marker))
+                    .size(12)
+                    .color(if active { colors.text_primary } else { colors.text_secondary })
+                    .wrapping(text::Wrapping::None);
+                let close = button(text("×").size(12))
+                    .padding([0, 4])
+                    .style(move |_theme, status| button::Style {
+                        background: None,
+                        text_color: if status == ButtonStatus::Hovered { colors.text_primary } else { colors.text_secondary },
+                        ..Default::default()
+                    })
+                    .on_press(Message::CloseTab(i));
+
+                let tab = container(row![label, Space::new().width(Length::Fixed(6.0)), close].align_y(Center))
+                    .padding([4, 10])
+                    .max_width(220.0)
+                    .clip(true)
+                    .style(move |_theme| container::Style {
+                        background: Some(if active { colors.background } else { colors.toolbar_bg }.into()),
+                        border: Border {
+                            color: colors.btn_border_bottom,
+                            width: 1.0,
+                            radius: Radius::from(0.0),
+                        },
+                        ..Default::default()
+                    });
+
+                mouse_area(tab)
+                    .on_press(Message::TabDragStart(i))
+                    .on_enter(Message::TabDragOver(i))
+                    .interaction(Interaction::Pointer)
+                    .into()
+            })
+            .collect();
+
+        let bar = container(row(tabs).push(Space::new().width(Fill)))
+            .width(Fill)
+            .style(move |_theme| container::Style {
+                background: Some(colors.toolbar_bg.into()),
+                ..Default::default()
+            });
+
+        mouse_area(bar)
+            .on_release(Message::TabDragEnd)
+            .on_exit(Message::TabDragEnd)
+            .into()
+    }
+
+    /// Render the active document with its toolbar, panels and dialogs
+    fn view_document(&self) -> Element<'_, Message> {
         let colors = get_theme_colors(self.theme);
 
-        let tree_view: Element<'_, Message> = match &self.tree {
+        let tree_view: Element<'_, Message> = match &self.doc.tree {
+            Some(tree) if let Some(table) = &self.doc.table => self.render_table_view(table, tree, colors),
             Some(_tree) => {
-                let total_rows = self.flat_rows.len();
-                let first_visible = (self.scroll_offset / ROW_HEIGHT).floor() as usize;
+                let total_rows = self.doc.flat_rows.len();
+                let first_visible = (self.doc.scroll_offset / ROW_HEIGHT).floor() as usize;
                 let visible_count = (self.viewport_height / ROW_HEIGHT).ceil() as usize + 1;
 
                 let start = first_visible.saturating_sub(BUFFER_ROWS);
@@ -1354,7 +3036,7 @@ impl App {
                     elements.push(Space::new().height(Length::Fixed(top_offset)).into());
                 }
 
-                for flat_row in self.flat_rows.iter().skip(start).take(end - start) {
+                for flat_row in self.doc.flat_rows.iter().skip(start).take(end - start) {
                     elements.push(self.render_flat_row(flat_row));
                 }
 
@@ -1377,7 +3059,7 @@ impl App {
                     .into()
             }
             None => {
-                if let Some(ref error) = self.parse_error {
+                if let Some(ref error) = self.doc.parse_error {
                     self.render_error_screen(error, colors)
                 } else {
                     self.render_welcome_screen(colors)
@@ -1385,127 +3067,1011 @@ impl App {
             }
         };
 
-        if self.tree.is_some() {
-            let toolbar = self.render_toolbar(colors);
-            let status_bar = self.render_status_bar(colors);
+        if self.doc.tree.is_some() {
+            let toolbar = self.render_toolbar(colors);
+            let status_bar = self.render_status_bar(colors);
+
+            let tree_container = container(tree_view)
+                .width(Fill)
+                .height(Fill)
+                .style(move |_theme| container::Style {
+                    background: Some(colors.background.into()),
+                    ..Default::default()
+                });
+            let tree_container: Element<'_, Message> = match self.breadcrumb_menu {
+                Some((parent, x)) if self.doc.table.is_none() => {
+                    stack![tree_container, self.render_breadcrumb_menu(parent, x, colors)].into()
+                }
+                _ => tree_container.into(),
+            };
+
+            let mut sections: Vec<Element<'_, Message>> = vec![toolbar];
+            match (&self.doc.compare, &self.doc.patch_preview) {
+                (Some(compare), _) => sections.push(self.render_compare_bar(compare, colors)),
+                (None, Some(preview)) => sections.push(self.render_patch_bar(preview, colors)),
+                (None, None) => {}
+            }
+            if self.doc.file_changed_on_disk {
+                sections.push(self.render_reload_bar(colors));
+            }
+            if self.doc.table.is_none() {
+                sections.push(self.render_breadcrumb_bar(colors));
+            }
+            if self.show_bookmarks && self.doc.compare.is_none() {
+                sections.push(row![tree_container, self.render_bookmarks_panel(colors)].height(Fill).into());
+            } else {
+                sections.push(tree_container);
+            }
+            if let Some(schema) = &self.doc.schema
+                && schema.show_problems
+                && self.doc.compare.is_none()
+            {
+                sections.push(self.render_problems_panel(schema, colors));
+            }
+            if let (Some(stats), Some(tree)) = (&self.doc.stats, &self.doc.tree) {
+                sections.push(self.render_stats_panel(stats, tree, colors));
+            }
+            sections.push(status_bar);
+            let main_content: Element<'_, Message> = column(sections).into();
+
+            if self.cli_install_result.is_some() {
+                stack![main_content, self.render_cli_install_dialog(colors)].into()
+            } else if let Some(dialog) = &self.csv_export {
+                stack![main_content, self.render_csv_export_dialog(dialog, colors)].into()
+            } else if self.show_format_options {
+                stack![main_content, self.render_format_options_dialog(colors)].into()
+            } else if let Some(dialog) = &self.goto_path {
+                stack![main_content, self.render_goto_path_dialog(dialog, colors)].into()
+            } else if self.update_check_state != UpdateCheckState::None {
+                stack![main_content, self.render_update_dialog(colors)].into()
+            } else if self.show_help {
+                stack![main_content, self.render_help_overlay(colors)].into()
+            } else if self.context_menu_state.is_some() {
+                stack![main_content, self.render_context_menu(colors)].into()
+            } else {
+                main_content
+            }
+        } else if self.cli_install_result.is_some() {
+            stack![tree_view, self.render_cli_install_dialog(colors)].into()
+        } else if self.show_format_options {
+            stack![tree_view, self.render_format_options_dialog(colors)].into()
+        } else if self.update_check_state != UpdateCheckState::None {
+            stack![tree_view, self.render_update_dialog(colors)].into()
+        } else if self.show_help {
+            stack![tree_view, self.render_help_overlay(colors)].into()
+        } else {
+            tree_view
+        }
+    }
+
+    /// Render the toolbar with search controls
+    fn render_toolbar<'a>(&self, colors: ThemeColors) -> Element<'a, Message> {
+        let case_button = button(text("Aa").size(11))
+            .padding([4, 8])
+            .style(button_toggle_style_themed(self.doc.search_case_sensitive, colors))
+            .on_press(Message::ToggleCaseSensitive);
+
+        let regex_button = button(text(".*").size(11))
+            .padding([4, 8])
+            .style(button_toggle_style_themed(self.doc.search_use_regex, colors))
+            .on_press(Message::ToggleRegex);
+
+        // Keys/values toggles, then the value types (only while values are searched)
+        let filter = self.doc.search_filter;
+        let filter_button = |label: &'static str, on: bool, enabled: bool, toggled: SearchFilter| {
+            button(text(label).size(11))
+                .padding([4, 6])
+                .style(button_toggle_style_themed(on && enabled, colors))
+                .on_press_maybe(enabled.then_some(Message::SetSearchFilter(toggled)))
+        };
+        let filter_buttons = row![
+            filter_button("Keys", filter.keys, true, SearchFilter { keys: !filter.keys, ..filter }),
+            filter_button("Values", filter.values, true, SearchFilter { values: !filter.values, ..filter }),
+            Space::new().width(Length::Fixed(6.0)),
+            filter_button("str", filter.strings, filter.values, SearchFilter { strings: !filter.strings, ..filter }),
+            filter_button("num", filter.numbers, filter.values, SearchFilter { numbers: !filter.numbers, ..filter }),
+            filter_button("bool", filter.booleans, filter.values, SearchFilter { booleans: !filter.booleans, ..filter }),
+            filter_button("null", filter.nulls, filter.values, SearchFilter { nulls: !filter.nulls, ..filter }),
+        ]
+        .spacing(3)
+        .align_y(Center);
+
+        // Chip naming the subtree the search is limited to, with a clear button
+        let scope_chip: Element<'a, Message> = match &self.doc.search_scope {
+            Some(pointer) => {
+                let chars: Vec<char> = pointer.chars().collect();
+                let label = if chars.len() > 24 {
+                    format!("in …{}", chars[chars.len() - 23..].iter().collect::<String>())
+                } else {
+                    format!("in {}", pointer)
+                };
+                let chip = container(
+                    row![
+                        text(label).size(11).color(colors.text_primary),
+                        button(text("✕").size(10).color(colors.text_secondary))
+                            .padding([0, 4])
+                            .style(button::text)
+                            .on_press(Message::ClearSearchScope),
+                    ]
+                    .align_y(Center)
+                )
+                .padding([2, 6])
+                .style(move |_theme| container::Style {
+                    background: Some(colors.btn_active_bg.into()),
+                    border: Border {
+                        color: colors.btn_active_border,
+                        width: 1.0,
+                        radius: Radius::from(10.0),
+                    },
+                    ..Default::default()
+                });
+                row![chip, Space::new().width(Length::Fixed(6.0))].into()
+            }
+            None => Space::new().into(),
+        };
+
+        let search_input = text_input("Find...", &self.doc.search_query)
+            .id(self.search_input_id.clone())
+            .on_input(Message::SearchQueryChanged)
+            .on_submit(Message::SearchSubmit)
+            .padding(5)
+            .width(Length::Fixed(200.0));
+
+        let search_result_text = if let Some(ref error) = self.doc.search_regex_error {
+            error.clone()
+        } else if self.doc.search_results.is_empty() {
+            if self.doc.search_query.is_empty() {
+                String::new()
+            } else if self.doc.search_filter.is_default() {
+                "No matches".to_string()
+            } else {
+                "No matches (filtered)".to_string()
+            }
+        } else {
+            let current = self.doc.search_result_index.map(|i| i + 1).unwrap_or(0);
+            format!("{} / {}", current, self.doc.search_results.len())
+        };
+
+        let has_results = !self.doc.search_results.is_empty();
+
+        let prev_button = button(text("◂ Prev").size(11))
+            .padding([5, 12])
+            .style(button_3d_style_themed(colors));
+
+        let next_button = button(text("Next ▸").size(11))
+            .padding([5, 12])
+            .style(button_3d_style_themed(colors));
+
+        let prev_button = if has_results {
+            prev_button.on_press(Message::SearchPrev)
+        } else {
+            prev_button
+        };
+
+        let next_button = if has_results {
+            next_button.on_press(Message::SearchNext)
+        } else {
+            next_button
+        };
+
+        let theme_icon = match self.theme {
+            AppTheme::Dark => "◑",
+            AppTheme::Light => "◐",
+        };
+        let theme_button = button(text(theme_icon).size(16))
+            .padding([4, 10])
+            .style(button_3d_style_themed(colors))
+            .on_press(Message::ToggleTheme);
+
+        let back_button = button(text("←").size(11))
+            .padding([5, 8])
+            .style(button_3d_style_themed(colors))
+            .on_press_maybe(self.doc.history.can_go_back().then_some(Message::NavigateBack));
+        let forward_button = button(text("→").size(11))
+            .padding([5, 8])
+            .style(button_3d_style_themed(colors))
+            .on_press_maybe(self.doc.history.can_go_forward().then_some(Message::NavigateForward));
+
+        let mut controls = row![
+            back_button,
+            Space::new().width(Length::Fixed(3.0)),
+            forward_button,
+            Space::new().width(Length::Fixed(12.0)),
+            case_button,
+            Space::new().width(Length::Fixed(3.0)),
+            regex_button,
+            Space::new().width(Length::Fixed(8.0)),
+            filter_buttons,
+            Space::new().width(Length::Fixed(8.0)),
+            scope_chip,
+            search_input,
+            Space::new().width(Length::Fixed(10.0)),
+            prev_button,
+            Space::new().width(Length::Fixed(5.0)),
+            next_button,
+            Space::new().width(Length::Fixed(10.0)),
+            text(search_result_text).size(11).color(colors.text_secondary),
+            Space::new().width(Length::Fill),
+        ]
+        .align_y(Center);
+        if self.doc.follower.is_some() {
+            controls = controls
+                .push(
+                    button(text("Follow").size(11))
+                        .padding([5, 12])
+                        .style(button_toggle_style_themed(self.doc.following, colors))
+                        .on_press(Message::ToggleFollow),
+                )
+                .push(Space::new().width(Length::Fixed(8.0)));
+        }
+
+        container(controls.push(theme_button))
+        .width(Fill)
+        .padding([8, 10])
+        .style(move |_theme| container::Style {
+            background: Some(colors.toolbar_bg.into()),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    /// Render the patch preview bar: applied patches, generate, save and revert
+    fn render_patch_bar<'a>(&self, preview: &PatchPreview, colors: ThemeColors) -> Element<'a, Message> {
+        let applied = match preview.applied.as_slice() {
+            [only] => only.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "1 patch".to_string()),
+            all => format!("{} patches", all.len()),
+        };
+
+        let bar_button = |label: &'static str, message: Message| {
+            button(text(label).size(11))
+                .padding([4, 10])
+                .style(button_3d_style_themed(colors))
+                .on_press(message)
+        };
+
+        container(
+            row![
+                text(format!("Patched: {}", applied)).size(11).color(colors.text_primary),
+                Space::new().width(Length::Fixed(12.0)),
+                text(format!("~{} nodes", preview.affected.len())).size(11).color(colors.diff_changed),
+                Space::new().width(Length::Fill),
+                bar_button("Generate JSON Patch", Message::GeneratePatch(PatchFormat::JsonPatch)),
+                Space::new().width(Length::Fixed(5.0)),
+                bar_button("Generate Merge Patch", Message::GeneratePatch(PatchFormat::MergePatch)),
+                Space::new().width(Length::Fixed(10.0)),
+                bar_button("Save As...", Message::SaveDocumentAs),
+                Space::new().width(Length::Fixed(5.0)),
+                bar_button("Revert", Message::RevertPatch),
+            ]
+            .align_y(Center)
+        )
+        .width(Fill)
+        .padding([4, 10])
+        .style(move |_theme| container::Style {
+            background: Some(colors.status_bar_bg.into()),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    /// Render the "file changed on disk" prompt
+    fn render_reload_bar<'a>(&self, colors: ThemeColors) -> Element<'a, Message> {
+        let bar_button = |label: &'static str, message: Message| {
+            button(text(label).size(11))
+                .padding([4, 10])
+                .style(button_3d_style_themed(colors))
+                .on_press(message)
+        };
+        let message = if self.doc.patch_preview.is_some() {
+            "The file changed on disk. Reloading discards the applied patches."
+        } else {
+            "The file changed on disk."
+        };
+
+        container(
+            row![
+                text(message).size(11).color(colors.text_primary),
+                Space::new().width(Length::Fill),
+                bar_button("Reload", Message::ReloadFile),
+                Space::new().width(Length::Fixed(5.0)),
+                bar_button("Always Reload", Message::SetAutoReload(true)),
+                Space::new().width(Length::Fixed(5.0)),
+                bar_button("Ignore", Message::DismissFileChanged),
+            ]
+            .align_y(Center)
+        )
+        .width(Fill)
+        .padding([4, 10])
+        .style(move |_theme| container::Style {
+            background: Some(colors.status_bar_bg.into()),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    /// Render the compare-mode bar: summary, change navigation and array matching
+    fn render_compare_bar<'a>(&self, compare: &CompareSession, colors: ThemeColors) -> Element<'a, Message> {
+        let name = |path: &PathBuf| path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let counts = row![
+            text(format!("+{}", compare.count(DiffKind::Added))).size(11).color(colors.diff_added),
+            text(format!("−{}", compare.count(DiffKind::Removed))).size(11).color(colors.diff_removed),
+            text(format!("~{}", compare.count(DiffKind::Changed))).size(11).color(colors.diff_changed),
+            text(format!("↕{}", compare.count(DiffKind::Moved))).size(11).color(colors.diff_moved),
+        ]
+        .spacing(8);
+
+        let has_changes = !compare.changes.is_empty();
+        let position_text = if has_changes {
+            let current = compare.change_index.map(|i| i + 1).unwrap_or(0);
+            format!("{} / {}", current, compare.changes.len())
+        } else {
+            "No differences".to_string()
+        };
+
+        let prev_button = button(text("◂ Prev").size(11))
+            .padding([4, 10])
+            .style(button_3d_style_themed(colors))
+            .on_press_maybe(has_changes.then_some(Message::SearchPrev));
+        let next_button = button(text("Next ▸").size(11))
+            .padding([4, 10])
+            .style(button_3d_style_themed(colors))
+            .on_press_maybe(has_changes.then_some(Message::SearchNext));
+
+        let matching_button = |label: &'static str, matching: ArrayMatching| {
+            let is_active = compare.matching == matching;
+            button(text(label).size(11))
+                .padding([4, 8])
+                .style(button_toggle_style_themed(is_active, colors))
+                .on_press(Message::SetArrayMatching(matching))
+        };
+        let key_input = compare.match_key_input.trim().to_string();
+        let key_matching = ArrayMatching::Key(key_input.clone());
+        let key_button = button(text("By key").size(11))
+            .padding([4, 8])
+            .style(button_toggle_style_themed(matches!(compare.matching, ArrayMatching::Key(_)), colors))
+            .on_press_maybe((!key_input.is_empty()).then_some(Message::SetArrayMatching(key_matching.clone())));
+        let key_field = text_input("id", &compare.match_key_input)
+            .on_input(Message::CompareMatchKeyChanged)
+            .on_submit_maybe((!key_input.is_empty()).then_some(Message::SetArrayMatching(key_matching)))
+            .size(11)
+            .padding(3)
+            .width(Length::Fixed(70.0));
+
+        let patch_button = |label: &'static str, format: PatchFormat| {
+            button(text(label).size(11))
+                .padding([4, 8])
+                .style(button_3d_style_themed(colors))
+                .on_press_maybe(has_changes.then_some(Message::GeneratePatch(format)))
+        };
+
+        let exit_button = button(text("Exit Compare").size(11))
+            .padding([4, 10])
+            .style(button_3d_style_themed(colors))
+            .on_press(Message::ExitCompare);
+
+        container(
+            row![
+                text(format!("{} ↔ {}", name(&compare.left_path), name(&compare.right_path)))
+                    .size(11)
+                    .color(colors.text_primary),
+                Space::new().width(Length::Fixed(12.0)),
+                counts,
+                Space::new().width(Length::Fixed(12.0)),
+                prev_button,
+                Space::new().width(Length::Fixed(5.0)),
+                next_button,
+                Space::new().width(Length::Fixed(8.0)),
+                text(position_text).size(11).color(colors.text_secondary),
+                Space::new().width(Length::Fill),
+                text("Arrays:").size(11).color(colors.text_secondary),
+                Space::new().width(Length::Fixed(5.0)),
+                matching_button("Index", ArrayMatching::Index),
+                Space::new().width(Length::Fixed(3.0)),
+                matching_button("Value", ArrayMatching::Value),
+                Space::new().width(Length::Fixed(3.0)),
+                key_button,
+                Space::new().width(Length::Fixed(3.0)),
+                key_field,
+                Space::new().width(Length::Fixed(10.0)),
+                patch_button("Patch", PatchFormat::JsonPatch),
+                Space::new().width(Length::Fixed(3.0)),
+                patch_button("Merge Patch", PatchFormat::MergePatch),
+                Space::new().width(Length::Fixed(10.0)),
+                exit_button,
+            ]
+            .align_y(Center)
+        )
+        .width(Fill)
+        .padding([4, 10])
+        .style(move |_theme| container::Style {
+            background: Some(colors.status_bar_bg.into()),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    /// Render the grid view of an array of objects (virtualized like the tree)
+    fn render_table_view<'a>(&self, table: &TableView, tree: &JsonTree, colors: ThemeColors) -> Element<'a, Message> {
+        /// Width of the element index column
+        const INDEX_WIDTH: f32 = 60.0;
+        /// Width of the drag handle between header cells
+        const HANDLE_WIDTH: f32 = 4.0;
+        /// Longer strings are cut before layout; the cell clips them anyway
+        const MAX_CELL_CHARS: usize = 200;
+
+        let path = self.doc.flat_rows.iter()
+            .find(|r| r.node_index == table.node_index)
+            .map(|r| r.path.clone())
+            .unwrap_or_else(|| "(root)".to_string());
+        let bar = container(
+            row![
+                text(format!("Table: {}  ({} rows × {} columns)", path, table.rows.len(), table.columns.len()))
+                    .size(11)
+                    .color(colors.text_primary),
+                Space::new().width(Length::Fill),
+                button(text("Back to Tree").size(11))
+                    .padding([2, 10])
+                    .style(button_3d_style_themed(colors))
+                    .on_press(Message::CloseTable),
+            ]
+            .align_y(Center)
+        )
+        .width(Fill)
+        .padding([4, 10])
+        .style(move |_theme| container::Style {
+            background: Some(colors.status_bar_bg.into()),
+            ..Default::default()
+        });
+
+        let mut header: Vec<Element<'a, Message>> = vec![
+            text("#").font(Font::MONOSPACE).size(13).color(colors.text_secondary)
+                .width(Length::Fixed(INDEX_WIDTH))
+                .into(),
+        ];
+        for (i, column) in table.columns.iter().enumerate() {
+            let arrow = match table.sort {
+                Some((c, true)) if c == i => " ▲",
+                Some((c, false)) if c == i => " ▼",
+                _ => "",
+            };
+            header.push(
+                button(
+                    text(format!("{}{}", column.key, arrow))
+                        .font(Font::MONOSPACE)
+                        .size(13)
+                        .color(colors.key)
+                        .wrapping(text::Wrapping::None)
+                )
+                .on_press(Message::SortTable(i))
+                .padding([0, 4])
+                .width(Length::Fixed(column.width - HANDLE_WIDTH))
+                .clip(true)
+                .style(button::text)
+                .into()
+            );
+            header.push(
+                mouse_area(
+                    container(Space::new().width(Length::Fixed(1.0)).height(Length::Fill))
+                        .width(Length::Fixed(HANDLE_WIDTH))
+                        .height(Length::Fixed(ROW_HEIGHT + 4.0))
+                        .style(move |_theme| container::Style {
+                            background: Some(colors.btn_border_bottom.into()),
+                            ..Default::default()
+                        })
+                )
+                .on_press(Message::TableResizeStart(i))
+                .interaction(Interaction::ResizingHorizontally)
+                .into()
+            );
+        }
+        let header_row = container(row(header).align_y(Center))
+            .padding([2, 0])
+            .style(move |_theme| container::Style {
+                background: Some(colors.toolbar_bg.into()),
+                ..Default::default()
+            });
+
+        // Same windowing as the tree: spacers stand in for rows outside the viewport
+        let total_rows = table.rows.len();
+        let first_visible = (table.scroll_offset / ROW_HEIGHT).floor() as usize;
+        let visible_count = (table.viewport_height / ROW_HEIGHT).ceil() as usize + 1;
+        let start = first_visible.saturating_sub(BUFFER_ROWS).min(total_rows);
+        let end = (first_visible + visible_count + BUFFER_ROWS).min(total_rows);
+
+        let mut elements: Vec<Element<'a, Message>> = Vec::new();
+        if start > 0 {
+            elements.push(Space::new().height(Length::Fixed(start as f32 * ROW_HEIGHT)).into());
+        }
+        for (position, &element) in table.rows.iter().enumerate().skip(start).take(end - start) {
+            let label = tree.get_node(element)
+                .and_then(|n| n.key.as_deref())
+                .unwrap_or_default()
+                .trim_matches(|c| c == '[' || c == ']')
+                .to_string();
+            let mut cells: Vec<Element<'a, Message>> = vec![
+                button(text(label).font(Font::MONOSPACE).size(13).color(colors.text_secondary))
+                    .on_press(Message::OpenTableCell(element))
+                    .padding(0)
+                    .width(Length::Fixed(INDEX_WIDTH))
+                    .style(button::text)
+                    .into(),
+            ];
+            for (column, col) in table.columns.iter().enumerate() {
+                let cell: Element<'a, Message> = match table.cell(tree, element, column) {
+                    Some(cell) => {
+                        let node = tree.get_node(cell);
+                        let color = match node.map(|n| &n.value) {
+                            Some(JsonValue::Null) => colors.null,
+                            Some(JsonValue::Bool(_)) => colors.boolean,
+                            Some(JsonValue::Number(_)) => colors.number,
+                            Some(JsonValue::String(_)) => colors.string,
+                            _ => colors.key,
+                        };
+                        let content: String = table::cell_text(tree, cell).chars().take(MAX_CELL_CHARS).collect();
+                        let label = text(content)
+                            .font(Font::MONOSPACE)
+                            .size(13)
+                            .color(color)
+                            .wrapping(text::Wrapping::None);
+                        if node.is_some_and(|n| n.is_expandable()) {
+                            // Nested values open in the tree
+                            button(label)
+                                .on_press(Message::OpenTableCell(cell))
+                                .padding(0)
+                                .style(button::text)
+                                .into()
+                        } else {
+                            label.into()
+                        }
+                    }
+                    None => Space::new().into(),
+                };
+                cells.push(
+                    container(cell)
+                        .padding([0, 4])
+                        .width(Length::Fixed(col.width))
+                        .height(Length::Fixed(ROW_HEIGHT))
+                        .clip(true)
+                        .into()
+                );
+            }
+            let background = (position % 2 == 1).then_some(colors.row_odd);
+            elements.push(
+                container(row(cells))
+                    .height(Length::Fixed(ROW_HEIGHT))
+                    .style(move |_theme| container::Style {
+                        background: background.map(Into::into),
+                        ..Default::default()
+                    })
+                    .into()
+            );
+        }
+        if end < total_rows {
+            elements.push(Space::new().height(Length::Fixed((total_rows - end) as f32 * ROW_HEIGHT)).into());
+        }
+
+        let width = INDEX_WIDTH + table.width();
+        let body = scrollable(column(elements).width(Length::Fixed(width)))
+            .id(self.table_scrollable_id.clone())
+            .on_scroll(Message::TableScrolled)
+            .height(Length::Fill)
+            .width(Length::Fixed(width + 12.0));
+
+        let grid = scrollable(column![header_row, body].width(Length::Fixed(width + 12.0)))
+            .direction(scrollable::Direction::Horizontal(scrollable::Scrollbar::default()))
+            .height(Length::Fill)
+            .width(Fill);
+
+        // Mouse moves are only tracked while a column border is being dragged
+        let grid: Element<'a, Message> = if table.resizing.is_some() {
+            mouse_area(grid)
+                .on_move(|position| Message::TableResizeMove(position.x))
+                .on_release(Message::TableResizeEnd)
+                .interaction(Interaction::ResizingHorizontally)
+                .into()
+        } else {
+            grid.into()
+        };
+
+        column![bar, grid].into()
+    }
 
-            let tree_container = container(tree_view)
-                .width(Fill)
-                .height(Fill)
-                .style(move |_theme| container::Style {
-                    background: Some(colors.background.into()),
-                    ..Default::default()
-                });
+    /// Render the schema problems panel: one row per violation, click to jump to the node
+    fn render_problems_panel<'a>(&self, schema: &SchemaSession, colors: ThemeColors) -> Element<'a, Message> {
+        /// Rendering every row of a huge report would stall the UI
+        const MAX_LISTED_PROBLEMS: usize = 500;
 
-            let main_content: Element<'_, Message> = column![toolbar, tree_container, status_bar].into();
+        let name = schema.path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "schema".to_string());
 
-            if self.cli_install_result.is_some() {
-                stack![main_content, self.render_cli_install_dialog(colors)].into()
-            } else if self.update_check_state != UpdateCheckState::None {
-                stack![main_content, self.render_update_dialog(colors)].into()
-            } else if self.show_help {
-                stack![main_content, self.render_help_overlay(colors)].into()
-            } else if self.context_menu_state.is_some() {
-                stack![main_content, self.render_context_menu(colors)].into()
-            } else {
-                main_content
-            }
-        } else if self.cli_install_result.is_some() {
-            stack![tree_view, self.render_cli_install_dialog(colors)].into()
-        } else if self.update_check_state != UpdateCheckState::None {
-            stack![tree_view, self.render_update_dialog(colors)].into()
-        } else if self.show_help {
-            stack![tree_view, self.render_help_overlay(colors)].into()
-        } else {
-            tree_view
+        let bar_button = |label: &'static str, message: Message| {
+            button(text(label).size(11))
+                .padding([2, 10])
+                .style(button_3d_style_themed(colors))
+                .on_press(message)
+        };
+
+        let header = row![
+            text(format!("Problems: {} ({})", name, schema.draft.label())).size(11).color(colors.text_primary),
+            Space::new().width(Length::Fill),
+            bar_button("Detach Schema", Message::DetachSchema),
+            Space::new().width(Length::Fixed(5.0)),
+            bar_button("Close", Message::ToggleProblemsPanel),
+        ]
+        .align_y(Center);
+
+        let mut rows: Vec<Element<'a, Message>> = Vec::new();
+        if let Some(error) = &schema.error {
+            rows.push(text(format!("Schema error: {}", error)).size(12).color(colors.error).into());
+        } else if schema.violations.is_empty() {
+            rows.push(text("No problems: the document matches the schema").size(12).color(colors.text_secondary).into());
+        }
+        for reference in &schema.unresolved_refs {
+            rows.push(
+                text(format!("Not checked: $ref \"{}\" points to another document", reference))
+                    .size(12)
+                    .color(colors.text_secondary)
+                    .into()
+            );
+        }
+        for (i, violation) in schema.violations.iter().enumerate().take(MAX_LISTED_PROBLEMS) {
+            let location = if violation.pointer.is_empty() { "/".to_string() } else { violation.pointer.clone() };
+            rows.push(
+                button(row![
+                    text("⚠ ").size(12).color(colors.error),
+                    text(location).size(12).font(Font::MONOSPACE).color(colors.key),
+                    text(format!("  {}: {}", violation.keyword, violation.message)).size(12).color(colors.text_primary),
+                ])
+                .on_press(Message::JumpToProblem(i))
+                .padding([1, 4])
+                .style(button::text)
+                .into()
+            );
+        }
+        if schema.violations.len() > MAX_LISTED_PROBLEMS {
+            rows.push(
+                text(format!("… and {} more", schema.violations.len() - MAX_LISTED_PROBLEMS))
+                    .size(12)
+                    .color(colors.text_secondary)
+                    .into()
+            );
         }
-    }
 
-    /// Render the toolbar with search controls
-    fn render_toolbar<'a>(&self, colors: ThemeColors) -> Element<'a, Message> {
-        let case_button = button(text("Aa").size(11))
-            .padding([4, 8])
-            .style(button_toggle_style_themed(self.search_case_sensitive, colors))
-            .on_press(Message::ToggleCaseSensitive);
+        container(
+            column![
+                header,
+                scrollable(column(rows).spacing(1))
+                    .height(Length::Fixed(140.0))
+                    .width(Fill),
+            ]
+            .spacing(4)
+        )
+        .width(Fill)
+        .padding([4, 10])
+        .style(move |_theme| container::Style {
+            background: Some(colors.status_bar_bg.into()),
+            ..Default::default()
+        })
+        .into()
+    }
 
-        let regex_button = button(text(".*").size(11))
-            .padding([4, 8])
-            .style(button_toggle_style_themed(self.search_use_regex, colors))
-            .on_press(Message::ToggleRegex);
+    /// Render the document statistics panel
+    fn render_stats_panel<'a>(&self, stats: &DocumentStats, tree: &JsonTree, colors: ThemeColors) -> Element<'a, Message> {
+        let bar_button = |label: &'static str, message: Message| {
+            button(text(label).size(11))
+                .padding([2, 10])
+                .style(button_3d_style_themed(colors))
+                .on_press(message)
+        };
+        let sort_button = |label: &'static str, sort: HeavySort| {
+            button(text(label).size(11))
+                .padding([2, 10])
+                .style(button_toggle_style_themed(stats.sort == sort, colors))
+                .on_press(Message::SetStatsSort(sort))
+        };
 
-        let search_input = text_input("Find...", &self.search_query)
-            .id(self.search_input_id.clone())
-            .on_input(Message::SearchQueryChanged)
-            .on_submit(Message::SearchSubmit)
-            .padding(5)
-            .width(Length::Fixed(200.0));
+        let total_bytes = stats.subtree_bytes.get(tree.root_index()).copied().unwrap_or(0);
+        let header = row![
+            text(format!("Statistics: {} nodes, {} minified", tree.node_count(), stats::format_bytes(total_bytes)))
+                .size(11)
+                .color(colors.text_primary),
+            Space::new().width(Length::Fill),
+            text("Heaviest by ").size(11).color(colors.text_secondary),
+            sort_button("Size", HeavySort::Bytes),
+            Space::new().width(Length::Fixed(5.0)),
+            sort_button("Nodes", HeavySort::Nodes),
+            Space::new().width(Length::Fixed(15.0)),
+            bar_button("Close", Message::ToggleStatsPanel),
+        ]
+        .align_y(Center);
 
-        let search_result_text = if let Some(ref error) = self.search_regex_error {
-            error.clone()
-        } else if self.search_results.is_empty() {
-            if self.search_query.is_empty() {
-                String::new()
-            } else {
-                "No matches".to_string()
-            }
-        } else {
-            let current = self.search_result_index.map(|i| i + 1).unwrap_or(0);
-            format!("{} / {}", current, self.search_results.len())
+        let heading = |label: &'static str| -> Element<'a, Message> {
+            text(label).size(12).color(colors.text_secondary).into()
+        };
+        let line = |label: String, value: String| -> Element<'a, Message> {
+            row![
+                text(label).size(12).color(colors.text_primary).width(Length::Fixed(110.0)),
+                text(value).size(12).font(Font::MONOSPACE).color(colors.number),
+            ]
+            .into()
+        };
+        let node_link = |node_index: usize, detail: String| -> Element<'a, Message> {
+            button(row![
+                text(stats.path(tree, node_index)).size(12).font(Font::MONOSPACE).color(colors.key),
+                text(format!("  {}", detail)).size(12).color(colors.text_secondary),
+            ])
+            .on_press(Message::JumpToNode(node_index))
+            .padding([1, 4])
+            .style(button::text)
+            .into()
         };
 
-        let has_results = !self.search_results.is_empty();
+        let counts = &stats.counts;
+        let summary = column![
+            heading("Nodes by type"),
+            line("Objects".to_string(), counts.objects.to_string()),
+            line("Arrays".to_string(), counts.arrays.to_string()),
+            line("Strings".to_string(), counts.strings.to_string()),
+            line("Numbers".to_string(), counts.numbers.to_string()),
+            line("Booleans".to_string(), counts.bools.to_string()),
+            line("Nulls".to_string(), counts.nulls.to_string()),
+            Space::new().height(Length::Fixed(6.0)),
+            heading("Depth"),
+            line("Maximum".to_string(), stats.max_depth.to_string()),
+            line("Average".to_string(), format!("{:.2}", stats.average_depth)),
+        ]
+        .spacing(1)
+        .width(Length::FillPortion(1));
+
+        let mut extremes: Vec<Element<'a, Message>> = vec![heading("Longest strings")];
+        for &node_index in &stats.longest_strings {
+            let length = match tree.get_node(node_index).map(|n| &n.value) {
+                Some(JsonValue::String(s)) => s.chars().count(),
+                _ => 0,
+            };
+            extremes.push(node_link(node_index, format!("{} chars", length)));
+        }
+        extremes.push(Space::new().height(Length::Fixed(6.0)).into());
+        extremes.push(heading("Largest arrays"));
+        for &node_index in &stats.largest_arrays {
+            let length = tree.get_node(node_index).map_or(0, |n| n.children.len());
+            extremes.push(node_link(node_index, format!("{} items", length)));
+        }
 
-        let prev_button = button(text("◂ Prev").size(11))
-            .padding([5, 12])
-            .style(button_3d_style_themed(colors));
+        let mut heaviest: Vec<Element<'a, Message>> = vec![heading("Heaviest nodes")];
+        for &node_index in stats.heaviest() {
+            let bytes = stats.subtree_bytes[node_index];
+            let share = if total_bytes == 0 { 0.0 } else { bytes as f64 * 100.0 / total_bytes as f64 };
+            heaviest.push(node_link(
+                node_index,
+                format!("{} ({:.1}%), {} nodes", stats::format_bytes(bytes), share, stats.subtree_nodes[node_index]),
+            ));
+        }
 
-        let next_button = button(text("Next ▸").size(11))
-            .padding([5, 12])
-            .style(button_3d_style_themed(colors));
+        container(
+            column![
+                header,
+                scrollable(
+                    row![
+                        summary,
+                        column(extremes).spacing(1).width(Length::FillPortion(2)),
+                        column(heaviest).spacing(1).width(Length::FillPortion(3)),
+                    ]
+                    .spacing(20)
+                )
+                .height(Length::Fixed(180.0))
+                .width(Fill),
+            ]
+            .spacing(4)
+        )
+        .width(Fill)
+        .padding([4, 10])
+        .style(move |_theme| container::Style {
+            background: Some(colors.status_bar_bg.into()),
+            ..Default::default()
+        })
+        .into()
+    }
 
-        let prev_button = if has_results {
-            prev_button.on_press(Message::SearchPrev)
-        } else {
-            prev_button
+    /// Render the breadcrumb bar: one segment per ancestor of the selected
+    /// node. Segments select their node; ▾ lists the segment and its siblings.
+    fn render_breadcrumb_bar<'a>(&self, colors: ThemeColors) -> Element<'a, Message> {
+        /// Longest segment label before it is cut off
+        const MAX_LABEL_CHARS: usize = 32;
+        /// Width of one character of the monospace font at size 12, for placing the dropdown
+        const CHAR_WIDTH: f32 = 7.2;
+
+        let crumbs = match (&self.doc.tree, self.doc.selected_node) {
+            (Some(tree), Some(selected)) => breadcrumb::crumbs(tree, selected),
+            (Some(tree), None) => breadcrumb::crumbs(tree, tree.root_index()),
+            _ => Vec::new(),
         };
 
-        let next_button = if has_results {
-            next_button.on_press(Message::SearchNext)
-        } else {
-            next_button
-        };
+        let mut segments = row![].align_y(Center);
+        // Estimated x offset of the next element, for the dropdown position
+        let mut x = 10.0;
+        for (i, crumb) in crumbs.iter().enumerate() {
+            if i > 0 {
+                segments = segments.push(text(" › ").size(12).font(Font::MONOSPACE).color(colors.text_secondary));
+                x += 3.0 * CHAR_WIDTH;
+            }
+            let label: String = if crumb.label.chars().count() > MAX_LABEL_CHARS {
+                crumb.label.chars().take(MAX_LABEL_CHARS - 1).chain(['…']).collect()
+            } else {
+                crumb.label.clone()
+            };
+            let is_last = i + 1 == crumbs.len();
+            let label_color = if is_last { colors.key } else { colors.text_primary };
+            let segment_x = x;
+            x += label.chars().count() as f32 * CHAR_WIDTH + 8.0;
+            segments = segments.push(
+                button(text(label).size(12).font(Font::MONOSPACE).color(label_color))
+                    .on_press(Message::JumpToNode(crumb.node_index))
+                    .padding([2, 4])
+                    .style(button::text),
+            );
+            // The root has no siblings
+            if i > 0 {
+                let parent = crumbs[i - 1].node_index;
+                segments = segments.push(
+                    button(text("▾").size(12).color(colors.text_secondary))
+                        .on_press(Message::ShowBreadcrumbMenu(parent, segment_x))
+                        .padding([2, 3])
+                        .style(button::text),
+                );
+                x += CHAR_WIDTH + 6.0;
+            }
+        }
 
-        let theme_icon = match self.theme {
-            AppTheme::Dark => "◑",
-            AppTheme::Light => "◐",
+        container(segments)
+            .width(Fill)
+            .padding([2, 6])
+            .style(move |_theme| container::Style {
+                background: Some(colors.status_bar_bg.into()),
+                ..Default::default()
+            })
+            .into()
+    }
+
+    /// Render the breadcrumb dropdown over the top of the tree. Clicking
+    /// anywhere else closes it.
+    fn render_breadcrumb_menu<'a>(&self, parent: usize, x: f32, colors: ThemeColors) -> Element<'a, Message> {
+        /// Rendering every child of a huge array would stall the UI
+        const MAX_LISTED_CHILDREN: usize = 500;
+
+        let Some(tree) = &self.doc.tree else {
+            return Space::new().into();
         };
-        let theme_button = button(text(theme_icon).size(16))
-            .padding([4, 10])
-            .style(button_3d_style_themed(colors))
-            .on_press(Message::ToggleTheme);
+        let children = breadcrumb::children(tree, parent);
+        let selected_path = self.doc.selected_node
+            .map(|selected| tree.get_path_to_node(selected))
+            .unwrap_or_default();
+        let mut items: Vec<Element<'a, Message>> = children.iter()
+            .take(MAX_LISTED_CHILDREN)
+            .map(|child| {
+                let is_selected = selected_path.contains(&child.node_index);
+                let color = if is_selected { colors.key } else { colors.text_primary };
+                button(text(child.label.clone()).size(12).font(Font::MONOSPACE).color(color))
+                    .on_press(Message::JumpToNode(child.node_index))
+                    .padding([3, 10])
+                    .width(Fill)
+                    .style(move |_theme, status| button::Style {
+                        background: matches!(status, ButtonStatus::Hovered).then(|| colors.selected.into()),
+                        text_color: color,
+                        ..Default::default()
+                    })
+                    .into()
+            })
+            .collect();
+        if children.len() > MAX_LISTED_CHILDREN {
+            items.push(
+                text(format!("  … and {} more", children.len() - MAX_LISTED_CHILDREN))
+                    .size(12)
+                    .color(colors.text_secondary)
+                    .into()
+            );
+        }
+
+        let menu_box = container(scrollable(column(items)).height(Length::Shrink))
+            .width(Length::Fixed(220.0))
+            .max_height(300.0)
+            .padding(4)
+            .style(move |_theme| container::Style {
+                background: Some(colors.toolbar_bg.into()),
+                border: Border {
+                    color: colors.btn_border_top,
+                    width: 1.0,
+                    radius: Radius::from(6.0),
+                },
+                shadow: Shadow {
+                    color: Color::from_rgba(0.0, 0.0, 0.0, 0.4),
+                    offset: iced::Vector::new(0.0, 2.0),
+                    blur_radius: 10.0,
+                },
+                ..Default::default()
+            });
+
+        stack![
+            mouse_area(Space::new().width(Fill).height(Fill)).on_press(Message::HideBreadcrumbMenu),
+            row![Space::new().width(Length::Fixed(x)), menu_box],
+        ]
+        .into()
+    }
+
+    /// Render the bookmarks sidebar: a label field, the path and a remove
+    /// button per bookmark. Clicking the path jumps to the node.
+    fn render_bookmarks_panel<'a>(&'a self, colors: ThemeColors) -> Element<'a, Message> {
+        let header = row![
+            text("Bookmarks").size(11).color(colors.text_primary),
+            Space::new().width(Length::Fill),
+            button(text("Close").size(11))
+                .padding([2, 10])
+                .style(button_3d_style_themed(colors))
+                .on_press(Message::ToggleBookmarksPanel),
+        ]
+        .align_y(Center);
+
+        let found: HashSet<usize> = self.doc.bookmark_nodes.values().copied().collect();
+        let mut entries: Vec<Element<'a, Message>> = Vec::new();
+        if self.doc.bookmarks.is_empty() {
+            let cmd_key = if cfg!(target_os = "macos") { "Cmd" } else { "Ctrl" };
+            entries.push(
+                text(format!("No bookmarks. Select a node and press {}+B.", cmd_key))
+                    .size(12)
+                    .color(colors.text_secondary)
+                    .into()
+            );
+        }
+        for (position, bookmark) in self.doc.bookmarks.iter().enumerate() {
+            let pointer = if bookmark.pointer.is_empty() { "/".to_string() } else { bookmark.pointer.clone() };
+            let (pointer_color, pointer) = if found.contains(&position) {
+                (colors.key, pointer)
+            } else {
+                (colors.text_secondary, format!("{} (missing)", pointer))
+            };
+            entries.push(
+                column![
+                    text_input("Label", &bookmark.label)
+                        .on_input(move |label| Message::BookmarkLabelChanged(position, label))
+                        .on_submit(Message::BookmarkLabelSubmitted)
+                        .size(12)
+                        .padding([2, 6]),
+                    row![
+                        button(text(pointer).size(12).font(Font::MONOSPACE).color(pointer_color))
+                            .on_press(Message::JumpToBookmark(position))
+                            .padding([1, 4])
+                            .style(button::text),
+                        Space::new().width(Length::Fill),
+                        button(text("×").size(12).color(colors.text_secondary))
+                            .on_press(Message::RemoveBookmark(position))
+                            .padding([1, 6])
+                            .style(button::text),
+                    ]
+                    .align_y(Center),
+                ]
+                .spacing(2)
+                .into()
+            );
+        }
 
         container(
-            row![
-                case_button,
-                Space::new().width(Length::Fixed(3.0)),
-                regex_button,
-                Space::new().width(Length::Fixed(8.0)),
-                search_input,
-                Space::new().width(Length::Fixed(10.0)),
-                prev_button,
-                Space::new().width(Length::Fixed(5.0)),
-                next_button,
-                Space::new().width(Length::Fixed(10.0)),
-                text(search_result_text).size(11).color(colors.text_secondary),
-                Space::new().width(Length::Fill),
-                theme_button,
+            column![
+                header,
+                scrollable(column(entries).spacing(8)).height(Fill),
             ]
-            .align_y(Center)
+            .spacing(8)
         )
-        .width(Fill)
-        .padding([8, 10])
+        .width(Length::Fixed(240.0))
+        .height(Fill)
+        .padding([6, 10])
         .style(move |_theme| container::Style {
-            background: Some(colors.toolbar_bg.into()),
+            background: Some(colors.status_bar_bg.into()),
             ..Default::default()
         })
         .into()
@@ -1513,17 +4079,17 @@ impl App {
 
     /// Render the status bar
     fn render_status_bar<'a>(&self, colors: ThemeColors) -> Element<'a, Message> {
-        let load_time_str: String = self.load_time
+        let load_time_str: String = self.doc.load_time
             .map(|d| format!("Load: {}ms", d.as_millis()))
             .unwrap_or_default();
 
-        let node_count: String = self.tree.as_ref()
+        let node_count: String = self.doc.tree.as_ref()
             .map(|t| format!("Nodes: {}", t.node_count()))
             .unwrap_or_default();
 
-        let path_display: String = if let Some(node_index) = self.selected_node {
-            if let Some(flat_row) = self.flat_rows.iter().find(|r| r.node_index == node_index) {
-                let type_info = if let Some(tree) = &self.tree {
+        let path_display: String = if let Some(node_index) = self.doc.selected_node {
+            if let Some(flat_row) = self.doc.flat_rows.iter().find(|r| r.node_index == node_index) {
+                let type_info = if let Some(tree) = &self.doc.tree {
                     if let Some(node) = tree.get_node(node_index) {
                         match &node.value {
                             JsonValue::Null => "(null)".to_string(),
@@ -1547,12 +4113,47 @@ impl App {
             String::new()
         };
 
+        let search_summary: String = if self.doc.search_query.is_empty() || self.doc.search_regex_error.is_some() {
+            String::new()
+        } else {
+            let count = match self.doc.search_results.len() {
+                1 => "1 match".to_string(),
+                n => format!("{} matches", n),
+            };
+            match &self.doc.search_scope {
+                Some(scope) => format!("{} in {}  |  ", count, scope),
+                None => format!("{}  |  ", count),
+            }
+        };
+
+        let schema_status: Element<'a, Message> = match &self.doc.schema {
+            Some(schema) if self.doc.compare.is_none() => {
+                let (label, color) = match (&schema.error, schema.violations.len()) {
+                    (Some(_), _) => ("✗ Schema error".to_string(), colors.error),
+                    (None, 0) => ("✓ Schema valid".to_string(), colors.diff_added),
+                    (None, 1) => ("⚠ 1 problem".to_string(), colors.error),
+                    (None, n) => (format!("⚠ {} problems", n), colors.error),
+                };
+                button(text(label).size(12).color(color))
+                    .on_press(Message::ToggleProblemsPanel)
+                    .padding([0, 10])
+                    .style(button::text)
+                    .into()
+            }
+            _ => Space::new().into(),
+        };
+
         container(
             row![
-                text(node_count).size(12).color(colors.text_secondary),
+                button(text(node_count).size(12).color(colors.text_secondary))
+                    .on_press(Message::ToggleStatsPanel)
+                    .padding(0)
+                    .style(button::text),
                 text("  |  ").size(12).color(colors.text_secondary),
                 text(path_display).size(12).color(colors.key),
                 Space::new().width(Length::Fill),
+                text(search_summary).size(12).color(colors.text_secondary),
+                schema_status,
                 text(load_time_str).size(12).color(colors.text_secondary),
             ]
         )
@@ -1589,8 +4190,17 @@ impl App {
             text(" or drag and drop .json file here.").size(15).color(colors.text_secondary),
         ];
 
-        let new_window_link = button(text("Open in new window").size(13))
-            .on_press(Message::OpenFileInNewWindow)
+        let new_tab_link = button(text("Open in new tab").size(13))
+            .on_press(Message::OpenFileInNewTab)
+            .padding(0)
+            .style(|_theme, _status| button::Style {
+                background: None,
+                text_color: Color::from_rgb(0.4, 0.55, 0.75),
+                ..Default::default()
+            });
+
+        let compare_link = button(text("Compare two files").size(13))
+            .on_press(Message::CompareWithFile)
             .padding(0)
             .style(|_theme, _status| button::Style {
                 background: None,
@@ -1632,10 +4242,65 @@ impl App {
         .spacing(4)
         .align_x(Center);
 
+        let now = unix_now();
+        let recent_list = self.config.recent_files.iter().enumerate().fold(
+            column![].spacing(4).align_x(Center),
+            |list, (index, recent)| {
+                let name = recent.path.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| recent.path.display().to_string());
+                let link = button(text(name).size(13))
+                    .on_press(Message::OpenRecentFile(index))
+                    .padding(0)
+                    .style(|_theme, _status| button::Style {
+                        background: None,
+                        text_color: Color::from_rgb(0.4, 0.55, 0.75),
+                        ..Default::default()
+                    });
+                let details = format!(
+                    "{}  ·  {}",
+                    stats::format_bytes(recent.size as usize),
+                    recent.opened_ago(now),
+                );
+                let entry = row![link, text(details).size(11).color(colors.text_secondary)]
+                    .spacing(10)
+                    .align_y(Center);
+                list.push(tooltip(
+                    entry,
+                    container(text(recent.path.display().to_string()).size(11).color(colors.text_primary))
+                        .padding([4, 8])
+                        .style(move |_theme| container::Style {
+                            background: Some(colors.toolbar_bg.into()),
+                            border: Border {
+                                color: colors.btn_border_bottom,
+                                width: 1.0,
+                                radius: Radius::from(4.0),
+                            },
+                            ..Default::default()
+                        }),
+                    tooltip::Position::Bottom,
+                ))
+            },
+        );
+        let recent_section: Element<'a, Message> = if self.config.recent_files.is_empty() {
+            Space::new().into()
+        } else {
+            column![
+                Space::new().height(Length::Fixed(12.0)),
+                text("Recent Files").size(13).color(colors.text_secondary),
+                recent_list,
+            ]
+            .spacing(8)
+            .align_x(Center)
+            .into()
+        };
+
         let welcome = column![
             welcome_text,
             action_row,
-            new_window_link,
+            new_tab_link,
+            compare_link,
+            recent_section,
             Space::new().height(Length::Fixed(20.0)),
             theme_link,
             Space::new().height(Length::Fixed(30.0)),
@@ -1738,53 +4403,49 @@ impl App {
             .into()
     }
 
-    /// Render the help overlay with keyboard shortcuts
+    /// Render the help overlay with the shortcuts of the active keymap
     fn render_help_overlay<'a>(&self, colors: ThemeColors) -> Element<'a, Message> {
         let cmd_key = if cfg!(target_os = "macos") { "⌘" } else { "Ctrl+" };
-        let shift = if cfg!(target_os = "macos") { "⇧" } else { "Shift+" };
-        let opt = if cfg!(target_os = "macos") { "⌥" } else { "Alt+" };
 
         fn shortcut_row<'a>(keys: String, desc: &'static str, colors: ThemeColors) -> Element<'a, Message> {
             row![
                 container(text(keys).size(12).font(Font::MONOSPACE).color(colors.text_primary))
-                    .width(Length::Fixed(120.0)),
+                    .width(Length::Fixed(150.0)),
                 text(desc).size(12).color(colors.text_secondary),
             ]
             .spacing(10)
             .into()
         }
 
-        let shortcuts = column![
-            text("Keyboard Shortcuts").size(16).color(colors.text_primary),
-            Space::new().height(Length::Fixed(15.0)),
-
-            text("File").size(13).color(colors.key),
-            shortcut_row(format!("{}O", cmd_key), "Open file", colors),
-            shortcut_row(format!("{}N", cmd_key), "Open in new window", colors),
-            Space::new().height(Length::Fixed(10.0)),
-
-            text("Edit").size(13).color(colors.key),
-            shortcut_row(format!("{}C", cmd_key), "Copy selected value", colors),
-            shortcut_row(format!("{}{}C", shift, cmd_key), "Copy key name", colors),
-            shortcut_row(format!("{}{}C", opt, cmd_key), "Copy node path", colors),
-            Space::new().height(Length::Fixed(10.0)),
-
-            text("Search").size(13).color(colors.key),
-            shortcut_row(format!("{}F", cmd_key), "Focus search", colors),
-            shortcut_row("Enter".to_string(), "Next result", colors),
-            shortcut_row(format!("{}Enter", shift), "Previous result", colors),
-            shortcut_row("Escape".to_string(), "Clear search", colors),
-            Space::new().height(Length::Fixed(10.0)),
-
-            text("View").size(13).color(colors.key),
-            shortcut_row(format!("{}T", cmd_key), "Toggle theme", colors),
-            shortcut_row(format!("{}/", cmd_key), "Toggle this help", colors),
-            Space::new().height(Length::Fixed(20.0)),
+        let mut sections = column![].spacing(4);
+        for section in Section::ALL {
+            sections = sections.push(text(section.title()).size(13).color(colors.key));
+            for command in keymap::Command::ALL.into_iter().filter(|command| command.section() == section) {
+                let keys: Vec<String> = self.keymap.keys_for(command).map(|binding| binding.label()).collect();
+                if !keys.is_empty() {
+                    sections = sections.push(shortcut_row(keys.join("  "), command.description(), colors));
+                }
+            }
+            // Keys that aren't commands
+            if section == Section::File {
+                sections = sections.push(shortcut_row(format!("{}1–9", cmd_key), "Go to tab", colors));
+            }
+            sections = sections.push(Space::new().height(Length::Fixed(10.0)));
+        }
 
-            text("Press Escape or ⌘/ to close").size(11).color(colors.text_secondary),
+        let mut shortcuts = column![
+            text("Keyboard Shortcuts").size(16).color(colors.text_primary),
+            Space::new().height(Length::Fixed(15.0)),
         ]
         .spacing(4)
         .padding(25);
+        for problem in self.keymap.problems.iter().chain(&self.keymap.shadowed(self.config.vim_keys)) {
+            shortcuts = shortcuts.push(text(format!("✗ {}", problem)).size(12).color(colors.error));
+        }
+        let shortcuts = shortcuts
+            .push(scrollable(sections).height(Length::Fixed(460.0)))
+            .push(Space::new().height(Length::Fixed(10.0)))
+            .push(text("Change shortcuts in ~/.unfold/keybindings.json. Press Escape to close").size(11).color(colors.text_secondary));
 
         let overlay_box = container(shortcuts)
             .style(move |_theme| container::Style {
@@ -1822,10 +4483,15 @@ impl App {
 
     /// Render the context menu overlay
     fn render_context_menu<'a>(&self, colors: ThemeColors) -> Element<'a, Message> {
+        /// Height of a menu row: 13pt text with 6px padding above and below
+        const CONTEXT_MENU_ITEM_HEIGHT: f32 = 31.0;
+        /// Height of a separator: a 1px line with 4px padding above and below
+        const CONTEXT_MENU_SEPARATOR_HEIGHT: f32 = 9.0;
+
         let (node_index, menu_x, menu_y) = self.context_menu_state.unwrap_or((0, 100.0, 100.0));
         let current_submenu = self.context_submenu;
 
-        let has_children = if let Some(tree) = &self.tree {
+        let has_children = if let Some(tree) = &self.doc.tree {
             tree.get_node(node_index)
                 .map(|n| !n.children.is_empty())
                 .unwrap_or(false)
@@ -1924,21 +4590,59 @@ impl App {
                 .into()
         };
 
-        let mut menu_items: Vec<Element<'a, Message>> = vec![
-            menu_item("Copy Key", Message::CopySelectedName),
-            menu_item("Copy Value", Message::CopySelectedValue),
-            submenu_parent("Copy Value As", ContextSubmenu::CopyValueAs, current_submenu == ContextSubmenu::CopyValueAs),
-            menu_item("Copy Path", Message::CopySelectedPath),
-            separator(),
-            submenu_parent("Export Value As", ContextSubmenu::ExportValueAs, current_submenu == ContextSubmenu::ExportValueAs),
+        enum Entry {
+            Item(&'static str, Message),
+            Submenu(&'static str, ContextSubmenu),
+            Separator,
+        }
+
+        let mut entries = vec![
+            Entry::Item("Copy Key", Message::CopySelectedName),
+            Entry::Item("Copy Value", Message::CopySelectedValue),
+            Entry::Submenu("Copy Value As", ContextSubmenu::CopyValueAs),
+            Entry::Submenu("Copy Type As", ContextSubmenu::CopyTypeAs),
+            Entry::Item("Copy Path", Message::CopySelectedPath),
+            Entry::Item("Copy SHA-256", Message::CopySubtreeHash),
+            Entry::Item(
+                if self.doc.bookmark_nodes.contains_key(&node_index) { "Remove Bookmark" } else { "Add Bookmark" },
+                Message::ToggleBookmark,
+            ),
+            Entry::Separator,
+            Entry::Submenu("Export Value As", ContextSubmenu::ExportValueAs),
+            Entry::Item("Generate Schema", Message::ViewInferredSchema),
         ];
 
+        if self.doc.tree.as_ref().is_some_and(|tree| table::is_tabular(tree, node_index)) {
+            entries.push(Entry::Item("View as Table", Message::ShowTable(node_index)));
+        }
+
         if has_children {
-            menu_items.push(separator());
-            menu_items.push(menu_item("Expand All Children", Message::ExpandAllChildren));
-            menu_items.push(menu_item("Collapse All Children", Message::CollapseAllChildren));
+            entries.push(Entry::Separator);
+            entries.push(Entry::Item("Search in Here", Message::SearchInSelection));
+            entries.push(Entry::Item("Expand All Children", Message::ExpandAllChildren));
+            entries.push(Entry::Item("Collapse All Children", Message::CollapseAllChildren));
         }
 
+        // Line the open submenu up with its parent row
+        let mut submenu_y_offset = 0.0;
+        let mut y = 0.0;
+        let menu_items: Vec<Element<'a, Message>> = entries.into_iter()
+            .map(|entry| {
+                let (element, height) = match entry {
+                    Entry::Item(label, message) => (menu_item(label, message), CONTEXT_MENU_ITEM_HEIGHT),
+                    Entry::Submenu(label, submenu) => {
+                        if submenu == current_submenu {
+                            submenu_y_offset = y;
+                        }
+                        (submenu_parent(label, submenu, submenu == current_submenu), CONTEXT_MENU_ITEM_HEIGHT)
+                    }
+                    Entry::Separator => (separator(), CONTEXT_MENU_SEPARATOR_HEIGHT),
+                };
+                y += height;
+                element
+            })
+            .collect();
+
         let menu_content = column(menu_items).spacing(0).padding(4);
 
         let menu_box = container(menu_content)
@@ -1962,6 +4666,10 @@ impl App {
                 let submenu_content = column![
                     submenu_item("Minified", Message::CopyValueMinified),
                     submenu_item("Formatted", Message::CopyValueFormatted),
+                    submenu_item("Canonical JSON", Message::CopyValueCanonical),
+                    submenu_item("YAML", Message::CopyValueAs(TextFormat::Yaml)),
+                    submenu_item("TOML", Message::CopyValueAs(TextFormat::Toml)),
+                    submenu_item("XML", Message::CopyValueAs(TextFormat::Xml)),
                 ]
                 .spacing(0)
                 .padding(4);
@@ -1983,12 +4691,48 @@ impl App {
                     })
                     .into())
             }
-            ContextSubmenu::ExportValueAs => {
+            ContextSubmenu::CopyTypeAs => {
                 let submenu_content = column![
+                    submenu_item(TypeLanguage::Rust.label(), Message::CopyTypeAs(TypeLanguage::Rust)),
+                    submenu_item(TypeLanguage::TypeScript.label(), Message::CopyTypeAs(TypeLanguage::TypeScript)),
+                    submenu_item(TypeLanguage::Go.label(), Message::CopyTypeAs(TypeLanguage::Go)),
+                ]
+                .spacing(0)
+                .padding(4);
+
+                Some(container(submenu_content)
+                    .style(move |_theme| container::Style {
+                        background: Some(colors.toolbar_bg.into()),
+                        border: Border {
+                            color: colors.btn_border_top,
+                            width: 1.0,
+                            radius: Radius::from(6.0),
+                        },
+                        shadow: Shadow {
+                            color: Color::from_rgba(0.0, 0.0, 0.0, 0.4),
+                            offset: iced::Vector::new(0.0, 2.0),
+                            blur_radius: 10.0,
+                        },
+                        ..Default::default()
+                    })
+                    .into())
+            }
+            ContextSubmenu::ExportValueAs => {
+                let mut export_items = vec![
                     submenu_item("JSON", Message::ExportAsJson),
                     submenu_item("Minified JSON", Message::ExportAsMinifiedJson),
                     submenu_item("Formatted JSON", Message::ExportAsFormattedJson),
-                ]
+                    submenu_item("Canonical JSON", Message::ExportAsCanonicalJson),
+                    submenu_item("JSON Schema", Message::ExportAsSchema),
+                    submenu_item("YAML", Message::ExportAs(TextFormat::Yaml)),
+                    submenu_item("TOML", Message::ExportAs(TextFormat::Toml)),
+                    submenu_item("XML", Message::ExportAs(TextFormat::Xml)),
+                ];
+                if self.doc.tree.as_ref().is_some_and(|tree| table::is_tabular(tree, node_index)) {
+                    export_items.push(submenu_item("CSV...", Message::ExportAsDelimited(Delimiter::Comma)));
+                    export_items.push(submenu_item("TSV...", Message::ExportAsDelimited(Delimiter::Tab)));
+                }
+                let submenu_content = column(export_items)
                 .spacing(0)
                 .padding(4);
 
@@ -2018,12 +4762,6 @@ impl App {
         let clamped_x = menu_x.max(10.0);
         let clamped_y = menu_y.max(10.0);
 
-        let submenu_y_offset = match current_submenu {
-            ContextSubmenu::CopyValueAs => 2.0 * 31.0,
-            ContextSubmenu::ExportValueAs => 5.0 * 31.0,
-            ContextSubmenu::None => 0.0,
-        };
-
         let menu_row: Element<'a, Message> = if let Some(submenu) = submenu_box {
             row![
                 Space::new().width(Length::Fixed(clamped_x)),
@@ -2225,6 +4963,392 @@ impl App {
         ].into()
     }
 
+    /// Render the CSV/TSV export dialog: delimiter, column selection and order
+    fn render_csv_export_dialog<'a>(&self, dialog: &CsvExportDialog, colors: ThemeColors) -> Element<'a, Message> {
+        let small_button = |label: &'static str, message: Option<Message>| {
+            button(text(label).size(11))
+                .padding([1, 6])
+                .style(button_3d_style_themed(colors))
+                .on_press_maybe(message)
+        };
+        let delimiter_button = |delimiter: Delimiter| {
+            button(text(delimiter.label()).size(12))
+                .padding([3, 12])
+                .style(button_toggle_style_themed(dialog.delimiter == delimiter, colors))
+                .on_press(Message::CsvSetDelimiter(delimiter))
+        };
+
+        let last = dialog.columns.len().saturating_sub(1);
+        let column_rows: Vec<Element<'a, Message>> = dialog.columns.iter().enumerate()
+            .map(|(i, (column, included))| {
+                row![
+                    small_button("↑", (i > 0).then_some(Message::CsvMoveColumn(i, true))),
+                    small_button("↓", (i < last).then_some(Message::CsvMoveColumn(i, false))),
+                    Space::new().width(Length::Fixed(8.0)),
+                    checkbox(*included)
+                        .label(column.header.clone())
+                        .size(14)
+                        .text_size(12)
+                        .on_toggle(move |_| Message::CsvToggleColumn(i)),
+                ]
+                .spacing(2)
+                .align_y(Center)
+                .into()
+            })
+            .collect();
+        let selected = dialog.columns.iter().filter(|(_, included)| *included).count();
+
+        let content: Element<'a, Message> = column![
+            text(format!("Export as {}", dialog.delimiter.label())).size(16).color(colors.text_primary),
+            Space::new().height(Length::Fixed(10.0)),
+            row![delimiter_button(Delimiter::Comma), Space::new().width(Length::Fixed(5.0)), delimiter_button(Delimiter::Tab)],
+            Space::new().height(Length::Fixed(10.0)),
+            text(format!("Columns ({} of {})", selected, dialog.columns.len())).size(12).color(colors.text_secondary),
+            scrollable(column(column_rows).spacing(2))
+                .height(Length::Fixed(260.0))
+                .width(Length::Fixed(380.0)),
+            Space::new().height(Length::Fixed(15.0)),
+            row![
+                Space::new().width(Length::Fill),
+                button(text("Cancel").size(13))
+                    .padding([6, 16])
+                    .style(button_3d_style_themed(colors))
+                    .on_press(Message::CsvExportCancel),
+                Space::new().width(Length::Fixed(8.0)),
+                button(text("Export...").size(13))
+                    .padding([6, 16])
+                    .style(button_3d_style_themed(colors))
+                    .on_press_maybe((selected > 0).then_some(Message::CsvExportConfirm)),
+            ]
+            .width(Length::Fixed(380.0)),
+        ]
+        .spacing(4)
+        .padding(25)
+        .into();
+
+        let overlay_box = container(content)
+            .style(move |_theme| container::Style {
+                background: Some(colors.toolbar_bg.into()),
+                border: Border {
+                    color: colors.btn_border_top,
+                    width: 1.0,
+                    radius: Radius::from(8.0),
+                },
+                shadow: Shadow {
+                    color: Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+                    offset: iced::Vector::new(0.0, 4.0),
+                    blur_radius: 20.0,
+                },
+                ..Default::default()
+            });
+
+        let backdrop = button(Space::new().width(Fill).height(Fill))
+            .on_press(Message::CsvExportCancel)
+            .style(|_theme, _status| button::Style {
+                background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.5).into()),
+                ..Default::default()
+            })
+            .width(Fill)
+            .height(Fill);
+
+        stack![
+            backdrop,
+            container(overlay_box)
+                .width(Fill)
+                .height(Fill)
+                .center(Fill),
+        ].into()
+    }
+
+    /// Render the formatting options dialog with a live preview
+    fn render_format_options_dialog<'a>(&self, colors: ThemeColors) -> Element<'a, Message> {
+        let options = self.config.format;
+        let choice = |label: String, active: bool, next: FormatOptions| {
+            button(text(label).size(12))
+                .padding([3, 10])
+                .style(button_toggle_style_themed(active, colors))
+                .on_press(Message::SetFormatOptions(next))
+        };
+        let flag = |label: &'static str, value: bool, set: fn(&mut FormatOptions, bool)| {
+            checkbox(value)
+                .label(label)
+                .size(14)
+                .text_size(12)
+                .on_toggle(move |checked| {
+                    let mut next = options;
+                    set(&mut next, checked);
+                    Message::SetFormatOptions(next)
+                })
+        };
+
+        let mut indent_row = row![text("Indent").size(12).color(colors.text_secondary).width(Length::Fixed(80.0))]
+            .spacing(5)
+            .align_y(Center);
+        for width in [2, 4, 8] {
+            indent_row = indent_row.push(choice(
+                format!("{} spaces", width),
+                !options.use_tabs && options.indent_width == width,
+                FormatOptions { indent_width: width, use_tabs: false, ..options },
+            ));
+        }
+        indent_row = indent_row.push(choice("Tabs".to_string(), options.use_tabs, FormatOptions { use_tabs: true, ..options }));
+
+        let mut width_row = row![text("Line width").size(12).color(colors.text_secondary).width(Length::Fixed(80.0))]
+            .spacing(5)
+            .align_y(Center);
+        for width in [60, 80, 100, 120] {
+            width_row = width_row.push(choice(
+                width.to_string(),
+                options.line_width == width,
+                FormatOptions { line_width: width, ..options },
+            ));
+        }
+
+        let sample = serde_json::json!({"name": "Zoë", "id": 7, "tags": ["a", "b", "c"], "point": {"y": 2, "x": 1}});
+        let preview = json_export::value_to_json_string_formatted(&sample, &options);
+
+        let content: Element<'a, Message> = column![
+            text("Formatting Options").size(16).color(colors.text_primary),
+            Space::new().height(Length::Fixed(10.0)),
+            indent_row,
+            width_row,
+            Space::new().height(Length::Fixed(6.0)),
+            flag("Sort object keys", options.sort_keys, |o, v| o.sort_keys = v),
+            flag("Escape non-ASCII characters", options.ascii_only, |o, v| o.ascii_only = v),
+            flag("Keep short arrays on one line", options.compact_arrays, |o, v| o.compact_arrays = v),
+            Space::new().height(Length::Fixed(10.0)),
+            text("Preview").size(12).color(colors.text_secondary),
+            container(text(preview).font(Font::MONOSPACE).size(12).color(colors.text_primary))
+                .padding(8)
+                .width(Length::Fixed(380.0))
+                .style(move |_theme| container::Style {
+                    background: Some(colors.background.into()),
+                    border: Border {
+                        color: colors.btn_border_bottom,
+                        width: 1.0,
+                        radius: Radius::from(4.0),
+                    },
+                    ..Default::default()
+                }),
+            Space::new().height(Length::Fixed(15.0)),
+            row![
+                button(text("Reset").size(13))
+                    .padding([6, 16])
+                    .style(button_3d_style_themed(colors))
+                    .on_press(Message::SetFormatOptions(FormatOptions::default())),
+                Space::new().width(Length::Fill),
+                button(text("Done").size(13))
+                    .padding([6, 16])
+                    .style(button_3d_style_themed(colors))
+                    .on_press(Message::ToggleFormatOptions),
+            ]
+            .width(Length::Fixed(380.0)),
+        ]
+        .spacing(4)
+        .padding(25)
+        .into();
+
+        let overlay_box = container(content)
+            .style(move |_theme| container::Style {
+                background: Some(colors.toolbar_bg.into()),
+                border: Border {
+                    color: colors.btn_border_top,
+                    width: 1.0,
+                    radius: Radius::from(8.0),
+                },
+                shadow: Shadow {
+                    color: Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+                    offset: iced::Vector::new(0.0, 4.0),
+                    blur_radius: 20.0,
+                },
+                ..Default::default()
+            });
+
+        let backdrop = button(Space::new().width(Fill).height(Fill))
+            .on_press(Message::ToggleFormatOptions)
+            .style(|_theme, _status| button::Style {
+                background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.5).into()),
+                ..Default::default()
+            })
+            .width(Fill)
+            .height(Fill);
+
+        stack![
+            backdrop,
+            container(overlay_box)
+                .width(Fill)
+                .height(Fill)
+                .center(Fill),
+        ].into()
+    }
+
+    /// Render the command palette: query input above the matching actions
+    fn render_command_palette<'a>(&self, palette: &'a CommandPalette, colors: ThemeColors) -> Element<'a, Message> {
+        let input = text_input("Type a command...", &palette.query)
+            .id(self.command_palette_input_id.clone())
+            .on_input(Message::CommandPaletteChanged)
+            .on_submit(Message::CommandPaletteSubmit)
+            .size(13)
+            .padding(8)
+            .width(Length::Fixed(520.0));
+
+        let mut entries = column![].spacing(0).width(Length::Fixed(520.0));
+        for (position, entry) in palette.visible() {
+            let highlighted = position == palette.selected;
+            let shortcut = entry.shortcut.clone().unwrap_or_default();
+            entries = entries.push(
+                button(
+                    row![
+                        text(entry.title.as_str()).size(12).color(colors.text_primary),
+                        Space::new().width(Fill),
+                        text(shortcut).size(11).font(Font::MONOSPACE).color(colors.text_secondary),
+                    ]
+                    .align_y(Center),
+                )
+                .padding([4, 8])
+                .width(Fill)
+                .style(move |_theme, status| button::Style {
+                    background: match status {
+                        _ if highlighted => Some(colors.selected.into()),
+                        ButtonStatus::Hovered | ButtonStatus::Pressed => Some(colors.btn_bg_hover.into()),
+                        _ => None,
+                    },
+                    border: Border { radius: Radius::from(4.0), ..Default::default() },
+                    ..Default::default()
+                })
+                .on_press(Message::CommandPaletteRun(position)),
+            );
+        }
+        if palette.match_count() == 0 {
+            entries = entries.push(text("No matching commands").size(12).color(colors.text_secondary));
+        }
+
+        let content = column![
+            input,
+            Space::new().height(Length::Fixed(4.0)),
+            entries,
+            Space::new().height(Length::Fixed(4.0)),
+            text("↑ ↓ to choose, Enter to run, Esc to close").size(11).color(colors.text_secondary),
+        ]
+        .spacing(2)
+        .padding(12);
+
+        let overlay_box = container(content)
+            .style(move |_theme| container::Style {
+                background: Some(colors.toolbar_bg.into()),
+                border: Border {
+                    color: colors.btn_border_top,
+                    width: 1.0,
+                    radius: Radius::from(8.0),
+                },
+                shadow: Shadow {
+                    color: Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+                    offset: iced::Vector::new(0.0, 4.0),
+                    blur_radius: 20.0,
+                },
+                ..Default::default()
+            });
+
+        let backdrop = button(Space::new().width(Fill).height(Fill))
+            .on_press(Message::ToggleCommandPalette)
+            .style(|_theme, _status| button::Style {
+                background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.3).into()),
+                ..Default::default()
+            })
+            .width(Fill)
+            .height(Fill);
+
+        stack![
+            backdrop,
+            container(overlay_box)
+                .width(Fill)
+                .padding(iced::Padding { top: 60.0, ..Default::default() })
+                .center_x(Fill),
+        ].into()
+    }
+
+    /// Render the go-to-path dialog: path input with completions below it
+    fn render_goto_path_dialog<'a>(&self, dialog: &'a GotoPathDialog, colors: ThemeColors) -> Element<'a, Message> {
+        let input = text_input("users[0].name, /users/0/name or $.users[0].name", &dialog.input)
+            .id(self.goto_path_input_id.clone())
+            .on_input(Message::GotoPathChanged)
+            .on_submit(Message::GotoPathSubmit)
+            .font(Font::MONOSPACE)
+            .size(13)
+            .padding(8)
+            .width(Length::Fixed(460.0));
+
+        let mut content = column![
+            text("Go to Path").size(16).color(colors.text_primary),
+            Space::new().height(Length::Fixed(6.0)),
+            input,
+        ]
+        .spacing(4);
+
+        if let Some(error) = &dialog.error {
+            content = content.push(text(format!("✗ {}", error)).size(12).color(colors.error));
+        }
+
+        if !dialog.suggestions.is_empty() {
+            let suggestions = column(dialog.suggestions.iter().map(|suggestion| {
+                button(text(suggestion.as_str()).font(Font::MONOSPACE).size(12).color(colors.text_primary))
+                    .padding([3, 8])
+                    .width(Fill)
+                    .style(move |_theme, status| button::Style {
+                        background: match status {
+                            ButtonStatus::Hovered | ButtonStatus::Pressed => Some(colors.selected.into()),
+                            _ => None,
+                        },
+                        ..Default::default()
+                    })
+                    .on_press(Message::GotoPathComplete(suggestion.clone()))
+                    .into()
+            }));
+            content = content.push(
+                scrollable(suggestions)
+                    .height(Length::Fixed((dialog.suggestions.len() as f32 * 24.0).min(300.0)))
+                    .width(Length::Fixed(460.0)),
+            );
+        }
+
+        content = content.push(
+            text("Tab completes, Enter jumps, Esc closes").size(11).color(colors.text_secondary),
+        );
+
+        let overlay_box = container(content.padding(20))
+            .style(move |_theme| container::Style {
+                background: Some(colors.toolbar_bg.into()),
+                border: Border {
+                    color: colors.btn_border_top,
+                    width: 1.0,
+                    radius: Radius::from(8.0),
+                },
+                shadow: Shadow {
+                    color: Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+                    offset: iced::Vector::new(0.0, 4.0),
+                    blur_radius: 20.0,
+                },
+                ..Default::default()
+            });
+
+        let backdrop = button(Space::new().width(Fill).height(Fill))
+            .on_press(Message::ToggleGotoPath)
+            .style(|_theme, _status| button::Style {
+                background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.3).into()),
+                ..Default::default()
+            })
+            .width(Fill)
+            .height(Fill);
+
+        stack![
+            backdrop,
+            container(overlay_box)
+                .width(Fill)
+                .padding(iced::Padding { top: 80.0, ..Default::default() })
+                .center_x(Fill),
+        ].into()
+    }
+
     /// Render the CLI installation result dialog overlay
     fn render_cli_install_dialog(&self, colors: ThemeColors) -> Element<'_, Message> {
         let (success, message) = self.cli_install_result.as_ref().unwrap();
@@ -2322,6 +5446,26 @@ mod tests {
     }
 
     #[test]
+    fn test_append_root_rows_matches_full_flatten() {
+        let mut tree = build_tree(&json!([{"a": 1}, {"b": [2, 3]}]));
+        let last = tree.get_node(tree.root_index()).unwrap().children[1];
+        App::set_expanded_recursive(&mut tree, last, true);
+        let mut rows = App::flatten_visible_nodes(&tree);
+
+        parser::append_root_element(&mut tree, &json!({"c": true}));
+        parser::append_root_element(&mut tree, &json!(4));
+        App::append_root_rows(&tree, &mut rows, 2);
+
+        let expected = App::flatten_visible_nodes(&tree);
+        let summary = |rows: &[FlatRow]| -> Vec<(usize, String, String, usize)> {
+            rows.iter().map(|r| (r.node_index, r.prefix.clone(), r.path.clone(), r.row_index)).collect()
+        };
+        assert_eq!(summary(&rows), summary(&expected));
+        assert_eq!(rows[1].prefix, "├");
+    }
+
+    #[test]
+    #[allow(clippy::collapsible_if)]
     fn test_set_expanded_recursive() {
         let value = json!({
             "level1": {
@@ -2380,13 +5524,30 @@ mod tests {
     }
 
     #[test]
-    fn test_cli_file_argument_uses_first_positional_arg() {
+    fn test_cli_file_arguments_keeps_every_positional_arg() {
         let args = vec![
             "unfold".to_string(),
             "/tmp/package.json".to_string(),
-            "/tmp/ignored.json".to_string(),
+            "--path=/name".to_string(),
+            "/tmp/second.json".to_string(),
         ];
 
-        assert_eq!(cli_file_argument(&args), Some(PathBuf::from("/tmp/package.json")));
+        assert_eq!(
+            cli_file_arguments(&args),
+            vec![PathBuf::from("/tmp/package.json"), PathBuf::from("/tmp/second.json")]
+        );
+    }
+
+    #[test]
+    fn test_cli_path_argument() {
+        let args: Vec<String> = ["unfold", "--path", "users[0].name", "/tmp/package.json"]
+            .iter().map(|s| s.to_string()).collect();
+        assert_eq!(cli_file_arguments(&args), vec![PathBuf::from("/tmp/package.json")]);
+        assert_eq!(cli_path_argument(&args), Some("users[0].name".to_string()));
+
+        let args: Vec<String> = ["unfold", "/tmp/package.json", "--path=/users/0"]
+            .iter().map(|s| s.to_string()).collect();
+        assert_eq!(cli_file_arguments(&args), vec![PathBuf::from("/tmp/package.json")]);
+        assert_eq!(cli_path_argument(&args), Some("/users/0".to_string()));
     }
 }
@@ -3,13 +3,14 @@
 //! Provides cross-platform native menus for macOS, Windows, and Linux.
 
 use muda::{
-    Menu, Submenu, MenuItem, PredefinedMenuItem, MenuEvent,
+    Menu, Submenu, MenuItem, CheckMenuItem, PredefinedMenuItem, MenuEvent,
     accelerator::{Accelerator, Modifiers as MudaModifiers, Code},
 };
 #[cfg(target_os = "macos")]
 use muda::AboutMetadata;
 
-use crate::config::Config;
+use crate::config::{Config, RecentFile};
+use crate::keymap::{Command, Keymap};
 use crate::message::Message;
 
 /// Menu item identifiers for handling events
@@ -18,12 +19,35 @@ pub mod menu_ids {
     pub const CHECK_UPDATES: &str = "check_updates";
     pub const INSTALL_CLI: &str = "install_cli";
     pub const OPEN_FILE: &str = "open_file";
-    pub const OPEN_NEW_WINDOW: &str = "open_new_window";
+    pub const OPEN_NEW_TAB: &str = "open_new_tab";
+    /// Followed by the entry's index in the recent files list
+    pub const OPEN_RECENT_PREFIX: &str = "open_recent:";
+    pub const CLEAR_RECENT: &str = "clear_recent";
+    pub const NEW_TAB: &str = "new_tab";
+    pub const CLOSE_TAB: &str = "close_tab";
+    pub const RELOAD_FILE: &str = "reload_file";
+    pub const RESTORE_SESSION: &str = "restore_session";
+    pub const QUIT: &str = "quit";
     pub const OPEN_EXTERNAL: &str = "open_external";
+    pub const COMPARE_FILES: &str = "compare_files";
+    pub const APPLY_PATCH: &str = "apply_patch";
+    pub const SAVE_AS: &str = "save_as";
+    pub const ATTACH_SCHEMA: &str = "attach_schema";
     pub const COPY_VALUE: &str = "copy_value";
     pub const COPY_KEY: &str = "copy_key";
     pub const COPY_PATH: &str = "copy_path";
+    pub const NAVIGATE_BACK: &str = "navigate_back";
+    pub const NAVIGATE_FORWARD: &str = "navigate_forward";
+    pub const GOTO_PATH: &str = "goto_path";
+    pub const TOGGLE_BOOKMARK: &str = "toggle_bookmark";
+    pub const NEXT_BOOKMARK: &str = "next_bookmark";
+    pub const PREV_BOOKMARK: &str = "prev_bookmark";
+    pub const BOOKMARKS_PANEL: &str = "bookmarks_panel";
+    pub const VIM_KEYS: &str = "vim_keys";
+    pub const COMMAND_PALETTE: &str = "command_palette";
     pub const TOGGLE_THEME: &str = "toggle_theme";
+    pub const STATISTICS: &str = "statistics";
+    pub const FORMAT_OPTIONS: &str = "format_options";
     pub const KEYBOARD_SHORTCUTS: &str = "keyboard_shortcuts";
     // Context menu items
     pub const EXPORT_JSON: &str = "export_json";
@@ -36,9 +60,56 @@ pub mod menu_ids {
 thread_local! {
     static APP_MENU: std::cell::RefCell<Option<Menu>> = const { std::cell::RefCell::new(None) };
     static CONTEXT_MENU: std::cell::RefCell<Option<Menu>> = const { std::cell::RefCell::new(None) };
+    static RECENT_MENU: std::cell::RefCell<Option<Submenu>> = const { std::cell::RefCell::new(None) };
     static MENU_INIT_COUNTER: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
 }
 
+/// The menu shortcut for a command: its first key the menu can show
+fn accelerator(keymap: &Keymap, command: Command) -> Option<Accelerator> {
+    keymap.keys_for(command).find_map(|binding| {
+        let code = key_code(&binding.key)?;
+        let modifiers = [
+            (binding.cmd, MudaModifiers::SUPER),
+            (binding.ctrl, MudaModifiers::CONTROL),
+            (binding.alt, MudaModifiers::ALT),
+            (binding.shift, MudaModifiers::SHIFT),
+        ]
+        .into_iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, modifier)| modifier)
+        .reduce(|a, b| a | b);
+        Some(Accelerator::new(modifiers, code))
+    })
+}
+
+/// The physical key for a key name from the keymap
+fn key_code(key: &str) -> Option<Code> {
+    let code = match key {
+        "a" => Code::KeyA, "b" => Code::KeyB, "c" => Code::KeyC, "d" => Code::KeyD,
+        "e" => Code::KeyE, "f" => Code::KeyF, "g" => Code::KeyG, "h" => Code::KeyH,
+        "i" => Code::KeyI, "j" => Code::KeyJ, "k" => Code::KeyK, "l" => Code::KeyL,
+        "m" => Code::KeyM, "n" => Code::KeyN, "o" => Code::KeyO, "p" => Code::KeyP,
+        "q" => Code::KeyQ, "r" => Code::KeyR, "s" => Code::KeyS, "t" => Code::KeyT,
+        "u" => Code::KeyU, "v" => Code::KeyV, "w" => Code::KeyW, "x" => Code::KeyX,
+        "y" => Code::KeyY, "z" => Code::KeyZ,
+        "0" => Code::Digit0, "1" => Code::Digit1, "2" => Code::Digit2, "3" => Code::Digit3,
+        "4" => Code::Digit4, "5" => Code::Digit5, "6" => Code::Digit6, "7" => Code::Digit7,
+        "8" => Code::Digit8, "9" => Code::Digit9,
+        "/" => Code::Slash, "[" => Code::BracketLeft, "]" => Code::BracketRight,
+        "," => Code::Comma, "." => Code::Period, ";" => Code::Semicolon, "'" => Code::Quote,
+        "-" => Code::Minus, "=" => Code::Equal, "\\" => Code::Backslash, "`" => Code::Backquote,
+        "up" => Code::ArrowUp, "down" => Code::ArrowDown, "left" => Code::ArrowLeft, "right" => Code::ArrowRight,
+        "home" => Code::Home, "end" => Code::End, "pageup" => Code::PageUp, "pagedown" => Code::PageDown,
+        "enter" => Code::Enter, "tab" => Code::Tab, "space" => Code::Space, "escape" => Code::Escape,
+        "backspace" => Code::Backspace, "delete" => Code::Delete,
+        "f1" => Code::F1, "f2" => Code::F2, "f3" => Code::F3, "f4" => Code::F4,
+        "f5" => Code::F5, "f6" => Code::F6, "f7" => Code::F7, "f8" => Code::F8,
+        "f9" => Code::F9, "f10" => Code::F10, "f11" => Code::F11, "f12" => Code::F12,
+        _ => return None,
+    };
+    Some(code)
+}
+
 /// Create the native application menu bar
 pub fn create_app_menu() -> Menu {
     let menu = Menu::new();
@@ -46,6 +117,7 @@ pub fn create_app_menu() -> Menu {
     // Load config to check if CLI is already installed (used on all platforms)
     let config = Config::load();
     let cli_not_installed = !config.cli_installed;
+    let keymap = Keymap::load();
 
     // ===== App Menu (macOS) =====
     #[cfg(target_os = "macos")]
@@ -78,35 +150,94 @@ pub fn create_app_menu() -> Menu {
             &PredefinedMenuItem::hide_others(None),
             &PredefinedMenuItem::show_all(None),
             &PredefinedMenuItem::separator(),
-            &PredefinedMenuItem::quit(None),
+            // Not the predefined item: quitting must go through the app to save the session
+            &MenuItem::with_id(
+                menu_ids::QUIT,
+                "Quit Unfold",
+                true,
+                accelerator(&keymap, Command::Quit),
+            ),
         ]);
         let _ = menu.append(&app_menu);
     }
 
     // ===== File Menu =====
+    let recent_menu = Submenu::new("Open Recent", true);
+    fill_recent_menu(&recent_menu, &config.recent_files);
+    RECENT_MENU.with(|m| *m.borrow_mut() = Some(recent_menu.clone()));
+
     let file_menu = Submenu::new("File", true);
     let _ = file_menu.append_items(&[
         &MenuItem::with_id(
             menu_ids::OPEN_FILE,
             "Open...",
             true,
-            Some(Accelerator::new(Some(MudaModifiers::SUPER), Code::KeyO)),
+            accelerator(&keymap, Command::OpenFile),
+        ),
+        &MenuItem::with_id(
+            menu_ids::OPEN_NEW_TAB,
+            "Open in New Tab...",
+            true,
+            accelerator(&keymap, Command::OpenInNewTab),
+        ),
+        &recent_menu,
+        &MenuItem::with_id(
+            menu_ids::NEW_TAB,
+            "New Tab",
+            true,
+            None,
+        ),
+        &MenuItem::with_id(
+            menu_ids::RELOAD_FILE,
+            "Reload",
+            true,
+            accelerator(&keymap, Command::ReloadFile),
+        ),
+        &MenuItem::with_id(
+            menu_ids::COMPARE_FILES,
+            "Compare With...",
+            true,
+            accelerator(&keymap, Command::CompareWithFile),
+        ),
+        &MenuItem::with_id(
+            menu_ids::APPLY_PATCH,
+            "Apply Patch...",
+            true,
+            None,
+        ),
+        &MenuItem::with_id(
+            menu_ids::SAVE_AS,
+            "Save As...",
+            true,
+            accelerator(&keymap, Command::SaveAs),
         ),
         &MenuItem::with_id(
-            menu_ids::OPEN_NEW_WINDOW,
-            "Open in New Window...",
+            menu_ids::ATTACH_SCHEMA,
+            "Attach Schema...",
             true,
-            Some(Accelerator::new(Some(MudaModifiers::SUPER), Code::KeyN)),
+            None,
         ),
         &PredefinedMenuItem::separator(),
         &MenuItem::with_id(
             menu_ids::OPEN_EXTERNAL,
             "Open in External Editor",
             true,
-            Some(Accelerator::new(Some(MudaModifiers::SUPER | MudaModifiers::SHIFT), Code::KeyE)),
+            accelerator(&keymap, Command::OpenExternal),
+        ),
+        &CheckMenuItem::with_id(
+            menu_ids::RESTORE_SESSION,
+            "Reopen Files at Launch",
+            true,
+            config.restore_session,
+            None::<Accelerator>,
         ),
         &PredefinedMenuItem::separator(),
-        &PredefinedMenuItem::close_window(None),
+        &MenuItem::with_id(
+            menu_ids::CLOSE_TAB,
+            "Close Tab",
+            true,
+            accelerator(&keymap, Command::CloseTab),
+        ),
     ]);
     let _ = menu.append(&file_menu);
 
@@ -120,25 +251,38 @@ pub fn create_app_menu() -> Menu {
             menu_ids::COPY_VALUE,
             "Copy Value",
             true,
-            Some(Accelerator::new(Some(MudaModifiers::SUPER), Code::KeyC)),
+            accelerator(&keymap, Command::CopyValue),
         ),
         &MenuItem::with_id(
             menu_ids::COPY_KEY,
             "Copy Key",
             true,
-            Some(Accelerator::new(
-                Some(MudaModifiers::SUPER | MudaModifiers::SHIFT),
-                Code::KeyC,
-            )),
+            accelerator(&keymap, Command::CopyKey),
         ),
         &MenuItem::with_id(
             menu_ids::COPY_PATH,
             "Copy Path",
             true,
-            Some(Accelerator::new(
-                Some(MudaModifiers::SUPER | MudaModifiers::ALT),
-                Code::KeyC,
-            )),
+            accelerator(&keymap, Command::CopyPath),
+        ),
+        &PredefinedMenuItem::separator(),
+        &MenuItem::with_id(
+            menu_ids::TOGGLE_BOOKMARK,
+            "Toggle Bookmark",
+            true,
+            accelerator(&keymap, Command::ToggleBookmark),
+        ),
+        &MenuItem::with_id(
+            menu_ids::NEXT_BOOKMARK,
+            "Next Bookmark",
+            true,
+            accelerator(&keymap, Command::NextBookmark),
+        ),
+        &MenuItem::with_id(
+            menu_ids::PREV_BOOKMARK,
+            "Previous Bookmark",
+            true,
+            accelerator(&keymap, Command::PreviousBookmark),
         ),
     ]);
     let _ = menu.append(&edit_menu);
@@ -146,17 +290,74 @@ pub fn create_app_menu() -> Menu {
     // ===== View Menu =====
     let view_menu = Submenu::new("View", true);
     let _ = view_menu.append_items(&[
+        &MenuItem::with_id(
+            menu_ids::COMMAND_PALETTE,
+            "Command Palette...",
+            true,
+            accelerator(&keymap, Command::Palette),
+        ),
+        &PredefinedMenuItem::separator(),
         &MenuItem::with_id(
             menu_ids::TOGGLE_THEME,
             "Toggle Theme",
             true,
-            Some(Accelerator::new(Some(MudaModifiers::SUPER), Code::KeyT)),
+            accelerator(&keymap, Command::ToggleTheme),
+        ),
+        &MenuItem::with_id(
+            menu_ids::STATISTICS,
+            "Document Statistics",
+            true,
+            None,
+        ),
+        &MenuItem::with_id(
+            menu_ids::BOOKMARKS_PANEL,
+            "Bookmarks",
+            true,
+            accelerator(&keymap, Command::BookmarksPanel),
+        ),
+        &MenuItem::with_id(
+            menu_ids::FORMAT_OPTIONS,
+            "Formatting Options...",
+            true,
+            None,
+        ),
+        &CheckMenuItem::with_id(
+            menu_ids::VIM_KEYS,
+            "Vim Key Bindings",
+            true,
+            config.vim_keys,
+            None::<Accelerator>,
         ),
         &PredefinedMenuItem::separator(),
         &PredefinedMenuItem::fullscreen(None),
     ]);
     let _ = menu.append(&view_menu);
 
+    // ===== Go Menu =====
+    let go_menu = Submenu::new("Go", true);
+    let _ = go_menu.append_items(&[
+        &MenuItem::with_id(
+            menu_ids::NAVIGATE_BACK,
+            "Back",
+            true,
+            accelerator(&keymap, Command::Back),
+        ),
+        &MenuItem::with_id(
+            menu_ids::NAVIGATE_FORWARD,
+            "Forward",
+            true,
+            accelerator(&keymap, Command::Forward),
+        ),
+        &PredefinedMenuItem::separator(),
+        &MenuItem::with_id(
+            menu_ids::GOTO_PATH,
+            "Go to Path...",
+            true,
+            accelerator(&keymap, Command::GoToPath),
+        ),
+    ]);
+    let _ = menu.append(&go_menu);
+
     // ===== Window Menu (macOS) =====
     #[cfg(target_os = "macos")]
     {
@@ -177,7 +378,7 @@ pub fn create_app_menu() -> Menu {
             menu_ids::KEYBOARD_SHORTCUTS,
             "Keyboard Shortcuts",
             true,
-            Some(Accelerator::new(Some(MudaModifiers::SUPER), Code::Slash)),
+            accelerator(&keymap, Command::Help),
         ),
         &PredefinedMenuItem::separator(),
         &MenuItem::with_id(
@@ -198,33 +399,59 @@ pub fn create_app_menu() -> Menu {
     menu
 }
 
+/// Add one item per recent file, then "Clear Menu"
+fn fill_recent_menu(submenu: &Submenu, recent_files: &[RecentFile]) {
+    for (index, recent) in recent_files.iter().enumerate() {
+        let _ = submenu.append(&MenuItem::with_id(
+            format!("{}{}", menu_ids::OPEN_RECENT_PREFIX, index),
+            recent.path.display().to_string(),
+            true,
+            None::<Accelerator>,
+        ));
+    }
+    if !recent_files.is_empty() {
+        let _ = submenu.append(&PredefinedMenuItem::separator());
+    }
+    let _ = submenu.append(&MenuItem::with_id(
+        menu_ids::CLEAR_RECENT,
+        "Clear Menu",
+        !recent_files.is_empty(),
+        None::<Accelerator>,
+    ));
+}
+
+/// Rebuild the "Open Recent" submenu after the recent files list changed
+pub fn update_recent_files_menu(recent_files: &[RecentFile]) {
+    RECENT_MENU.with(|m| {
+        if let Some(submenu) = m.borrow().as_ref() {
+            while submenu.remove_at(0).is_some() {}
+            fill_recent_menu(submenu, recent_files);
+        }
+    });
+}
+
 /// Create context menu for right-click on nodes
 pub fn create_context_menu() -> Menu {
     let menu = Menu::new();
+    let keymap = Keymap::load();
     let _ = menu.append_items(&[
         &MenuItem::with_id(
             menu_ids::COPY_VALUE,
             "Copy Value",
             true,
-            Some(Accelerator::new(Some(MudaModifiers::SUPER), Code::KeyC)),
+            accelerator(&keymap, Command::CopyValue),
         ),
         &MenuItem::with_id(
             menu_ids::COPY_KEY,
             "Copy Key",
             true,
-            Some(Accelerator::new(
-                Some(MudaModifiers::SUPER | MudaModifiers::SHIFT),
-                Code::KeyC,
-            )),
+            accelerator(&keymap, Command::CopyKey),
         ),
         &MenuItem::with_id(
             menu_ids::COPY_PATH,
             "Copy Path",
             true,
-            Some(Accelerator::new(
-                Some(MudaModifiers::SUPER | MudaModifiers::ALT),
-                Code::KeyC,
-            )),
+            accelerator(&keymap, Command::CopyPath),
         ),
         &PredefinedMenuItem::separator(),
         &MenuItem::with_id(menu_ids::EXPORT_JSON, "Export JSON...", true, None::<Accelerator>),
@@ -267,13 +494,37 @@ pub fn try_initialize_menu() -> bool {
 
 /// Convert a menu event to a Message
 pub fn menu_event_to_message(event: &muda::MenuEvent) -> Message {
+    let recent_index = event.id().as_ref()
+        .strip_prefix(menu_ids::OPEN_RECENT_PREFIX)
+        .and_then(|index| index.parse().ok());
+    if let Some(index) = recent_index {
+        return Message::OpenRecentFile(index);
+    }
+
     match event.id().as_ref() {
         id if id == menu_ids::OPEN_FILE => Message::OpenFileDialog,
-        id if id == menu_ids::OPEN_NEW_WINDOW => Message::OpenFileInNewWindow,
+        id if id == menu_ids::OPEN_NEW_TAB => Message::OpenFileInNewTab,
+        id if id == menu_ids::CLEAR_RECENT => Message::ClearRecentFiles,
+        id if id == menu_ids::NEW_TAB => Message::NewTab,
+        id if id == menu_ids::CLOSE_TAB => Message::CloseActiveTab,
+        id if id == menu_ids::RELOAD_FILE => Message::ReloadFile,
+        id if id == menu_ids::RESTORE_SESSION => Message::ToggleRestoreSession,
+        id if id == menu_ids::QUIT => Message::Quit,
         id if id == menu_ids::COPY_VALUE => Message::CopySelectedValue,
         id if id == menu_ids::COPY_KEY => Message::CopySelectedName,
         id if id == menu_ids::COPY_PATH => Message::CopySelectedPath,
+        id if id == menu_ids::NAVIGATE_BACK => Message::NavigateBack,
+        id if id == menu_ids::NAVIGATE_FORWARD => Message::NavigateForward,
+        id if id == menu_ids::GOTO_PATH => Message::ToggleGotoPath,
+        id if id == menu_ids::TOGGLE_BOOKMARK => Message::ToggleBookmark,
+        id if id == menu_ids::NEXT_BOOKMARK => Message::NextBookmark,
+        id if id == menu_ids::PREV_BOOKMARK => Message::PrevBookmark,
+        id if id == menu_ids::BOOKMARKS_PANEL => Message::ToggleBookmarksPanel,
+        id if id == menu_ids::VIM_KEYS => Message::ToggleVimKeys,
+        id if id == menu_ids::COMMAND_PALETTE => Message::ToggleCommandPalette,
         id if id == menu_ids::TOGGLE_THEME => Message::ToggleTheme,
+        id if id == menu_ids::STATISTICS => Message::ToggleStatsPanel,
+        id if id == menu_ids::FORMAT_OPTIONS => Message::ToggleFormatOptions,
         id if id == menu_ids::KEYBOARD_SHORTCUTS => Message::ToggleHelp,
         id if id == menu_ids::CHECK_UPDATES => Message::CheckForUpdates,
         id if id == menu_ids::INSTALL_CLI => Message::InstallCLI,
@@ -281,6 +532,10 @@ pub fn menu_event_to_message(event: &muda::MenuEvent) -> Message {
         id if id == menu_ids::EXPAND_ALL => Message::ExpandAllChildren,
         id if id == menu_ids::COLLAPSE_ALL => Message::CollapseAllChildren,
         id if id == menu_ids::OPEN_EXTERNAL => Message::OpenInExternalEditor,
+        id if id == menu_ids::COMPARE_FILES => Message::CompareWithFile,
+        id if id == menu_ids::APPLY_PATCH => Message::ApplyPatchFile,
+        id if id == menu_ids::SAVE_AS => Message::SaveDocumentAs,
+        id if id == menu_ids::ATTACH_SCHEMA => Message::AttachSchema,
         _ => Message::NoOp, // PredefinedMenuItems handled by OS
     }
 }
@@ -301,18 +556,63 @@ mod tests {
             menu_ids::CHECK_UPDATES,
             menu_ids::INSTALL_CLI,
             menu_ids::OPEN_FILE,
-            menu_ids::OPEN_NEW_WINDOW,
+            menu_ids::OPEN_NEW_TAB,
+            menu_ids::OPEN_RECENT_PREFIX,
+            menu_ids::CLEAR_RECENT,
+            menu_ids::NEW_TAB,
+            menu_ids::CLOSE_TAB,
+            menu_ids::RELOAD_FILE,
+            menu_ids::RESTORE_SESSION,
+            menu_ids::QUIT,
             menu_ids::COPY_VALUE,
             menu_ids::COPY_KEY,
             menu_ids::COPY_PATH,
+            menu_ids::NAVIGATE_BACK,
+            menu_ids::NAVIGATE_FORWARD,
+            menu_ids::GOTO_PATH,
+            menu_ids::TOGGLE_BOOKMARK,
+            menu_ids::NEXT_BOOKMARK,
+            menu_ids::PREV_BOOKMARK,
+            menu_ids::BOOKMARKS_PANEL,
+            menu_ids::VIM_KEYS,
+            menu_ids::COMMAND_PALETTE,
             menu_ids::TOGGLE_THEME,
+            menu_ids::STATISTICS,
+            menu_ids::FORMAT_OPTIONS,
             menu_ids::KEYBOARD_SHORTCUTS,
             menu_ids::EXPORT_JSON,
             menu_ids::EXPAND_ALL,
             menu_ids::COLLAPSE_ALL,
+            menu_ids::OPEN_EXTERNAL,
+            menu_ids::COMPARE_FILES,
+            menu_ids::APPLY_PATCH,
+            menu_ids::SAVE_AS,
+            menu_ids::ATTACH_SCHEMA,
         ];
 
         let unique: std::collections::HashSet<_> = ids.iter().collect();
         assert_eq!(ids.len(), unique.len(), "Menu IDs must be unique");
     }
+
+    #[test]
+    fn test_recent_file_menu_events() {
+        let event = MenuEvent { id: format!("{}3", menu_ids::OPEN_RECENT_PREFIX).into() };
+        assert!(matches!(menu_event_to_message(&event), Message::OpenRecentFile(3)));
+
+        let event = MenuEvent { id: menu_ids::CLEAR_RECENT.into() };
+        assert!(matches!(menu_event_to_message(&event), Message::ClearRecentFiles));
+    }
+
+    #[test]
+    fn test_default_keys_have_menu_codes() {
+        let keymap = Keymap::default();
+        for command in Command::ALL {
+            // Shifted symbols have no key of their own
+            for binding in keymap.keys_for(command).filter(|binding| !matches!(binding.key.as_str(), "*" | "?")) {
+                assert!(key_code(&binding.key).is_some(), "no menu key for {}", binding);
+            }
+        }
+        assert_eq!(key_code("["), Some(Code::BracketLeft));
+        assert_eq!(key_code("*"), None);
+    }
 }
@@ -6,6 +6,16 @@ use iced::keyboard::{Key, Modifiers};
 use iced::widget::scrollable::Viewport;
 use std::path::PathBuf;
 
+use crate::codegen::TypeLanguage;
+use crate::config::FormatOptions;
+use crate::csv_export::Delimiter;
+use crate::diff::ArrayMatching;
+use crate::formats::TextFormat;
+use crate::patch::PatchFormat;
+use crate::search::SearchFilter;
+use crate::session::Session;
+use crate::stats::HeavySort;
+use crate::tree_nav::TreeMove;
 use crate::update_check::UpdateCheckState;
 
 /// Messages that can be sent to update the app
@@ -16,6 +26,18 @@ pub enum Message {
     OpenFileDialog,
     FileSelected(Option<PathBuf>),
     FileDropped(PathBuf),
+    /// The watcher saw an open file change on disk
+    FileChangedOnDisk(PathBuf),
+    /// Re-read the open file, keeping expansion, selection and search
+    ReloadFile,
+    /// Turn JSON Lines follow mode on or off
+    ToggleFollow,
+    /// Catch validation and statistics up with lines appended in follow mode
+    RefreshFollowAnalysis,
+    /// Hide the "file changed" prompt without reloading
+    DismissFileChanged,
+    /// Reload changed files without asking (saved to config)
+    SetAutoReload(bool),
     ToggleNode(usize),
     Scrolled(Viewport),
     SearchQueryChanged(String),
@@ -23,19 +45,67 @@ pub enum Message {
     SearchPrev,
     ToggleCaseSensitive,
     ToggleRegex,
+    /// Restrict search to keys, values, or value types
+    SetSearchFilter(SearchFilter),
     /// Keyboard events - Key and Modifiers tell us what was pressed
     KeyPressed(Key, Modifiers),
     ModifiersChanged(Modifiers),
     ClearSearch,
     FocusSearch,
+    /// Limit search to the selected node's descendants
+    SearchInSelection,
+    ClearSearchScope,
     /// Search submit from text input (checks current_modifiers for Shift)
     SearchSubmit,
-    /// Open file dialog, then open selected file in new window
-    OpenFileInNewWindow,
-    /// File was selected for opening in new window
-    FileSelectedForNewWindow(Option<PathBuf>),
+    /// Open file dialog, then open the selected file in a new tab
+    OpenFileInNewTab,
+    /// Open files in new tabs (reusing the active tab if it is empty)
+    OpenInNewTabs(Vec<PathBuf>),
+    /// Open an entry of the recent files list (by index)
+    OpenRecentFile(usize),
+    ClearRecentFiles,
+    /// Add an empty tab
+    NewTab,
+    /// Switch to a tab
+    SelectTab(usize),
+    /// Switch to the next (`true`) or previous tab, wrapping around
+    CycleTab(bool),
+    /// Close a tab
+    CloseTab(usize),
+    CloseActiveTab,
+    /// Mouse pressed on a tab: select it and start dragging
+    TabDragStart(usize),
+    /// Dragged tab moved over another tab's position
+    TabDragOver(usize),
+    /// Mouse released or left the tab bar
+    TabDragEnd,
+    /// Reopen the tabs saved by the previous session
+    RestoreSession(Session),
+    /// Turn session restore at launch on or off (saved to config)
+    ToggleRestoreSession,
+    /// Window closed or Quit chosen: save the session and exit
+    Quit,
     /// Select a node (for copy, path display)
     SelectNode(usize),
+    /// Move the selection with the keyboard
+    MoveSelection(TreeMove),
+    /// Turn the vim keymap on or off (saved to config)
+    ToggleVimKeys,
+    /// Go back / forward in the navigation history
+    NavigateBack,
+    NavigateForward,
+    /// Bookmark the selected node, or remove its bookmark
+    ToggleBookmark,
+    NextBookmark,
+    PrevBookmark,
+    /// Select and reveal a bookmark's node (by position in the list)
+    JumpToBookmark(usize),
+    RemoveBookmark(usize),
+    /// A label was edited (kept in memory until submitted)
+    BookmarkLabelChanged(usize, String),
+    /// Enter in a label field: write the labels to disk
+    BookmarkLabelSubmitted,
+    ToggleBookmarksPanel,
     /// Copy selected node's value to clipboard
     CopySelectedValue,
     /// Copy selected node's key/name to clipboard
@@ -73,16 +143,127 @@ pub enum Message {
     /// Copy value in specific format
     CopyValueMinified,
     CopyValueFormatted,
+    /// Copy value as RFC 8785 canonical JSON
+    CopyValueCanonical,
+    /// Copy the SHA-256 of the value's canonical JSON
+    CopySubtreeHash,
+    /// Copy value converted to YAML, TOML or XML
+    CopyValueAs(TextFormat),
+    /// Copy type definitions generated from the selected value
+    CopyTypeAs(TypeLanguage),
     /// Export value in specific format
     ExportAsJson,
     ExportAsMinifiedJson,
     ExportAsFormattedJson,
+    /// Export value as RFC 8785 canonical JSON
+    ExportAsCanonicalJson,
+    /// Export value converted to YAML, TOML or XML
+    ExportAs(TextFormat),
+    /// Open the CSV/TSV export dialog for the selected array of objects
+    ExportAsDelimited(Delimiter),
+    /// Switch the delimiter in the CSV/TSV export dialog
+    CsvSetDelimiter(Delimiter),
+    /// Move an export column up (`true`) or down
+    CsvMoveColumn(usize, bool),
+    /// Include or exclude an export column
+    CsvToggleColumn(usize),
+    /// Pick a file and write the CSV/TSV export
+    CsvExportConfirm,
+    /// Close the CSV/TSV export dialog
+    CsvExportCancel,
+    /// Export a JSON Schema inferred from the selected value
+    ExportAsSchema,
+    /// Open a JSON Schema inferred from the selected value in a new window
+    ViewInferredSchema,
     /// Install CLI command to /usr/local/bin
     InstallCLI,
     /// Result of CLI installation attempt
     InstallCLIResult(Result<String, String>),
     /// Dismiss CLI install dialog
     DismissCLIDialog,
+    /// Pick a file to compare against the current one (or two files)
+    CompareWithFile,
+    /// Files chosen for comparison (one: against current file, two: left/right)
+    CompareFilesSelected(Vec<PathBuf>),
+    /// Change how array elements are paired in compare mode
+    SetArrayMatching(ArrayMatching),
+    /// "Match by key" input in the compare bar changed
+    CompareMatchKeyChanged(String),
+    /// Leave compare mode and reopen the right-hand file
+    ExitCompare,
+    /// Pick a JSON Patch / Merge Patch file to apply to the document
+    ApplyPatchFile,
+    /// Patch file was selected
+    PatchFileSelected(Option<PathBuf>),
+    /// Discard applied patches and restore the original document
+    RevertPatch,
+    /// Generate a patch from the current edits or compared files and save it
+    GeneratePatch(PatchFormat),
+    /// Save the whole (possibly patched) document to a new file
+    SaveDocumentAs,
+    /// Pick a JSON Schema file to validate the document against
+    AttachSchema,
+    /// Schema file was selected
+    SchemaFileSelected(Option<PathBuf>),
+    /// Remove the attached schema and its problem markers
+    DetachSchema,
+    /// Show or hide the schema problems panel
+    ToggleProblemsPanel,
+    /// Show or hide the document statistics panel
+    ToggleStatsPanel,
+    /// Show or hide the formatting options dialog
+    ToggleFormatOptions,
+    /// Pretty-printer settings changed (saved to config)
+    SetFormatOptions(FormatOptions),
+    /// Change the order of the "heaviest nodes" list
+    SetStatsSort(HeavySort),
+    /// Select a node and scroll it into view
+    JumpToNode(usize),
+    /// Open the breadcrumb dropdown listing the children of a node, at an x offset
+    ShowBreadcrumbMenu(usize, f32),
+    HideBreadcrumbMenu,
+    /// Open or close the go-to-path dialog
+    ToggleGotoPath,
+    /// Escape: close the topmost dialog or overlay, or else clear the search
+    Dismiss,
+    /// Go-to-path input edited
+    GotoPathChanged(String),
+    /// Take a completion from the go-to-path dialog
+    GotoPathComplete(String),
+    /// Take the first completion offered in the go-to-path dialog
+    CompleteGotoPath,
+    /// Jump to the path typed in the go-to-path dialog
+    GotoPathSubmit,
+    /// Jump to a node by path (from `--path` on the command line)
+    GotoPath(String),
+    /// Open or close the command palette
+    ToggleCommandPalette,
+    /// Command palette query edited
+    CommandPaletteChanged(String),
+    /// Move the palette highlight down (`true`) or up
+    CommandPaletteMove(bool),
+    /// Run the highlighted palette entry
+    CommandPaletteSubmit,
+    /// Run a palette entry (by position in the filtered list)
+    CommandPaletteRun(usize),
+    /// Show an array of objects as a grid
+    ShowTable(usize),
+    /// Leave the grid and return to the tree
+    CloseTable,
+    /// Grid body scrolled (for virtual scrolling)
+    TableScrolled(Viewport),
+    /// Sort the grid by a column (ascending, descending, document order)
+    SortTable(usize),
+    /// Start dragging the right border of a grid column
+    TableResizeStart(usize),
+    /// Cursor x position while a grid column is being resized
+    TableResizeMove(f32),
+    /// Stop resizing a grid column
+    TableResizeEnd,
+    /// Leave the grid and reveal a row or nested cell in the tree
+    OpenTableCell(usize),
+    /// Select and reveal the node of a schema problem (index into the violations)
+    JumpToProblem(usize),
 }
 
 /// Which submenu is currently open in context menu
@@ -90,6 +271,7 @@ pub enum Message {
 pub enum ContextSubmenu {
     None,
     CopyValueAs,
+    CopyTypeAs,
     ExportValueAs,
 }
 
@@ -0,0 +1,225 @@
+//! Command palette: every action in one fuzzy-searchable list.
+//!
+//! Entries are built when the palette opens, from the keymap's commands
+//! (with their current shortcuts), the actions that only live in menus, and
+//! the recent files. Choosing an entry dispatches its [`Message`].
+
+use crate::codegen::TypeLanguage;
+use crate::config::RecentFile;
+use crate::csv_export::Delimiter;
+use crate::formats::TextFormat;
+use crate::keymap::{Command, Keymap};
+use crate::message::Message;
+use crate::patch::PatchFormat;
+
+/// Most entries shown at once
+pub const VISIBLE_ENTRIES: usize = 12;
+
+/// One action in the palette
+#[derive(Debug, Clone)]
+pub struct PaletteEntry {
+    pub title: String,
+    /// Label of the first key bound to the action, if any
+    pub shortcut: Option<String>,
+    pub message: Message,
+}
+
+/// Every action available right now
+pub fn entries(keymap: &Keymap, recent_files: &[RecentFile]) -> Vec<PaletteEntry> {
+    let mut entries: Vec<PaletteEntry> = Command::ALL.into_iter()
+        // Keys that only make sense with a dialog open aren't actions to pick
+        .filter(|&command| !matches!(command, Command::Palette | Command::Dismiss | Command::CompletePath))
+        .map(|command| PaletteEntry {
+            title: format!("{}: {}", command.section().title(), command.description()),
+            shortcut: keymap.keys_for(command).next().map(|binding| binding.label()),
+            message: command.message(),
+        })
+        .collect();
+
+    let mut add = |title: String, message: Message| {
+        entries.push(PaletteEntry { title, shortcut: None, message });
+    };
+    add("File: New tab".to_string(), Message::NewTab);
+    add("File: Apply patch...".to_string(), Message::ApplyPatchFile);
+    add("File: Attach schema...".to_string(), Message::AttachSchema);
+    add("File: Generate JSON Patch".to_string(), Message::GeneratePatch(PatchFormat::JsonPatch));
+    add("File: Generate JSON Merge Patch".to_string(), Message::GeneratePatch(PatchFormat::MergePatch));
+    add("File: Reopen files at launch (on/off)".to_string(), Message::ToggleRestoreSession);
+    add("File: Clear recent files".to_string(), Message::ClearRecentFiles);
+    add("Edit: Copy value as minified JSON".to_string(), Message::CopyValueMinified);
+    add("Edit: Copy value as formatted JSON".to_string(), Message::CopyValueFormatted);
+    add("Edit: Copy value as canonical JSON".to_string(), Message::CopyValueCanonical);
+    for format in [TextFormat::Yaml, TextFormat::Toml, TextFormat::Xml] {
+        add(format!("Edit: Copy value as {}", format.label()), Message::CopyValueAs(format));
+    }
+    add("Edit: Copy SHA-256".to_string(), Message::CopySubtreeHash);
+    for language in [TypeLanguage::Rust, TypeLanguage::TypeScript, TypeLanguage::Go] {
+        add(format!("Edit: Copy type as {}", language.label()), Message::CopyTypeAs(language));
+    }
+    add("Export: JSON".to_string(), Message::ExportAsJson);
+    add("Export: Minified JSON".to_string(), Message::ExportAsMinifiedJson);
+    add("Export: Formatted JSON".to_string(), Message::ExportAsFormattedJson);
+    add("Export: Canonical JSON".to_string(), Message::ExportAsCanonicalJson);
+    add("Export: JSON Schema".to_string(), Message::ExportAsSchema);
+    for format in [TextFormat::Yaml, TextFormat::Toml, TextFormat::Xml] {
+        add(format!("Export: {}", format.label()), Message::ExportAs(format));
+    }
+    add("Export: CSV...".to_string(), Message::ExportAsDelimited(Delimiter::Comma));
+    add("Export: TSV...".to_string(), Message::ExportAsDelimited(Delimiter::Tab));
+    add("Navigate: Collapse all children".to_string(), Message::CollapseAllChildren);
+    add("View: Generate schema".to_string(), Message::ViewInferredSchema);
+    add("View: Document statistics".to_string(), Message::ToggleStatsPanel);
+    add("View: Formatting options...".to_string(), Message::ToggleFormatOptions);
+    add("View: Vim key bindings (on/off)".to_string(), Message::ToggleVimKeys);
+    add("Help: Check for updates...".to_string(), Message::CheckForUpdates);
+    add("Help: Install command line tool...".to_string(), Message::InstallCLI);
+
+    for (index, recent) in recent_files.iter().enumerate() {
+        let name = recent.path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| recent.path.display().to_string());
+        add(format!("Open recent: {}", name), Message::OpenRecentFile(index));
+    }
+    entries
+}
+
+/// How well `query` matches `text`: its characters must appear in order
+/// (ignoring case). Runs of consecutive characters and matches at the start
+/// of a word score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let query: Vec<char> = query.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect();
+    let Some(&first) = query.first() else {
+        return Some(0);
+    };
+    // Try every place the first character occurs, keeping the best
+    (0..text.len())
+        .filter(|&start| text[start] == first)
+        .filter_map(|start| score_from(&text, &query, start))
+        .max()
+}
+
+/// Score of matching `query` greedily in `text`, starting at `start`
+fn score_from(text: &[char], query: &[char], start: usize) -> Option<i32> {
+    let mut score = 0;
+    let mut next = start;
+    let mut previous: Option<usize> = None;
+    for &wanted in query {
+        let found = next + text[next..].iter().position(|&c| c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+/// State of the open palette
+#[derive(Debug, Clone)]
+pub struct CommandPalette {
+    pub query: String,
+    entries: Vec<PaletteEntry>,
+    /// Indexes into `entries` of the entries matching `query`, best first
+    matches: Vec<usize>,
+    /// Position in `matches` of the highlighted entry
+    pub selected: usize,
+}
+
+impl CommandPalette {
+    pub fn new(entries: Vec<PaletteEntry>) -> Self {
+        let matches = (0..entries.len()).collect();
+        CommandPalette { query: String::new(), entries, matches, selected: 0 }
+    }
+
+    /// Filter and rank the entries for a new query
+    pub fn set_query(&mut self, query: String) {
+        let mut scored: Vec<(i32, usize)> = self.entries.iter()
+            .enumerate()
+            .filter_map(|(i, entry)| fuzzy_score(&query, &entry.title).map(|score| (score, i)))
+            .collect();
+        // Best score first; ties keep the list order
+        scored.sort_by_key(|&(score, i)| (-score, i));
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        self.selected = 0;
+        self.query = query;
+    }
+
+    /// Move the highlight down (`true`) or up, wrapping around
+    pub fn move_selection(&mut self, down: bool) {
+        let count = self.matches.len();
+        if count > 0 {
+            self.selected = if down { (self.selected + 1) % count } else { (self.selected + count - 1) % count };
+        }
+    }
+
+    /// The matching entries with their positions, `VISIBLE_ENTRIES` at a
+    /// time, scrolled so the highlighted one is shown
+    pub fn visible(&self) -> impl Iterator<Item = (usize, &PaletteEntry)> {
+        let first = (self.selected + 1).saturating_sub(VISIBLE_ENTRIES);
+        self.matches.iter()
+            .enumerate()
+            .skip(first)
+            .take(VISIBLE_ENTRIES)
+            .map(|(position, &i)| (position, &self.entries[i]))
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// The message of the matching entry at `position`
+    pub fn message(&self, position: usize) -> Option<Message> {
+        self.matches.get(position).map(|&i| self.entries[i].message.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("cpv", "Edit: Copy value as YAML").is_some());
+        assert!(fuzzy_score("xyz", "Edit: Copy value as YAML").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        // Word starts and consecutive letters beat scattered ones
+        assert!(fuzzy_score("theme", "View: Toggle theme") > fuzzy_score("theme", "The: Home key"));
+    }
+
+    #[test]
+    fn test_palette_filters_and_ranks() {
+        let recent = vec![RecentFile { path: PathBuf::from("/data/orders.json"), size: 10, opened_at: 0 }];
+        let mut palette = CommandPalette::new(entries(&Keymap::default(), &recent));
+        assert!(palette.match_count() > 40);
+
+        palette.set_query("toggle theme".to_string());
+        assert!(matches!(palette.message(0), Some(Message::ToggleTheme)));
+
+        palette.set_query("orders".to_string());
+        assert!(matches!(palette.message(0), Some(Message::OpenRecentFile(0))));
+
+        palette.set_query("export yaml".to_string());
+        assert!(matches!(palette.message(0), Some(Message::ExportAs(TextFormat::Yaml))));
+
+        // Wraps around, and the highlighted entry stays visible
+        palette.set_query(String::new());
+        palette.move_selection(false);
+        assert_eq!(palette.selected, palette.match_count() - 1);
+        assert_eq!(palette.visible().count(), VISIBLE_ENTRIES);
+        assert_eq!(palette.visible().last().map(|(position, _)| position), Some(palette.selected));
+    }
+
+    #[test]
+    fn test_entries_show_shortcuts() {
+        let entries = entries(&Keymap::default(), &[]);
+        let open = entries.iter().find(|e| matches!(e.message, Message::OpenFileDialog)).unwrap();
+        assert!(open.shortcut.is_some());
+        assert!(!entries.iter().any(|e| matches!(e.message, Message::ToggleCommandPalette)));
+    }
+}
@@ -15,6 +15,22 @@ pub fn build_tree(json: &Value) -> JsonTree {
     tree
 }
 
+/// Append a value as the last element of a root array (JSON Lines follow mode).
+///
+/// The new subtree is added after the root, so it is the one place where a
+/// child's index is greater than its parent's. Returns the new node's index,
+/// or `None` if the root isn't an array.
+pub fn append_root_element(tree: &mut JsonTree, value: &Value) -> Option<usize> {
+    let root_index = tree.root_index();
+    let position = match tree.get_node(root_index) {
+        Some(root) if root.value == JsonValue::Array => root.children.len(),
+        _ => return None,
+    };
+    let index = build_node(tree, Some(format!("[{}]", position)), value, 1);
+    tree.get_node_mut(root_index)?.children.push(index);
+    Some(index)
+}
+
 /// Recursively build a node and its children
 /// Returns the index of the created node
 fn build_node(
@@ -124,4 +140,21 @@ mod tests {
         assert_eq!(root.value, JsonValue::Array);
         assert_eq!(root.children.len(), 3);
     }
+
+    #[test]
+    fn test_append_root_element() {
+        let mut tree = build_tree(&json!([{"a": 1}]));
+        let index = append_root_element(&mut tree, &json!({"b": [true]})).unwrap();
+
+        let root = tree.root().unwrap();
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[1], index);
+        let appended = tree.get_node(index).unwrap();
+        assert_eq!(appended.key.as_deref(), Some("[1]"));
+        assert_eq!(appended.depth, 1);
+        assert_eq!(tree.pointer_to_node(tree.resolve_pointer("/1/b/0").unwrap()), "/1/b/0");
+
+        let mut object = build_tree(&json!({"a": 1}));
+        assert_eq!(append_root_element(&mut object, &json!(2)), None);
+    }
 }
@@ -2,10 +2,14 @@
 pub mod node;
 pub mod tree;
 pub mod builder;
+pub mod pointer;
+pub mod value;
 
 // Re-export for easier access (optional but convenient)
 #[allow(unused_imports)]  // May be used by tests or future code
 pub use node::JsonNode;
 pub use node::JsonValue;
 pub use tree::JsonTree;
-pub use builder::build_tree;
+pub use builder::{append_root_element, build_tree};
+pub use pointer::{escape_token, resolve_closest, unescape_token};
+pub use value::json_equal;
@@ -0,0 +1,39 @@
+//! JSON Pointer (RFC 6901) helpers shared by patches, schemas and history.
+
+use super::tree::JsonTree;
+
+/// Escape a member name for use as a pointer token
+pub fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// The member name a pointer token stands for (the inverse of `escape_token`)
+pub fn unescape_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Resolve a pointer, falling back to its nearest existing ancestor
+pub fn resolve_closest(tree: &JsonTree, pointer: &str) -> Option<usize> {
+    let mut current = pointer;
+    loop {
+        if let Some(index) = tree.resolve_pointer(current) {
+            return Some(index);
+        }
+        let cut = current.rfind('/')?;
+        current = &current[..cut];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_escaping_round_trips() {
+        for name in ["plain", "a/b", "m~n", "~1", "/~0/"] {
+            assert_eq!(unescape_token(&escape_token(name)), name);
+        }
+        assert_eq!(escape_token("~1"), "~01");
+        assert_eq!(unescape_token("~01"), "~1");
+    }
+}
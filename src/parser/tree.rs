@@ -1,4 +1,5 @@
 use super::node::{JsonNode, JsonValue};
+use super::pointer::{escape_token, unescape_token};
 use std::fmt::Write;
 
 /// A complete JSON tree stored as a flat array of nodes
@@ -48,7 +49,6 @@ impl JsonTree {
     }
 
     /// Get a mutable reference to a node
-    #[allow(dead_code)]
     pub fn get_node_mut(&mut self, index: usize) -> Option<&mut JsonNode> {
         self.nodes.get_mut(index)
     }
@@ -77,6 +77,10 @@ impl JsonTree {
     /// Get the path from root to a given node (list of indices from root to target)
     /// Used for auto-expanding the tree to show a search result
     pub fn get_path_to_node(&self, target_index: usize) -> Vec<usize> {
+        if let Some(path) = self.descend_to_node(target_index) {
+            return path;
+        }
+
         let mut path = Vec::new();
 
         // Use a recursive search starting from root
@@ -88,6 +92,19 @@ impl JsonTree {
         }
     }
 
+    /// Fast path for `get_path_to_node`: nodes are stored in post-order, so
+    /// a child's subtree holds the indices after its previous sibling and up
+    /// to the child itself. Returns None if the layout doesn't lead there.
+    fn descend_to_node(&self, target_index: usize) -> Option<Vec<usize>> {
+        let mut path = vec![self.root_index];
+        let mut current = self.root_index;
+        while current != target_index {
+            current = self.get_node(current)?.children.iter().copied().find(|&child| child >= target_index)?;
+            path.push(current);
+        }
+        Some(path)
+    }
+
     /// Helper: recursively search for target and build path
     fn find_path_recursive(&self, current: usize, target: usize, path: &mut Vec<usize>) -> bool {
         path.push(current);
@@ -109,6 +126,85 @@ impl JsonTree {
         false
     }
 
+    /// Resolve an RFC 6901 JSON Pointer to a node index
+    pub fn resolve_pointer(&self, pointer: &str) -> Option<usize> {
+        if pointer.is_empty() {
+            return self.get_node(self.root_index).map(|_| self.root_index);
+        }
+        let rest = pointer.strip_prefix('/')?;
+
+        let mut current = self.root_index;
+        for token in rest.split('/') {
+            let token = unescape_token(token);
+            let node = self.get_node(current)?;
+            current = match node.value {
+                JsonValue::Array => {
+                    let position: usize = token.parse().ok()?;
+                    *node.children.get(position)?
+                }
+                JsonValue::Object => *node.children.iter().find(|&&c| {
+                    self.get_node(c).and_then(|n| n.key.as_deref()) == Some(token.as_str())
+                })?,
+                _ => return None,
+            };
+        }
+
+        Some(current)
+    }
+
+    /// RFC 6901 JSON Pointer of a node (the inverse of `resolve_pointer`)
+    pub fn pointer_to_node(&self, target_index: usize) -> String {
+        let path = self.get_path_to_node(target_index);
+        let mut pointer = String::new();
+        for pair in path.windows(2) {
+            let (Some(parent), Some(child)) = (self.get_node(pair[0]), self.get_node(pair[1])) else {
+                break;
+            };
+            pointer.push('/');
+            match parent.value {
+                JsonValue::Array => {
+                    let position = parent.children.iter().position(|&c| c == pair[1]).unwrap_or(0);
+                    let _ = write!(pointer, "{}", position);
+                }
+                _ => {
+                    let key = child.key.as_deref().unwrap_or("");
+                    pointer.push_str(&escape_token(key));
+                }
+            }
+        }
+        pointer
+    }
+
+    /// JSON Pointers of all expanded nodes (including ones hidden under a
+    /// collapsed ancestor), in document order.
+    ///
+    /// Pointers survive re-parsing, so this is how expansion state is carried
+    /// over to a freshly loaded tree.
+    pub fn expanded_pointers(&self) -> Vec<String> {
+        let mut pointers = Vec::new();
+        let mut stack = vec![(self.root_index, String::new())];
+        while let Some((index, pointer)) = stack.pop() {
+            let Some(node) = self.get_node(index) else {
+                continue;
+            };
+            for (position, &child) in node.children.iter().enumerate().rev() {
+                // Only containers can be expanded
+                if !self.get_node(child).is_some_and(|c| c.is_expandable()) {
+                    continue;
+                }
+                let token = match node.value {
+                    JsonValue::Array => position.to_string(),
+                    _ => escape_token(self.get_node(child).and_then(|c| c.key.as_deref()).unwrap_or("")),
+                };
+                stack.push((child, format!("{}/{}", pointer, token)));
+            }
+            if node.expanded {
+                pointers.push(pointer);
+            }
+        }
+        pointers
+    }
+
     /// Pretty print the tree structure (for debugging)
     #[allow(dead_code)]
     pub fn print_tree(&self) -> String {
@@ -278,4 +374,50 @@ mod tests {
         assert!(output.contains("Unfold"));
         assert!(output.contains("version"));
   }
+
+    #[test]
+    fn test_resolve_pointer() {
+        use crate::parser::builder::build_tree;
+        use serde_json::json;
+
+        let tree = build_tree(&json!({
+            "users": [{"email": "a@b.c"}, {"email": "d@e.f"}],
+            "a/b": {"m~n": true}
+        }));
+
+        let email = tree.resolve_pointer("/users/1/email").unwrap();
+        assert_eq!(tree.get_node(email).unwrap().value, JsonValue::String("d@e.f".to_string()));
+
+        let escaped = tree.resolve_pointer("/a~1b/m~0n").unwrap();
+        assert_eq!(tree.get_node(escaped).unwrap().value, JsonValue::Bool(true));
+
+        assert_eq!(tree.resolve_pointer(""), Some(tree.root_index()));
+        assert_eq!(tree.resolve_pointer("/users/9"), None);
+        assert_eq!(tree.resolve_pointer("users"), None);
+    }
+
+    #[test]
+    fn test_pointer_to_node_and_expanded_pointers() {
+        use crate::parser::builder::build_tree;
+        use serde_json::json;
+
+        let mut tree = build_tree(&json!({
+            "users": [{"email": "a@b.c"}, {"email": "d@e.f"}],
+            "a/b": {"m~n": true}
+        }));
+
+        for pointer in ["", "/users/1/email", "/a~1b/m~0n"] {
+            let index = tree.resolve_pointer(pointer).unwrap();
+            assert_eq!(tree.pointer_to_node(index), pointer);
+        }
+
+        for pointer in ["", "/users", "/users/0", "/a~1b"] {
+            let index = tree.resolve_pointer(pointer).unwrap();
+            tree.set_expanded(index, true);
+        }
+        let users = tree.resolve_pointer("/users").unwrap();
+        assert_eq!(tree.expanded_pointers(), ["", "/users", "/users/0", "/a~1b"]);
+        tree.set_expanded(users, false);
+        assert_eq!(tree.expanded_pointers(), ["", "/users/0", "/a~1b"]);
+    }
 }
@@ -0,0 +1,518 @@
+//! JSON Patch (RFC 6902) and JSON Merge Patch (RFC 7386) support.
+//!
+//! Patches are applied to the document rebuilt from a `JsonTree`, and the
+//! result is turned back into a new tree. Every operation records the JSON
+//! Pointer it touched so the affected nodes can be highlighted afterwards.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use serde_json::{Map, Value};
+
+use crate::json_export::node_to_value;
+use crate::parser::{build_tree, escape_token, json_equal, resolve_closest, unescape_token, JsonTree};
+
+/// The two supported patch document formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchFormat {
+    /// RFC 6902: an array of add/remove/replace/move/copy/test operations
+    JsonPatch,
+    /// RFC 7386: an object merged into the target, `null` deletes a member
+    MergePatch,
+}
+
+impl PatchFormat {
+    /// Detect the format of a patch document: JSON Patches are arrays of
+    /// operations, Merge Patches are objects.
+    pub fn detect(patch: &Value) -> Option<PatchFormat> {
+        match patch {
+            Value::Array(_) => Some(PatchFormat::JsonPatch),
+            Value::Object(_) => Some(PatchFormat::MergePatch),
+            _ => None,
+        }
+    }
+
+    /// Default file name when saving a generated patch
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            PatchFormat::JsonPatch => "patch.json",
+            PatchFormat::MergePatch => "merge-patch.json",
+        }
+    }
+}
+
+/// Preview state after one or more patches were applied to the document
+#[derive(Debug)]
+pub struct PatchPreview {
+    /// The document as it was before the first patch was applied
+    pub original: JsonTree,
+    /// Patch files applied so far, in order
+    pub applied: Vec<PathBuf>,
+    /// Nodes of the current tree touched by the most recent patch
+    pub affected: HashSet<usize>,
+}
+
+/// Result of applying a patch to a tree
+#[derive(Debug)]
+pub struct PatchOutcome {
+    /// The patched document
+    pub tree: JsonTree,
+    /// Nodes in `tree` that the patch added or modified (or, for removals,
+    /// the closest surviving ancestor)
+    pub affected: Vec<usize>,
+    /// Number of operations (JSON Patch) or changed members (Merge Patch)
+    pub operations: usize,
+}
+
+/// Apply a JSON Patch or Merge Patch (auto-detected) to a tree.
+///
+/// The tree is left untouched; on success a new patched tree is returned.
+/// JSON Patches are atomic: if any operation fails, nothing is applied.
+pub fn apply_patch_to_tree(tree: &JsonTree, patch: &Value) -> Result<PatchOutcome, String> {
+    let mut document = node_to_value(tree, tree.root_index());
+
+    let pointers = match PatchFormat::detect(patch) {
+        Some(PatchFormat::JsonPatch) => apply_json_patch(&mut document, patch)?,
+        Some(PatchFormat::MergePatch) => apply_merge_patch(&mut document, patch),
+        None => return Err("A patch must be a JSON array (RFC 6902) or object (RFC 7386)".to_string()),
+    };
+
+    let patched = build_tree(&document);
+    let mut affected = Vec::new();
+    let mut seen = HashSet::new();
+    for pointer in &pointers {
+        if let Some(index) = resolve_closest(&patched, pointer)
+            && seen.insert(index)
+        {
+            affected.push(index);
+        }
+    }
+
+    Ok(PatchOutcome {
+        tree: patched,
+        affected,
+        operations: pointers.len(),
+    })
+}
+
+/// Generate a patch that turns the `from` tree into the `to` tree
+pub fn generate_patch(from: &JsonTree, to: &JsonTree, format: PatchFormat) -> Value {
+    let from = node_to_value(from, from.root_index());
+    let to = node_to_value(to, to.root_index());
+    match format {
+        PatchFormat::JsonPatch => generate_json_patch(&from, &to),
+        PatchFormat::MergePatch => generate_merge_patch(&from, &to),
+    }
+}
+
+// ============================================================================
+// RFC 6902 - JSON Patch
+// ============================================================================
+
+/// Apply an RFC 6902 patch in place. Returns the pointers of every location
+/// touched. On error the document is left unchanged.
+pub fn apply_json_patch(document: &mut Value, patch: &Value) -> Result<Vec<String>, String> {
+    let Value::Array(operations) = patch else {
+        return Err("JSON Patch must be an array of operations".to_string());
+    };
+
+    let mut working = document.clone();
+    let mut affected = Vec::new();
+
+    for (i, operation) in operations.iter().enumerate() {
+        apply_operation(&mut working, operation, &mut affected)
+            .map_err(|e| format!("Operation {}: {}", i + 1, e))?;
+    }
+
+    *document = working;
+    Ok(affected)
+}
+
+fn apply_operation(document: &mut Value, operation: &Value, affected: &mut Vec<String>) -> Result<(), String> {
+    let op = operation.get("op").and_then(Value::as_str).ok_or("missing \"op\"")?;
+    let path = operation.get("path").and_then(Value::as_str).ok_or("missing \"path\"")?;
+    let tokens = parse_pointer(path)?;
+
+    let value = || operation.get("value").cloned().ok_or(format!("\"{}\" requires a \"value\"", op));
+    let from = || -> Result<(String, Vec<String>), String> {
+        let from = operation.get("from").and_then(Value::as_str).ok_or(format!("\"{}\" requires \"from\"", op))?;
+        Ok((from.to_string(), parse_pointer(from)?))
+    };
+
+    match op {
+        "add" => {
+            add(document, &tokens, value()?)?;
+            affected.push(path.to_string());
+        }
+        "remove" => {
+            remove(document, &tokens)?;
+            affected.push(parent_pointer(path).to_string());
+        }
+        "replace" => {
+            let target = get_mut(document, &tokens)?;
+            *target = value()?;
+            affected.push(path.to_string());
+        }
+        "move" => {
+            let (from_path, from_tokens) = from()?;
+            if tokens.len() > from_tokens.len() && tokens.starts_with(&from_tokens) {
+                return Err(format!("cannot move \"{}\" into its own child \"{}\"", from_path, path));
+            }
+            let moved = remove(document, &from_tokens)?;
+            add(document, &tokens, moved)?;
+            affected.push(parent_pointer(&from_path).to_string());
+            affected.push(path.to_string());
+        }
+        "copy" => {
+            let (_, from_tokens) = from()?;
+            let copied = get_mut(document, &from_tokens)?.clone();
+            add(document, &tokens, copied)?;
+            affected.push(path.to_string());
+        }
+        "test" => {
+            let expected = value()?;
+            let actual = get_mut(document, &tokens)?;
+            if !json_equal(actual, &expected) {
+                return Err(format!("test failed at \"{}\"", path));
+            }
+        }
+        other => return Err(format!("unknown operation \"{}\"", other)),
+    }
+
+    Ok(())
+}
+
+/// Split an RFC 6901 pointer into unescaped reference tokens
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let rest = pointer
+        .strip_prefix('/')
+        .ok_or_else(|| format!("invalid JSON Pointer \"{}\"", pointer))?;
+    Ok(rest.split('/').map(unescape_token).collect())
+}
+
+/// Pointer of the parent location ("" for top-level members)
+fn parent_pointer(pointer: &str) -> &str {
+    pointer.rfind('/').map(|i| &pointer[..i]).unwrap_or("")
+}
+
+/// Parse an array index token (no leading zeros, per RFC 6901)
+fn array_index(token: &str) -> Result<usize, String> {
+    if token.len() > 1 && token.starts_with('0') {
+        return Err(format!("invalid array index \"{}\"", token));
+    }
+    token.parse().map_err(|_| format!("invalid array index \"{}\"", token))
+}
+
+fn get_mut<'a>(document: &'a mut Value, tokens: &[String]) -> Result<&'a mut Value, String> {
+    let mut current = document;
+    for token in tokens {
+        current = match current {
+            Value::Object(map) => map.get_mut(token),
+            Value::Array(items) => items.get_mut(array_index(token)?),
+            _ => None,
+        }
+        .ok_or_else(|| format!("path not found at \"{}\"", token))?;
+    }
+    Ok(current)
+}
+
+fn add(document: &mut Value, tokens: &[String], value: Value) -> Result<(), String> {
+    let Some((last, parent_tokens)) = tokens.split_last() else {
+        *document = value;
+        return Ok(());
+    };
+
+    match get_mut(document, parent_tokens)? {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+            Ok(())
+        }
+        Value::Array(items) => {
+            let index = if last == "-" { items.len() } else { array_index(last)? };
+            if index > items.len() {
+                return Err(format!("index {} is out of bounds", index));
+            }
+            items.insert(index, value);
+            Ok(())
+        }
+        _ => Err(format!("cannot add \"{}\" to a non-container value", last)),
+    }
+}
+
+fn remove(document: &mut Value, tokens: &[String]) -> Result<Value, String> {
+    let (last, parent_tokens) = tokens.split_last().ok_or("cannot remove the document root")?;
+
+    match get_mut(document, parent_tokens)? {
+        Value::Object(map) => map.shift_remove(last).ok_or_else(|| format!("member \"{}\" does not exist", last)),
+        Value::Array(items) => {
+            let index = array_index(last)?;
+            if index >= items.len() {
+                return Err(format!("index {} is out of bounds", index));
+            }
+            Ok(items.remove(index))
+        }
+        _ => Err(format!("cannot remove \"{}\" from a non-container value", last)),
+    }
+}
+
+/// Generate an RFC 6902 patch turning `from` into `to`.
+///
+/// Objects are compared member by member and arrays position by position;
+/// array removals are emitted from the end so indices stay valid.
+pub fn generate_json_patch(from: &Value, to: &Value) -> Value {
+    let mut operations = Vec::new();
+    diff_values(from, to, "", &mut operations);
+    Value::Array(operations)
+}
+
+fn diff_values(from: &Value, to: &Value, path: &str, operations: &mut Vec<Value>) {
+    if json_equal(from, to) {
+        return;
+    }
+
+    match (from, to) {
+        (Value::Object(a), Value::Object(b)) => {
+            for key in a.keys().filter(|k| !b.contains_key(*k)) {
+                operations.push(operation("remove", &format!("{}/{}", path, escape_token(key)), None));
+            }
+            for (key, value) in b {
+                let child_path = format!("{}/{}", path, escape_token(key));
+                match a.get(key) {
+                    Some(old) => diff_values(old, value, &child_path, operations),
+                    None => operations.push(operation("add", &child_path, Some(value))),
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for (i, (old, new)) in a.iter().zip(b).enumerate() {
+                diff_values(old, new, &format!("{}/{}", path, i), operations);
+            }
+            for i in (b.len()..a.len()).rev() {
+                operations.push(operation("remove", &format!("{}/{}", path, i), None));
+            }
+            for (i, value) in b.iter().enumerate().skip(a.len()) {
+                operations.push(operation("add", &format!("{}/{}", path, i), Some(value)));
+            }
+        }
+        _ => operations.push(operation("replace", path, Some(to))),
+    }
+}
+
+fn operation(op: &str, path: &str, value: Option<&Value>) -> Value {
+    let mut map = Map::new();
+    map.insert("op".to_string(), Value::String(op.to_string()));
+    map.insert("path".to_string(), Value::String(path.to_string()));
+    if let Some(value) = value {
+        map.insert("value".to_string(), value.clone());
+    }
+    Value::Object(map)
+}
+
+// ============================================================================
+// RFC 7386 - JSON Merge Patch
+// ============================================================================
+
+/// Apply an RFC 7386 merge patch in place. Returns the pointers touched.
+pub fn apply_merge_patch(document: &mut Value, patch: &Value) -> Vec<String> {
+    let mut affected = Vec::new();
+    merge(document, patch, "", &mut affected);
+    affected
+}
+
+fn merge(target: &mut Value, patch: &Value, path: &str, affected: &mut Vec<String>) {
+    let Value::Object(members) = patch else {
+        if !json_equal(target, patch) {
+            *target = patch.clone();
+            affected.push(path.to_string());
+        }
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+        affected.push(path.to_string());
+    }
+    let Value::Object(map) = target else {
+        return;
+    };
+
+    for (key, value) in members {
+        let child_path = format!("{}/{}", path, escape_token(key));
+        if value.is_null() {
+            if map.shift_remove(key).is_some() {
+                affected.push(path.to_string());
+            }
+        } else {
+            let entry = map.entry(key.clone()).or_insert(Value::Null);
+            merge(entry, value, &child_path, affected);
+        }
+    }
+}
+
+/// Generate an RFC 7386 merge patch turning `from` into `to`.
+///
+/// Merge patches cannot express setting a member to `null` (that means
+/// "delete") or partial array edits (arrays are replaced whole).
+pub fn generate_merge_patch(from: &Value, to: &Value) -> Value {
+    match (from, to) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut patch = Map::new();
+            for key in a.keys().filter(|k| !b.contains_key(*k)) {
+                patch.insert(key.clone(), Value::Null);
+            }
+            for (key, value) in b {
+                match a.get(key) {
+                    Some(old) if json_equal(old, value) => {}
+                    Some(old) => {
+                        patch.insert(key.clone(), generate_merge_patch(old, value));
+                    }
+                    None => {
+                        patch.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            Value::Object(patch)
+        }
+        _ => to.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_patch_operations() {
+        let mut doc = json!({"a": 1, "list": [1, 2], "nested": {"x": true}});
+        let patch = json!([
+            {"op": "add", "path": "/b", "value": "new"},
+            {"op": "remove", "path": "/a"},
+            {"op": "replace", "path": "/nested/x", "value": false},
+            {"op": "add", "path": "/list/-", "value": 3},
+            {"op": "add", "path": "/list/0", "value": 0},
+            {"op": "copy", "from": "/b", "path": "/c"},
+            {"op": "move", "from": "/c", "path": "/nested/c"},
+            {"op": "test", "path": "/list/3", "value": 3.0}
+        ]);
+
+        let affected = apply_json_patch(&mut doc, &patch).unwrap();
+        assert_eq!(doc, json!({"b": "new", "list": [0, 1, 2, 3], "nested": {"x": false, "c": "new"}}));
+        assert!(affected.contains(&"/nested/x".to_string()));
+        assert!(affected.contains(&"".to_string()), "removal marks the parent");
+    }
+
+    #[test]
+    fn test_patches_keep_key_order() {
+        let keys = |doc: &Value| doc.as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+
+        let mut doc = json!({"a": 1, "b": 2, "c": 3, "d": 4});
+        apply_json_patch(&mut doc, &json!([{"op": "remove", "path": "/a"}])).unwrap();
+        assert_eq!(keys(&doc), ["b", "c", "d"]);
+
+        let mut doc = json!({"a": 1, "b": 2, "c": 3, "d": 4});
+        apply_json_patch(&mut doc, &json!([{"op": "move", "from": "/a", "path": "/e"}])).unwrap();
+        assert_eq!(keys(&doc), ["b", "c", "d", "e"]);
+
+        let mut doc = json!({"a": 1, "b": 2, "c": 3, "d": 4});
+        apply_merge_patch(&mut doc, &json!({"a": null, "c": 5}));
+        assert_eq!(keys(&doc), ["b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_json_patch_is_atomic() {
+        let mut doc = json!({"a": 1});
+        let patch = json!([
+            {"op": "add", "path": "/b", "value": 2},
+            {"op": "test", "path": "/a", "value": 99}
+        ]);
+
+        let error = apply_json_patch(&mut doc, &patch).unwrap_err();
+        assert!(error.contains("Operation 2"));
+        assert_eq!(doc, json!({"a": 1}), "failed patch must leave the document unchanged");
+    }
+
+    #[test]
+    fn test_json_patch_errors() {
+        let mut doc = json!({"list": [1]});
+        assert!(apply_json_patch(&mut doc, &json!([{"op": "remove", "path": "/missing"}])).is_err());
+        assert!(apply_json_patch(&mut doc, &json!([{"op": "add", "path": "/list/5", "value": 1}])).is_err());
+        assert!(apply_json_patch(&mut doc, &json!([{"op": "add", "path": "/list/01", "value": 1}])).is_err());
+        assert!(apply_json_patch(&mut doc, &json!([{"op": "frobnicate", "path": ""}])).is_err());
+        assert!(apply_json_patch(&mut doc, &json!([{"op": "move", "from": "/list", "path": "/list/0"}])).is_err());
+        assert!(apply_json_patch(&mut doc, &json!({"op": "add"})).is_err());
+    }
+
+    #[test]
+    fn test_merge_patch_rfc_example() {
+        // Example from RFC 7386, section 3
+        let mut doc = json!({
+            "title": "Goodbye!",
+            "author": {"givenName": "John", "familyName": "Doe"},
+            "tags": ["example", "sample"],
+            "content": "This will be unchanged"
+        });
+        let patch = json!({
+            "title": "Hello!",
+            "phoneNumber": "+01-123-456-7890",
+            "author": {"familyName": null},
+            "tags": ["example"]
+        });
+
+        let affected = apply_merge_patch(&mut doc, &patch);
+        assert_eq!(doc, json!({
+            "title": "Hello!",
+            "author": {"givenName": "John"},
+            "tags": ["example"],
+            "content": "This will be unchanged",
+            "phoneNumber": "+01-123-456-7890"
+        }));
+        assert!(affected.contains(&"/title".to_string()));
+        assert!(affected.contains(&"/author".to_string()));
+    }
+
+    #[test]
+    fn test_generated_patches_round_trip() {
+        let from = json!({"keep": 1, "drop": true, "list": [1, 2, 3], "obj": {"a": "x", "b": "y"}});
+        let to = json!({"keep": 1, "add": null, "list": [1, 5], "obj": {"a": "z"}});
+
+        let mut patched = from.clone();
+        apply_json_patch(&mut patched, &generate_json_patch(&from, &to)).unwrap();
+        assert_eq!(patched, to);
+
+        // Merge patches cannot add a null member, so compare without it
+        let to_without_null = json!({"keep": 1, "list": [1, 5], "obj": {"a": "z"}});
+        let mut merged = from.clone();
+        apply_merge_patch(&mut merged, &generate_merge_patch(&from, &to_without_null));
+        assert_eq!(merged, to_without_null);
+    }
+
+    #[test]
+    fn test_apply_patch_to_tree_highlights_nodes() {
+        let tree = build_tree(&json!({"user": {"name": "old", "age": 30}}));
+        let patch = json!([{"op": "replace", "path": "/user/name", "value": "new"}]);
+
+        let outcome = apply_patch_to_tree(&tree, &patch).unwrap();
+        assert_eq!(outcome.operations, 1);
+        assert_eq!(outcome.affected.len(), 1);
+        let node = outcome.tree.get_node(outcome.affected[0]).unwrap();
+        assert_eq!(node.key.as_deref(), Some("name"));
+
+        assert!(apply_patch_to_tree(&tree, &json!("nope")).is_err());
+    }
+
+    #[test]
+    fn test_generate_patch_from_trees() {
+        let from = build_tree(&json!({"a": 1}));
+        let to = build_tree(&json!({"a": 2}));
+
+        assert_eq!(
+            generate_patch(&from, &to, PatchFormat::JsonPatch),
+            json!([{"op": "replace", "path": "/a", "value": 2}])
+        );
+        assert_eq!(generate_patch(&from, &to, PatchFormat::MergePatch), json!({"a": 2}));
+    }
+}
@@ -0,0 +1,1019 @@
+//! JSON Schema validation (draft 2020-12 and draft-07).
+//!
+//! A self-contained validator for the assertion and applicator keywords of
+//! both drafts. Every violation records the JSON Pointer of the failing
+//! instance location so it can be mapped back onto a node of the tree.
+//!
+//! Only local references (`#`, `#/json/pointer`, `#anchor`) are resolved;
+//! references to other documents are skipped and listed as unresolved.
+//! `format` is treated as an annotation and `unevaluated*` is not supported.
+//!
+//! The reverse direction is covered too: `infer_schema` describes an existing
+//! subtree, merging the shapes of all elements of an array.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::json_export::node_to_value;
+use crate::parser::{escape_token, json_equal, resolve_closest, JsonTree, JsonValue};
+
+/// Most `$ref`s followed inside one another before giving up, a backstop for
+/// the stack; real cycles are caught sooner (see `Validator::active_refs`)
+const MAX_REF_DEPTH: usize = 1024;
+
+/// Inferred string enums are limited to this many distinct values
+const MAX_ENUM_VALUES: usize = 8;
+
+/// `$schema` URI written into generated schemas
+const DRAFT_2020_12_URI: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The schema dialects we understand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaDraft {
+    /// Draft-07: tuple `items`, `additionalItems`, `dependencies`, `$ref` hides siblings
+    Draft7,
+    /// Draft 2020-12: `prefixItems`, `dependentRequired`/`dependentSchemas`, `$defs`
+    Draft202012,
+}
+
+impl SchemaDraft {
+    /// Detect the dialect from the schema's own `$schema` keyword
+    /// (defaults to 2020-12 when absent or unrecognised)
+    pub fn detect(schema: &Value) -> SchemaDraft {
+        match schema.get("$schema").and_then(Value::as_str) {
+            Some(uri) if uri.contains("draft-07") || uri.contains("draft-06") => SchemaDraft::Draft7,
+            _ => SchemaDraft::Draft202012,
+        }
+    }
+
+    /// Short label for the UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            SchemaDraft::Draft7 => "draft-07",
+            SchemaDraft::Draft202012 => "2020-12",
+        }
+    }
+}
+
+/// A single failed assertion
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// JSON Pointer of the failing instance location
+    pub pointer: String,
+    /// The schema keyword that failed (e.g. "required", "type")
+    pub keyword: String,
+    /// Human readable description
+    pub message: String,
+}
+
+/// Everything one validation run found
+#[derive(Debug, Default)]
+pub struct Report {
+    pub violations: Vec<Violation>,
+    /// `$ref`s to other documents; their subschemas are not checked
+    pub unresolved_refs: Vec<String>,
+}
+
+/// An attached schema and the violations found in the current document
+#[derive(Debug)]
+pub struct SchemaSession {
+    pub path: PathBuf,
+    pub schema: Value,
+    pub draft: SchemaDraft,
+    pub violations: Vec<Violation>,
+    /// Violation indices grouped by the node they point at
+    pub by_node: HashMap<usize, Vec<usize>>,
+    /// Problem with the schema itself that stopped validation
+    pub error: Option<String>,
+    /// `$ref`s that were skipped because they point outside the schema
+    pub unresolved_refs: Vec<String>,
+    /// Whether the problems panel is open
+    pub show_problems: bool,
+}
+
+impl SchemaSession {
+    /// Read a schema file
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        let schema = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| serde_json::from_str::<Value>(&contents).map_err(|e| e.to_string()))?;
+        if !schema.is_object() && !schema.is_boolean() {
+            return Err("a schema must be a JSON object or boolean".to_string());
+        }
+
+        Ok(SchemaSession {
+            path,
+            draft: SchemaDraft::detect(&schema),
+            schema,
+            violations: Vec::new(),
+            by_node: HashMap::new(),
+            error: None,
+            unresolved_refs: Vec::new(),
+            show_problems: false,
+        })
+    }
+
+    /// Validate a tree, replacing the previous results
+    pub fn validate(&mut self, tree: &JsonTree) {
+        let instance = node_to_value(tree, tree.root_index());
+        self.by_node.clear();
+        match validate(&self.schema, &instance, self.draft) {
+            Ok(report) => {
+                self.violations = report.violations;
+                self.unresolved_refs = report.unresolved_refs;
+                self.error = None;
+            }
+            Err(e) => {
+                self.violations.clear();
+                self.unresolved_refs.clear();
+                self.error = Some(e);
+            }
+        }
+
+        for (i, violation) in self.violations.iter().enumerate() {
+            if let Some(node) = resolve_closest(tree, &violation.pointer) {
+                self.by_node.entry(node).or_default().push(i);
+            }
+        }
+    }
+
+    /// Forget all results (e.g. while the tree holds something else than the document)
+    pub fn clear(&mut self) {
+        self.violations.clear();
+        self.by_node.clear();
+        self.error = None;
+        self.unresolved_refs.clear();
+    }
+}
+
+/// Find a schema referenced by a document's top-level `$schema` key.
+///
+/// Only local files are considered (absolute, relative to the document, or
+/// `file://` URIs); remote meta-schema URLs are ignored.
+pub fn referenced_schema_path(document: &JsonTree, document_path: &Path) -> Option<PathBuf> {
+    let root = document.get_node(document.root_index())?;
+    let uri = root.children.iter().find_map(|&c| {
+        let child = document.get_node(c)?;
+        match (&child.key, &child.value) {
+            (Some(key), JsonValue::String(s)) if key == "$schema" => Some(s.clone()),
+            _ => None,
+        }
+    })?;
+
+    if uri.starts_with("http://") || uri.starts_with("https://") {
+        return None;
+    }
+    let uri = uri.split('#').next().unwrap_or_default();
+    let local = uri.strip_prefix("file://").unwrap_or(uri);
+    if local.is_empty() {
+        return None;
+    }
+
+    let path = Path::new(local);
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        document_path.parent()?.join(path)
+    };
+    path.is_file().then_some(path)
+}
+
+/// Validate an instance against a schema written in `draft`.
+///
+/// Returns every violation found, or an error when the schema itself cannot
+/// be used (broken local `$ref`, invalid `pattern`, ...). References to
+/// other documents don't fail validation: they are listed in the report and
+/// everything else is still checked.
+pub fn validate(schema: &Value, instance: &Value, draft: SchemaDraft) -> Result<Report, String> {
+    let mut validator = Validator {
+        root: schema,
+        draft,
+        regexes: HashMap::new(),
+        active_refs: Vec::new(),
+        unresolved_refs: Vec::new(),
+    };
+    let mut violations = Vec::new();
+    validator.check(schema, instance, "", &mut violations)?;
+    Ok(Report { violations, unresolved_refs: validator.unresolved_refs })
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    draft: SchemaDraft,
+    regexes: HashMap<String, Regex>,
+    /// `$ref`s being followed and the instance location each one was
+    /// followed at. Meeting the same pair again is a cycle that would never
+    /// get any further into the instance.
+    active_refs: Vec<(&'a str, String)>,
+    unresolved_refs: Vec<String>,
+}
+
+impl<'a> Validator<'a> {
+    fn check(&mut self, schema: &'a Value, instance: &Value, pointer: &str, out: &mut Vec<Violation>) -> Result<(), String> {
+        let map = match schema {
+            Value::Bool(true) => return Ok(()),
+            Value::Bool(false) => {
+                push(out, pointer, "false", "no value is allowed here".to_string());
+                return Ok(());
+            }
+            Value::Object(map) => map,
+            _ => return Err(format!("invalid schema at instance \"{}\"", pointer)),
+        };
+
+        if let Some(reference) = map.get("$ref").or_else(|| map.get("$dynamicRef")).and_then(Value::as_str) {
+            match self.resolve_ref(reference)? {
+                Some(target) => {
+                    if self.active_refs.iter().any(|(r, p)| *r == reference && p == pointer) {
+                        return Err(format!("$ref \"{}\" recurses without end", reference));
+                    }
+                    if self.active_refs.len() >= MAX_REF_DEPTH {
+                        return Err(format!("$ref \"{}\" is nested too deeply", reference));
+                    }
+                    self.active_refs.push((reference, pointer.to_string()));
+                    let result = self.check(target, instance, pointer, out);
+                    self.active_refs.pop();
+                    result?;
+                }
+                // Another document: skip it, but keep checking the rest
+                None if !self.unresolved_refs.iter().any(|r| r == reference) => {
+                    self.unresolved_refs.push(reference.to_string());
+                }
+                None => {}
+            }
+            // Before 2019-09, $ref replaces all of its sibling keywords
+            if self.draft == SchemaDraft::Draft7 {
+                return Ok(());
+            }
+        }
+
+        self.check_generic(map, instance, pointer, out)?;
+        self.check_combinators(map, instance, pointer, out)?;
+        match instance {
+            Value::Number(n) => self.check_number(map, n.as_f64().unwrap_or(0.0), pointer, out),
+            Value::String(s) => self.check_string(map, s, pointer, out)?,
+            Value::Array(items) => self.check_array(map, items, pointer, out)?,
+            Value::Object(members) => self.check_object(map, members, pointer, out)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Does the instance satisfy the schema? (violations are discarded)
+    fn is_valid(&mut self, schema: &'a Value, instance: &Value, pointer: &str) -> Result<bool, String> {
+        let mut scratch = Vec::new();
+        self.check(schema, instance, pointer, &mut scratch)?;
+        Ok(scratch.is_empty())
+    }
+
+    fn check_generic(&mut self, map: &Map<String, Value>, instance: &Value, pointer: &str, out: &mut Vec<Violation>) -> Result<(), String> {
+        if let Some(expected) = map.get("type") {
+            let allowed: Vec<&str> = match expected {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                _ => return Err("\"type\" must be a string or an array".to_string()),
+            };
+            if !allowed.iter().any(|t| type_matches(t, instance)) {
+                push(out, pointer, "type", format!("expected {}, found {}", allowed.join(" or "), type_name(instance)));
+            }
+        }
+
+        if let Some(Value::Array(options)) = map.get("enum")
+            && !options.iter().any(|option| json_equal(option, instance))
+        {
+            push(out, pointer, "enum", format!("{} is not one of the allowed values", summary(instance)));
+        }
+
+        if let Some(expected) = map.get("const")
+            && !json_equal(expected, instance)
+        {
+            push(out, pointer, "const", format!("value must be {}", summary(expected)));
+        }
+        Ok(())
+    }
+
+    fn check_combinators(&mut self, map: &'a Map<String, Value>, instance: &Value, pointer: &str, out: &mut Vec<Violation>) -> Result<(), String> {
+        if let Some(Value::Array(schemas)) = map.get("allOf") {
+            for schema in schemas {
+                self.check(schema, instance, pointer, out)?;
+            }
+        }
+
+        if let Some(Value::Array(schemas)) = map.get("anyOf") {
+            let mut any = false;
+            for schema in schemas {
+                if self.is_valid(schema, instance, pointer)? {
+                    any = true;
+                    break;
+                }
+            }
+            if !any {
+                push(out, pointer, "anyOf", "value does not match any of the anyOf schemas".to_string());
+            }
+        }
+
+        if let Some(Value::Array(schemas)) = map.get("oneOf") {
+            let mut matching = 0;
+            for schema in schemas {
+                if self.is_valid(schema, instance, pointer)? {
+                    matching += 1;
+                }
+            }
+            if matching != 1 {
+                push(out, pointer, "oneOf", format!("value matches {} of the oneOf schemas, expected exactly one", matching));
+            }
+        }
+
+        if let Some(schema) = map.get("not")
+            && self.is_valid(schema, instance, pointer)?
+        {
+            push(out, pointer, "not", "value must not match the \"not\" schema".to_string());
+        }
+
+        if let Some(condition) = map.get("if") {
+            let branch = if self.is_valid(condition, instance, pointer)? { "then" } else { "else" };
+            if let Some(schema) = map.get(branch) {
+                self.check(schema, instance, pointer, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_number(&mut self, map: &Map<String, Value>, n: f64, pointer: &str, out: &mut Vec<Violation>) {
+        let limit = |keyword: &str| map.get(keyword).and_then(Value::as_f64);
+
+        if let Some(m) = limit("multipleOf")
+            && m > 0.0
+        {
+            let quotient = n / m;
+            if (quotient - quotient.round()).abs() > 1e-9 {
+                push(out, pointer, "multipleOf", format!("{} is not a multiple of {}", n, m));
+            }
+        }
+        if let Some(min) = limit("minimum")
+            && n < min
+        {
+            push(out, pointer, "minimum", format!("{} is less than the minimum of {}", n, min));
+        }
+        if let Some(max) = limit("maximum")
+            && n > max
+        {
+            push(out, pointer, "maximum", format!("{} is greater than the maximum of {}", n, max));
+        }
+        if let Some(min) = limit("exclusiveMinimum")
+            && n <= min
+        {
+            push(out, pointer, "exclusiveMinimum", format!("{} must be greater than {}", n, min));
+        }
+        if let Some(max) = limit("exclusiveMaximum")
+            && n >= max
+        {
+            push(out, pointer, "exclusiveMaximum", format!("{} must be less than {}", n, max));
+        }
+    }
+
+    fn check_string(&mut self, map: &Map<String, Value>, s: &str, pointer: &str, out: &mut Vec<Violation>) -> Result<(), String> {
+        let length = s.chars().count();
+        if let Some(min) = map.get("minLength").and_then(Value::as_u64)
+            && (length as u64) < min
+        {
+            push(out, pointer, "minLength", format!("string is {} characters, minimum is {}", length, min));
+        }
+        if let Some(max) = map.get("maxLength").and_then(Value::as_u64)
+            && (length as u64) > max
+        {
+            push(out, pointer, "maxLength", format!("string is {} characters, maximum is {}", length, max));
+        }
+        if let Some(pattern) = map.get("pattern").and_then(Value::as_str)
+            && !self.regex(pattern)?.is_match(s)
+        {
+            push(out, pointer, "pattern", format!("\"{}\" does not match pattern \"{}\"", s, pattern));
+        }
+        Ok(())
+    }
+
+    fn check_array(&mut self, map: &'a Map<String, Value>, items: &[Value], pointer: &str, out: &mut Vec<Violation>) -> Result<(), String> {
+        let item_pointer = |i: usize| format!("{}/{}", pointer, i);
+
+        // Leading positional schemas: 2020-12 `prefixItems`, draft-07 array-form `items`
+        let (prefix, rest): (&[Value], Option<&Value>) = match (map.get("prefixItems"), map.get("items")) {
+            (Some(Value::Array(prefix)), rest) => (prefix, rest),
+            (None, Some(Value::Array(prefix))) => (prefix, map.get("additionalItems")),
+            (_, rest) => (&[], rest),
+        };
+        for (i, (schema, item)) in prefix.iter().zip(items).enumerate() {
+            self.check(schema, item, &item_pointer(i), out)?;
+        }
+        if let Some(schema) = rest {
+            for (i, item) in items.iter().enumerate().skip(prefix.len()) {
+                self.check(schema, item, &item_pointer(i), out)?;
+            }
+        }
+
+        if let Some(schema) = map.get("contains") {
+            let mut count = 0;
+            for (i, item) in items.iter().enumerate() {
+                if self.is_valid(schema, item, &item_pointer(i))? {
+                    count += 1;
+                }
+            }
+            let (min, max) = match self.draft {
+                SchemaDraft::Draft7 => (1, None),
+                SchemaDraft::Draft202012 => (
+                    map.get("minContains").and_then(Value::as_u64).unwrap_or(1),
+                    map.get("maxContains").and_then(Value::as_u64),
+                ),
+            };
+            if count < min {
+                push(out, pointer, "contains", format!("{} items match \"contains\", expected at least {}", count, min));
+            }
+            if let Some(max) = max
+                && count > max
+            {
+                push(out, pointer, "maxContains", format!("{} items match \"contains\", expected at most {}", count, max));
+            }
+        }
+
+        if let Some(min) = map.get("minItems").and_then(Value::as_u64)
+            && (items.len() as u64) < min
+        {
+            push(out, pointer, "minItems", format!("array has {} items, minimum is {}", items.len(), min));
+        }
+        if let Some(max) = map.get("maxItems").and_then(Value::as_u64)
+            && (items.len() as u64) > max
+        {
+            push(out, pointer, "maxItems", format!("array has {} items, maximum is {}", items.len(), max));
+        }
+        if map.get("uniqueItems") == Some(&Value::Bool(true)) {
+            let duplicate = (1..items.len()).find(|&j| items[..j].iter().any(|earlier| json_equal(earlier, &items[j])));
+            if let Some(j) = duplicate {
+                push(out, &item_pointer(j), "uniqueItems", "duplicate array item".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn check_object(&mut self, map: &'a Map<String, Value>, members: &Map<String, Value>, pointer: &str, out: &mut Vec<Violation>) -> Result<(), String> {
+        let member_pointer = |key: &str| format!("{}/{}", pointer, escape_token(key));
+
+        if let Some(Value::Array(required)) = map.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !members.contains_key(key) {
+                    push(out, pointer, "required", format!("missing required property \"{}\"", key));
+                }
+            }
+        }
+
+        let properties = map.get("properties").and_then(Value::as_object);
+        let pattern_properties = map.get("patternProperties").and_then(Value::as_object);
+        for (key, value) in members {
+            let child = member_pointer(key);
+            let mut evaluated = false;
+
+            if let Some(schema) = properties.and_then(|p| p.get(key)) {
+                evaluated = true;
+                self.check(schema, value, &child, out)?;
+            }
+            if let Some(patterns) = pattern_properties {
+                for (pattern, schema) in patterns {
+                    if self.regex(pattern)?.is_match(key) {
+                        evaluated = true;
+                        self.check(schema, value, &child, out)?;
+                    }
+                }
+            }
+            if !evaluated && let Some(schema) = map.get("additionalProperties") {
+                if schema == &Value::Bool(false) {
+                    push(out, &child, "additionalProperties", format!("property \"{}\" is not allowed", key));
+                } else {
+                    self.check(schema, value, &child, out)?;
+                }
+            }
+
+            if let Some(schema) = map.get("propertyNames")
+                && !self.is_valid(schema, &Value::String(key.clone()), &child)?
+            {
+                push(out, &child, "propertyNames", format!("property name \"{}\" is not valid", key));
+            }
+        }
+
+        if let Some(min) = map.get("minProperties").and_then(Value::as_u64)
+            && (members.len() as u64) < min
+        {
+            push(out, pointer, "minProperties", format!("object has {} properties, minimum is {}", members.len(), min));
+        }
+        if let Some(max) = map.get("maxProperties").and_then(Value::as_u64)
+            && (members.len() as u64) > max
+        {
+            push(out, pointer, "maxProperties", format!("object has {} properties, maximum is {}", members.len(), max));
+        }
+
+        // Draft-07 `dependencies` covers both forms handled separately in 2020-12
+        let dependency_maps = ["dependentRequired", "dependentSchemas", "dependencies"]
+            .into_iter()
+            .filter_map(|keyword| map.get(keyword).and_then(Value::as_object).map(|deps| (keyword, deps)));
+        for (keyword, dependencies) in dependency_maps {
+            for (trigger, dependency) in dependencies {
+                if !members.contains_key(trigger) {
+                    continue;
+                }
+                match dependency {
+                    Value::Array(keys) => {
+                        for key in keys.iter().filter_map(Value::as_str) {
+                            if !members.contains_key(key) {
+                                push(out, pointer, keyword, format!("property \"{}\" requires \"{}\"", trigger, key));
+                            }
+                        }
+                    }
+                    schema => {
+                        let object = Value::Object(members.clone());
+                        self.check(schema, &object, pointer, out)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The subschema a local `$ref` points at, or `None` for a reference
+    /// to another document
+    fn resolve_ref(&self, reference: &str) -> Result<Option<&'a Value>, String> {
+        let Some(fragment) = reference.strip_prefix('#') else {
+            return Ok(None);
+        };
+
+        let target = if fragment.is_empty() {
+            Some(self.root)
+        } else if fragment.starts_with('/') {
+            let decoded = fragment.replace("%25", "%").replace("%22", "\"").replace("%20", " ");
+            self.root.pointer(&decoded)
+        } else {
+            find_anchor(self.root, fragment)
+        };
+        target.map(Some).ok_or_else(|| format!("cannot resolve $ref \"{}\"", reference))
+    }
+
+    fn regex(&mut self, pattern: &str) -> Result<&Regex, String> {
+        if !self.regexes.contains_key(pattern) {
+            let regex = Regex::new(pattern).map_err(|e| format!("invalid pattern \"{}\": {}", pattern, e))?;
+            self.regexes.insert(pattern.to_string(), regex);
+        }
+        Ok(&self.regexes[pattern])
+    }
+}
+
+/// Find a subschema declaring `$anchor` (2020-12) or `$id: "#name"` (draft-07)
+fn find_anchor<'a>(schema: &'a Value, name: &str) -> Option<&'a Value> {
+    match schema {
+        Value::Object(map) => {
+            let anchored = map.get("$anchor").and_then(Value::as_str) == Some(name)
+                || map.get("$dynamicAnchor").and_then(Value::as_str) == Some(name)
+                || map.get("$id").and_then(Value::as_str).and_then(|id| id.strip_prefix('#')) == Some(name);
+            if anchored {
+                return Some(schema);
+            }
+            map.values().find_map(|v| find_anchor(v, name))
+        }
+        Value::Array(items) => items.iter().find_map(|v| find_anchor(v, name)),
+        _ => None,
+    }
+}
+
+fn push(out: &mut Vec<Violation>, pointer: &str, keyword: &str, message: String) {
+    out.push(Violation {
+        pointer: pointer.to_string(),
+        keyword: keyword.to_string(),
+        message,
+    });
+}
+
+fn type_matches(expected: &str, instance: &Value) -> bool {
+    match expected {
+        "integer" => instance.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "number" => instance.is_number(),
+        other => type_name(instance) == other,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Short display of a value for messages
+fn summary(value: &Value) -> String {
+    match value {
+        Value::Array(_) => "[...]".to_string(),
+        Value::Object(_) => "{...}".to_string(),
+        other => other.to_string(),
+    }
+}
+
+// ============================================================================
+// Schema inference
+// ============================================================================
+
+/// Everything seen at one location across all samples
+#[derive(Debug, Default)]
+struct Shape {
+    null: bool,
+    boolean: bool,
+    integer: bool,
+    number: bool,
+    /// Distinct string values in first-seen order (`None` once there are too many)
+    strings: Option<Vec<String>>,
+    string_samples: usize,
+    /// Merged shape of all array elements (`Some` once an array was seen)
+    items: Option<Box<Shape>>,
+    /// Whether any array sample had elements
+    has_items: bool,
+    object: Option<ObjectShape>,
+}
+
+#[derive(Debug, Default)]
+struct ObjectShape {
+    samples: usize,
+    /// Members in first-seen order with the number of samples containing them
+    properties: Vec<(String, usize, Shape)>,
+}
+
+/// Infer a draft 2020-12 schema describing a node and its children.
+///
+/// Types, required members and small string enums are inferred; the shapes
+/// of all elements of an array are merged into a single `items` schema.
+pub fn infer_schema(tree: &JsonTree, node_index: usize) -> Value {
+    let mut shape = Shape::default();
+    shape.add(tree, node_index);
+
+    match shape.to_schema() {
+        Value::Object(map) => {
+            let mut schema = Map::new();
+            schema.insert("$schema".to_string(), Value::String(DRAFT_2020_12_URI.to_string()));
+            schema.extend(map);
+            Value::Object(schema)
+        }
+        schema => schema,
+    }
+}
+
+impl Shape {
+    /// Merge one more sample into the shape
+    fn add(&mut self, tree: &JsonTree, index: usize) {
+        let Some(node) = tree.get_node(index) else {
+            return;
+        };
+
+        match &node.value {
+            JsonValue::Null => self.null = true,
+            JsonValue::Bool(_) => self.boolean = true,
+            JsonValue::Number(n) if n.fract() == 0.0 => self.integer = true,
+            JsonValue::Number(_) => self.number = true,
+            JsonValue::String(s) => {
+                if self.string_samples == 0 {
+                    self.strings = Some(Vec::new());
+                }
+                self.string_samples += 1;
+                if let Some(values) = &mut self.strings
+                    && !values.contains(s)
+                {
+                    values.push(s.clone());
+                    if values.len() > MAX_ENUM_VALUES {
+                        self.strings = None;
+                    }
+                }
+            }
+            JsonValue::Array => {
+                let items = self.items.get_or_insert_with(Box::default);
+                for &child in &node.children {
+                    items.add(tree, child);
+                }
+                self.has_items |= !node.children.is_empty();
+            }
+            JsonValue::Object => {
+                let object = self.object.get_or_insert_with(ObjectShape::default);
+                object.samples += 1;
+                for &child in &node.children {
+                    let Some(key) = tree.get_node(child).and_then(|c| c.key.as_ref()) else {
+                        continue;
+                    };
+                    let position = match object.properties.iter().position(|(k, _, _)| k == key) {
+                        Some(position) => position,
+                        None => {
+                            object.properties.push((key.clone(), 0, Shape::default()));
+                            object.properties.len() - 1
+                        }
+                    };
+                    let (_, seen, shape) = &mut object.properties[position];
+                    *seen += 1;
+                    shape.add(tree, child);
+                }
+            }
+        }
+    }
+
+    fn to_schema(&self) -> Value {
+        let mut types = Vec::new();
+        if self.object.is_some() {
+            types.push("object");
+        }
+        if self.items.is_some() {
+            types.push("array");
+        }
+        if self.string_samples > 0 {
+            types.push("string");
+        }
+        if self.number {
+            types.push("number");
+        } else if self.integer {
+            types.push("integer");
+        }
+        if self.boolean {
+            types.push("boolean");
+        }
+        if self.null {
+            types.push("null");
+        }
+
+        let mut map = Map::new();
+        match types.as_slice() {
+            [] => return Value::Bool(true),
+            [only] => {
+                map.insert("type".to_string(), Value::String(only.to_string()));
+            }
+            several => {
+                map.insert("type".to_string(), several.iter().map(|t| Value::String(t.to_string())).collect());
+            }
+        }
+
+        // An enum needs repetition to be more than a list of the samples
+        if let Some(values) = &self.strings
+            && types == ["string"]
+            && values.len() < self.string_samples
+        {
+            map.insert("enum".to_string(), values.iter().cloned().map(Value::String).collect());
+        }
+
+        if let Some(items) = &self.items
+            && self.has_items
+        {
+            map.insert("items".to_string(), items.to_schema());
+        }
+
+        if let Some(object) = &self.object {
+            let properties: Map<String, Value> = object.properties.iter()
+                .map(|(key, _, shape)| (key.clone(), shape.to_schema()))
+                .collect();
+            let required: Vec<Value> = object.properties.iter()
+                .filter(|(_, seen, _)| *seen == object.samples)
+                .map(|(key, _, _)| Value::String(key.clone()))
+                .collect();
+            map.insert("properties".to_string(), Value::Object(properties));
+            if !required.is_empty() {
+                map.insert("required".to_string(), Value::Array(required));
+            }
+        }
+
+        Value::Object(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::build_tree;
+    use serde_json::json;
+
+    fn keywords(schema: &Value, instance: &Value) -> Vec<(String, String)> {
+        validate(schema, instance, SchemaDraft::detect(schema))
+            .unwrap()
+            .violations
+            .into_iter()
+            .map(|v| (v.pointer, v.keyword))
+            .collect()
+    }
+
+    #[test]
+    fn test_type_and_required() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "name"],
+            "properties": {
+                "id": {"type": "integer"},
+                "name": {"type": "string", "minLength": 2},
+                "tags": {"type": "array", "items": {"type": "string"}, "uniqueItems": true}
+            },
+            "additionalProperties": false
+        });
+
+        assert!(keywords(&schema, &json!({"id": 1, "name": "ok"})).is_empty());
+        assert_eq!(
+            keywords(&schema, &json!({"id": 1.5, "tags": ["a", 2, "a"], "extra": true})),
+            vec![
+                ("".to_string(), "required".to_string()),
+                ("/id".to_string(), "type".to_string()),
+                ("/tags/1".to_string(), "type".to_string()),
+                ("/tags/2".to_string(), "uniqueItems".to_string()),
+                ("/extra".to_string(), "additionalProperties".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_numbers_strings_and_enums() {
+        let schema = json!({
+            "properties": {
+                "age": {"minimum": 0, "exclusiveMaximum": 150, "multipleOf": 1},
+                "code": {"pattern": "^[A-Z]{3}$"},
+                "level": {"enum": ["low", "high"]},
+                "kind": {"const": "user"}
+            }
+        });
+
+        let violations = keywords(&schema, &json!({"age": 150, "code": "abc", "level": "mid", "kind": "user"}));
+        assert_eq!(
+            violations,
+            vec![
+                ("/age".to_string(), "exclusiveMaximum".to_string()),
+                ("/code".to_string(), "pattern".to_string()),
+                ("/level".to_string(), "enum".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_combinators_and_conditionals() {
+        let schema = json!({
+            "oneOf": [{"type": "string"}, {"type": "number"}],
+            "not": {"const": 0},
+            "if": {"type": "number"},
+            "then": {"minimum": -5}
+        });
+
+        assert!(keywords(&schema, &json!("text")).is_empty());
+        assert_eq!(keywords(&schema, &json!(0)), vec![("".to_string(), "not".to_string())]);
+        assert_eq!(keywords(&schema, &json!(-10)), vec![("".to_string(), "minimum".to_string())]);
+        assert_eq!(keywords(&schema, &json!(true)), vec![("".to_string(), "oneOf".to_string())]);
+    }
+
+    #[test]
+    fn test_refs_and_drafts() {
+        let modern = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$defs": {"point": {"prefixItems": [{"type": "number"}, {"type": "number"}], "items": false}},
+            "$ref": "#/$defs/point",
+            "maxItems": 2
+        });
+        assert_eq!(SchemaDraft::detect(&modern), SchemaDraft::Draft202012);
+        assert!(keywords(&modern, &json!([1, 2])).is_empty());
+        let violations = keywords(&modern, &json!([1, "y", 3]));
+        assert!(violations.contains(&("/1".to_string(), "type".to_string())));
+        assert!(violations.contains(&("/2".to_string(), "false".to_string())));
+        assert!(violations.contains(&("".to_string(), "maxItems".to_string())), "2020-12 applies $ref siblings");
+
+        let draft7 = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "definitions": {"pair": {"items": [{"type": "string"}], "additionalItems": {"type": "number"}}},
+            "$ref": "#/definitions/pair",
+            "maxItems": 1
+        });
+        assert_eq!(SchemaDraft::detect(&draft7), SchemaDraft::Draft7);
+        assert_eq!(keywords(&draft7, &json!(["a", "b"])), vec![("/1".to_string(), "type".to_string())]);
+
+        assert!(validate(&json!({"$ref": "#/$defs/missing"}), &json!(1), SchemaDraft::Draft202012).is_err());
+        assert!(validate(&json!({"$ref": "#"}), &json!(1), SchemaDraft::Draft202012).is_err(), "cycles are reported");
+    }
+
+    #[test]
+    fn test_recursive_ref_on_deep_data() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"child": {"$ref": "#"}, "name": {"type": "string"}}
+        });
+        let mut instance = json!({"name": "leaf"});
+        for _ in 0..300 {
+            instance = json!({"child": instance});
+        }
+        assert!(validate(&schema, &instance, SchemaDraft::Draft202012).unwrap().violations.is_empty());
+
+        instance = json!({"child": {"name": 1}});
+        let report = validate(&schema, &instance, SchemaDraft::Draft202012).unwrap();
+        assert_eq!(report.violations[0].pointer, "/child/name");
+    }
+
+    #[test]
+    fn test_remote_refs_are_skipped() {
+        let schema = json!({
+            "properties": {
+                "address": {"$ref": "https://example.com/address.json"},
+                "zip": {"$ref": "other.json#/zip", "type": "string"},
+                "age": {"type": "integer"}
+            }
+        });
+        let report = validate(&schema, &json!({"address": 1, "zip": 5, "age": "old"}), SchemaDraft::Draft202012).unwrap();
+        assert_eq!(report.unresolved_refs, vec!["https://example.com/address.json", "other.json#/zip"]);
+        let failed: Vec<&str> = report.violations.iter().map(|v| v.pointer.as_str()).collect();
+        assert_eq!(failed, vec!["/zip", "/age"], "siblings and other properties are still checked");
+    }
+
+    #[test]
+    fn test_session_uses_its_draft() {
+        // Without $schema this would be read as 2020-12, where $ref siblings apply
+        let schema = json!({"$defs": {"any": true}, "$ref": "#/$defs/any", "type": "string"});
+        let tree = build_tree(&json!(1));
+        let mut session = SchemaSession {
+            path: PathBuf::from("schema.json"),
+            schema,
+            draft: SchemaDraft::Draft7,
+            violations: Vec::new(),
+            by_node: HashMap::new(),
+            error: None,
+            unresolved_refs: Vec::new(),
+            show_problems: false,
+        };
+        session.validate(&tree);
+        assert!(session.violations.is_empty());
+    }
+
+    #[test]
+    fn test_dependencies_and_property_names() {
+        let schema = json!({
+            "dependentRequired": {"card": ["billing"]},
+            "dependencies": {"vip": {"required": ["tier"]}},
+            "propertyNames": {"maxLength": 5}
+        });
+
+        let violations = keywords(&schema, &json!({"card": 1, "vip": true, "toolong": 0}));
+        assert_eq!(
+            violations,
+            vec![
+                ("/toolong".to_string(), "propertyNames".to_string()),
+                ("".to_string(), "dependentRequired".to_string()),
+                ("".to_string(), "required".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_session_maps_violations_to_nodes() {
+        let tree = build_tree(&json!({"users": [{"email": "a@b.c"}, {"email": 7}]}));
+        let mut session = SchemaSession {
+            path: PathBuf::from("schema.json"),
+            schema: json!({"properties": {"users": {"items": {"properties": {"email": {"type": "string"}}}}}}),
+            draft: SchemaDraft::Draft202012,
+            violations: Vec::new(),
+            by_node: HashMap::new(),
+            error: None,
+            unresolved_refs: Vec::new(),
+            show_problems: false,
+        };
+
+        session.validate(&tree);
+        assert_eq!(session.violations.len(), 1);
+        let email = tree.resolve_pointer("/users/1/email").unwrap();
+        assert_eq!(session.by_node.get(&email), Some(&vec![0]));
+    }
+
+    #[test]
+    fn test_infer_schema_merges_array_items() {
+        let tree = build_tree(&json!({
+            "users": [
+                {"id": 1, "role": "admin", "email": "a@b.c"},
+                {"id": 2, "role": "user", "score": 1.5},
+                {"id": 3, "role": "user", "email": null}
+            ]
+        }));
+
+        let schema = infer_schema(&tree, tree.root_index());
+        assert_eq!(schema["$schema"], json!(DRAFT_2020_12_URI));
+        assert_eq!(schema.as_object().unwrap().keys().next().unwrap(), "$schema");
+        assert_eq!(schema["required"], json!(["users"]));
+
+        let item = &schema["properties"]["users"]["items"];
+        assert_eq!(item["type"], json!("object"));
+        assert_eq!(item["required"], json!(["id", "role"]));
+        assert_eq!(item["properties"]["id"], json!({"type": "integer"}));
+        assert_eq!(item["properties"]["role"], json!({"type": "string", "enum": ["admin", "user"]}));
+        assert_eq!(item["properties"]["email"], json!({"type": ["string", "null"]}));
+        assert_eq!(item["properties"]["score"], json!({"type": "number"}));
+    }
+
+    #[test]
+    fn test_inferred_schema_validates_its_source() {
+        let value = json!({"tags": ["a", "b", "c"], "nested": {"flag": true, "empty": []}, "n": 3});
+        let tree = build_tree(&value);
+
+        let schema = infer_schema(&tree, tree.root_index());
+        assert_eq!(schema["properties"]["tags"], json!({"type": "array", "items": {"type": "string"}}));
+        assert_eq!(schema["properties"]["nested"]["properties"]["empty"], json!({"type": "array"}));
+        assert!(validate(&schema, &value, SchemaDraft::Draft202012).unwrap().violations.is_empty());
+    }
+}
@@ -1,12 +1,65 @@
 //! Search functionality for the JSON tree.
 //!
 //! Supports plain text and regex search with case sensitivity options.
-//! Search always checks both keys and values simultaneously.
+//! By default search checks both keys and values; a [`SearchFilter`]
+//! narrows it to keys, values, or values of certain types, and
+//! [`search_subtree`] to the descendants of one node.
 
+use crate::flat_row::ValueType;
 use crate::parser::{JsonTree, JsonValue};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Which parts of each node a search looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchFilter {
+    pub keys: bool,
+    pub values: bool,
+    /// Value types searched when `values` is on
+    pub strings: bool,
+    pub numbers: bool,
+    pub booleans: bool,
+    pub nulls: bool,
+}
+
+impl Default for SearchFilter {
+    fn default() -> Self {
+        SearchFilter { keys: true, values: true, strings: true, numbers: true, booleans: true, nulls: true }
+    }
+}
+
+impl SearchFilter {
+    /// Whether this value is searched (containers never are)
+    pub fn searches_value(&self, value: &JsonValue) -> bool {
+        self.searches_type(match value {
+            JsonValue::String(_) => ValueType::String,
+            JsonValue::Number(_) => ValueType::Number,
+            JsonValue::Bool(_) => ValueType::Bool,
+            JsonValue::Null => ValueType::Null,
+            _ => ValueType::Bracket,
+        })
+    }
+
+    /// Whether values of a row's type are searched
+    pub fn searches_type(&self, value_type: ValueType) -> bool {
+        self.values && match value_type {
+            ValueType::String => self.strings,
+            ValueType::Number => self.numbers,
+            ValueType::Bool => self.booleans,
+            ValueType::Null => self.nulls,
+            ValueType::Bracket | ValueType::Key => false,
+        }
+    }
+
+    /// Whether every part of every node is searched
+    pub fn is_default(&self) -> bool {
+        *self == SearchFilter::default()
+    }
+}
 
-/// Search all nodes in the tree for matches against both keys and values.
+/// Search all nodes in the tree for matches against keys and values, as
+/// allowed by `filter`.
 ///
 /// Returns `(results, error_message)` where `error_message` is `Some` if the
 /// regex pattern is invalid.
@@ -16,11 +69,63 @@ use regex::Regex;
 /// * `query`          – the search string (or regex pattern)
 /// * `case_sensitive` – whether the match is case-sensitive
 /// * `use_regex`      – interpret `query` as a regular expression
+/// * `filter`         – which keys and values are checked
 pub fn search_nodes(
     tree: &JsonTree,
     query: &str,
     case_sensitive: bool,
     use_regex: bool,
+    filter: SearchFilter,
+) -> (Vec<usize>, Option<String>) {
+    search_nodes_from(tree, 0, query, case_sensitive, use_regex, filter)
+}
+
+/// Like [`search_nodes`], but only checks nodes with index `start` or above.
+///
+/// Nodes appended to the tree (JSON Lines follow mode) get the highest
+/// indices, so this searches just the new ones.
+pub fn search_nodes_from(
+    tree: &JsonTree,
+    start: usize,
+    query: &str,
+    case_sensitive: bool,
+    use_regex: bool,
+    filter: SearchFilter,
+) -> (Vec<usize>, Option<String>) {
+    search_in(tree, start..tree.node_count(), query, case_sensitive, use_regex, filter)
+}
+
+/// Like [`search_nodes`], but only checks the descendants of `scope`, in
+/// document order.
+pub fn search_subtree(
+    tree: &JsonTree,
+    scope: usize,
+    query: &str,
+    case_sensitive: bool,
+    use_regex: bool,
+    filter: SearchFilter,
+) -> (Vec<usize>, Option<String>) {
+    let mut descendants = Vec::new();
+    let mut pending: Vec<usize> = tree.get_node(scope)
+        .map(|node| node.children.iter().rev().copied().collect())
+        .unwrap_or_default();
+    while let Some(index) = pending.pop() {
+        descendants.push(index);
+        if let Some(node) = tree.get_node(index) {
+            pending.extend(node.children.iter().rev());
+        }
+    }
+    search_in(tree, descendants, query, case_sensitive, use_regex, filter)
+}
+
+/// Check the given nodes, in order
+fn search_in(
+    tree: &JsonTree,
+    nodes: impl IntoIterator<Item = usize>,
+    query: &str,
+    case_sensitive: bool,
+    use_regex: bool,
+    filter: SearchFilter,
 ) -> (Vec<usize>, Option<String>) {
     if query.is_empty() {
         return (Vec::new(), None);
@@ -54,14 +159,14 @@ pub fn search_nodes(
         }
     };
 
-    // Iterate through all nodes
-    for i in 0..tree.node_count() {
+    for i in nodes {
         let Some(node) = tree.get_node(i) else {
             continue;
         };
 
         // --- Key check ---
-        if let Some(key) = &node.key
+        if filter.keys
+            && let Some(key) = &node.key
             && matches(key)
         {
             results.push(i);
@@ -69,6 +174,9 @@ pub fn search_nodes(
         }
 
         // --- Value check ---
+        if !filter.searches_value(&node.value) {
+            continue;
+        }
         let value_matches = match &node.value {
             JsonValue::String(s) => matches(s),
             JsonValue::Number(n) => matches(&n.to_string()),
@@ -207,12 +315,12 @@ mod tests {
         let tree = build_tree(&value);
 
         // Search for "Unfold" (appears in value)
-        let (results, error) = search_nodes(&tree, "Unfold", false, false);
+        let (results, error) = search_nodes(&tree, "Unfold", false, false, SearchFilter::default());
         assert!(error.is_none());
         assert!(!results.is_empty(), "Should find 'Unfold'");
 
         // Search for non-existent
-        let (results, error) = search_nodes(&tree, "nonexistent", false, false);
+        let (results, error) = search_nodes(&tree, "nonexistent", false, false, SearchFilter::default());
         assert!(error.is_none());
         assert!(results.is_empty());
     }
@@ -223,15 +331,15 @@ mod tests {
         let tree = build_tree(&value);
 
         // Case insensitive should find key "Name" via "name"
-        let (results, _) = search_nodes(&tree, "name", false, false);
+        let (results, _) = search_nodes(&tree, "name", false, false, SearchFilter::default());
         assert!(!results.is_empty());
 
         // Case sensitive should not find lowercase "name" when key is "Name"
-        let (results, _) = search_nodes(&tree, "name", true, false);
+        let (results, _) = search_nodes(&tree, "name", true, false, SearchFilter::default());
         assert!(results.is_empty());
 
         // Case sensitive should find exact match
-        let (results, _) = search_nodes(&tree, "Name", true, false);
+        let (results, _) = search_nodes(&tree, "Name", true, false, SearchFilter::default());
         assert!(!results.is_empty());
     }
 
@@ -241,21 +349,34 @@ mod tests {
         let tree = build_tree(&value);
 
         // Regex search for email pattern
-        let (results, error) = search_nodes(&tree, r".*@.*\.com", false, true);
+        let (results, error) = search_nodes(&tree, r".*@.*\.com", false, true, SearchFilter::default());
         assert!(error.is_none());
         assert!(!results.is_empty());
 
         // Invalid regex should return error
-        let (results, error) = search_nodes(&tree, r"[invalid", false, true);
+        let (results, error) = search_nodes(&tree, r"[invalid", false, true, SearchFilter::default());
         assert!(error.is_some());
         assert!(results.is_empty());
     }
 
-    /// Search always checks both keys and values.
+    #[test]
+    fn test_search_nodes_from_only_checks_new_nodes() {
+        let mut tree = build_tree(&json!([{"level": "error"}]));
+        let start = tree.node_count();
+        crate::parser::append_root_element(&mut tree, &json!({"level": "error", "code": 7}));
+
+        let (all, _) = search_nodes(&tree, "error", false, false, SearchFilter::default());
+        let (new, _) = search_nodes_from(&tree, start, "error", false, false, SearchFilter::default());
+        assert_eq!(all.len(), 2);
+        assert_eq!(new, &all[1..]);
+        assert!(new.iter().all(|&i| i >= start));
+    }
+
+    /// By default search checks both keys and values.
     #[test]
     fn test_search_matches_keys_and_values() {
         let tree = tran_tree();
-        let (results, error) = search_nodes(&tree, "tran", false, false);
+        let (results, error) = search_nodes(&tree, "tran", false, false, SearchFilter::default());
         assert!(error.is_none());
         assert_eq!(
             results.len(),
@@ -269,7 +390,7 @@ mod tests {
     fn test_search_no_duplicate_when_key_and_value_both_match() {
         // {"transfer": "transfer credit"} – key="transfer", value contains "transfer"
         let tree = build_tree(&json!({"transfer": "transfer credit"}));
-        let (results, _) = search_nodes(&tree, "transfer", false, false);
+        let (results, _) = search_nodes(&tree, "transfer", false, false, SearchFilter::default());
         assert_eq!(
             results.len(),
             1,
@@ -277,11 +398,42 @@ mod tests {
         );
     }
 
+    /// Filters restrict matching to keys, values, or value types.
+    #[test]
+    fn test_search_filter() {
+        let tree = build_tree(&json!({"status": "status ok", "count": 7, "flag": true, "none": null, "list": [7]}));
+        let search = |query, filter| search_nodes(&tree, query, false, false, filter).0;
+        let keys_only = SearchFilter { values: false, ..SearchFilter::default() };
+        let values_only = SearchFilter { keys: false, ..SearchFilter::default() };
+        let numbers_only = SearchFilter { keys: false, strings: false, booleans: false, nulls: false, ..SearchFilter::default() };
+
+        assert_eq!(search("status", SearchFilter::default()).len(), 1);
+        assert_eq!(search("ok", keys_only), Vec::<usize>::new());
+        assert_eq!(search("count", values_only), Vec::<usize>::new());
+        assert_eq!(search("ok", values_only), vec![tree.resolve_pointer("/status").unwrap()]);
+        assert_eq!(search("7", numbers_only).len(), 2);
+        assert_eq!(search("true", numbers_only), Vec::<usize>::new());
+        assert_eq!(search("null", SearchFilter { nulls: false, ..SearchFilter::default() }), Vec::<usize>::new());
+    }
+
+    /// A scoped search only finds descendants of the scope node.
+    #[test]
+    fn test_search_subtree() {
+        let tree = build_tree(&json!({"a": {"id": 1, "list": [{"id": 2}]}, "b": {"id": 3}}));
+        let node = |pointer| tree.resolve_pointer(pointer).unwrap();
+        let (results, _) = search_subtree(&tree, node("/a"), "id", false, false, SearchFilter::default());
+        assert_eq!(results, vec![node("/a/id"), node("/a/list/0/id")]);
+
+        // The scope node itself isn't a match
+        let (results, _) = search_subtree(&tree, node("/b"), "b", false, false, SearchFilter::default());
+        assert!(results.is_empty());
+    }
+
     /// Empty query returns no results.
     #[test]
     fn test_search_empty_query() {
         let tree = tran_tree();
-        let (results, error) = search_nodes(&tree, "", false, false);
+        let (results, error) = search_nodes(&tree, "", false, false, SearchFilter::default());
         assert!(error.is_none());
         assert!(results.is_empty(), "Empty query should return no results");
     }
@@ -295,7 +447,7 @@ mod tests {
         //   - key "crsTransferStatus"  (contains "Transfer" → "Tran")
         //   - key "innerTransfer"      (contains "Transfer" → "Tran")
         //   - value "John Tran"        (exact "Tran")
-        let (results, _) = search_nodes(&tree, "Tran", true, false);
+        let (results, _) = search_nodes(&tree, "Tran", true, false, SearchFilter::default());
         assert_eq!(
             results.len(),
             3,
@@ -303,7 +455,7 @@ mod tests {
         );
 
         // "TRAN" (all caps, case-sensitive) → no match anywhere
-        let (results, _) = search_nodes(&tree, "TRAN", true, false);
+        let (results, _) = search_nodes(&tree, "TRAN", true, false, SearchFilter::default());
         assert!(results.is_empty(), "Case-sensitive 'TRAN' should find nothing");
     }
 
@@ -314,7 +466,7 @@ mod tests {
         // "transfer" in keys: crsTransferStatus, innerTransfer (2)
         // "transfer" or "transition" in values: description, creditType, innerField (3)
         // studentName "John Tran" doesn't match trans(fer|ition)
-        let (results, error) = search_nodes(&tree, r"trans(fer|ition)", false, true);
+        let (results, error) = search_nodes(&tree, r"trans(fer|ition)", false, true, SearchFilter::default());
         assert!(error.is_none());
         assert_eq!(
             results.len(),
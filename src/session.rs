@@ -0,0 +1,181 @@
+//! Restoring the previous session at launch.
+//!
+//! Stores the open tabs in ~/.unfold/session.json, next to the config. Each
+//! tab keeps its file, view state (by JSON Pointer, see [`ViewSnapshot`]) and
+//! search query, so a restart puts everything back where it was.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::{self, Config};
+use crate::document::Document;
+use crate::search::SearchFilter;
+use crate::watcher::ViewSnapshot;
+
+/// File in the config directory holding the session
+const SESSION_FILE: &str = "session.json";
+
+/// Open tabs saved when the app quits
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub tabs: Vec<SessionTab>,
+    /// Index of the active tab in `tabs`
+    pub active_tab: usize,
+}
+
+/// One restorable tab
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTab {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub view: ViewSnapshot,
+    #[serde(default)]
+    pub search_query: String,
+    #[serde(default)]
+    pub search_case_sensitive: bool,
+    #[serde(default)]
+    pub search_use_regex: bool,
+    #[serde(default)]
+    pub search_filter: SearchFilter,
+    #[serde(default)]
+    pub search_scope: Option<String>,
+}
+
+impl SessionTab {
+    /// Snapshot a document; empty tabs and compare views aren't restorable
+    pub fn capture(doc: &Document) -> Option<Self> {
+        let (Some(path), Some(tree)) = (&doc.current_file, &doc.tree) else {
+            return None;
+        };
+        if doc.compare.is_some() {
+            return None;
+        }
+        let current_result = doc.search_result_index.and_then(|i| doc.search_results.get(i).copied());
+        Some(SessionTab {
+            path: path.clone(),
+            view: ViewSnapshot::capture(tree, doc.selected_node, current_result, doc.scroll_offset),
+            search_query: doc.search_query.clone(),
+            search_case_sensitive: doc.search_case_sensitive,
+            search_use_regex: doc.search_use_regex,
+            search_filter: doc.search_filter,
+            search_scope: doc.search_scope.clone(),
+        })
+    }
+}
+
+impl Session {
+    /// Snapshot the open documents (in tab order) with `active` as the active tab
+    pub fn capture<'a>(documents: impl Iterator<Item = &'a Document>, active: usize) -> Self {
+        let mut session = Session::default();
+        for (index, doc) in documents.enumerate() {
+            if index == active {
+                session.active_tab = session.tabs.len();
+            }
+            if let Some(tab) = SessionTab::capture(doc) {
+                session.tabs.push(tab);
+            }
+        }
+        session.active_tab = session.active_tab.min(session.tabs.len().saturating_sub(1));
+        session
+    }
+
+    /// Drop tabs whose file no longer exists, keeping the active tab in place
+    pub fn retain_existing(&mut self) {
+        let mut index = 0;
+        let active = self.active_tab;
+        self.tabs.retain(|tab| {
+            let exists = tab.path.is_file();
+            if !exists && index < active {
+                self.active_tab -= 1;
+            }
+            index += 1;
+            exists
+        });
+        self.active_tab = self.active_tab.min(self.tabs.len().saturating_sub(1));
+    }
+
+    /// Get the session file path (~/.unfold/session.json)
+    pub fn session_path() -> Option<PathBuf> {
+        Config::config_dir().map(|dir| dir.join(SESSION_FILE))
+    }
+
+    /// Load the saved session, or an empty one if there is none
+    pub fn load() -> Self {
+        config::load_json(SESSION_FILE)
+    }
+
+    /// Save the session, replacing the previous one
+    pub fn save(&self) -> Result<(), String> {
+        config::save_json(SESSION_FILE, "session", self)
+    }
+
+    /// Forget the saved session (when restoring is turned off)
+    pub fn clear() {
+        if let Some(path) = Self::session_path() {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::build_tree;
+    use serde_json::json;
+
+    fn open(path: &str) -> Document {
+        Document {
+            tree: Some(build_tree(&json!({"a": [1, 2]}))),
+            current_file: Some(PathBuf::from(path)),
+            ..Document::default()
+        }
+    }
+
+    #[test]
+    fn test_capture_skips_empty_tabs() {
+        let mut searched = open("/data/b.json");
+        searched.search_query = "needle".to_string();
+        searched.scroll_offset = 40.0;
+        let docs = [open("/data/a.json"), Document::default(), searched];
+
+        let session = Session::capture(docs.iter(), 2);
+        assert_eq!(session.tabs.len(), 2);
+        assert_eq!(session.active_tab, 1);
+        assert_eq!(session.tabs[1].search_query, "needle");
+        assert_eq!(session.tabs[1].view.scroll_offset, 40.0);
+
+        // Round-trips through JSON
+        let json = serde_json::to_string(&session).unwrap();
+        let parsed: Session = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.tabs[0].path, PathBuf::from("/data/a.json"));
+        assert_eq!(parsed.active_tab, 1);
+    }
+
+    #[test]
+    fn test_retain_existing_drops_missing_files() {
+        let existing = std::env::temp_dir().join(format!("unfold-session-test-{}.json", std::process::id()));
+        fs::write(&existing, "{}").unwrap();
+        let tab = |path: PathBuf| SessionTab {
+            path,
+            view: ViewSnapshot::default(),
+            search_query: String::new(),
+            search_case_sensitive: false,
+            search_use_regex: false,
+            search_filter: SearchFilter::default(),
+            search_scope: None,
+        };
+
+        let mut session = Session {
+            tabs: vec![tab(PathBuf::from("/no/such/file.json")), tab(existing.clone())],
+            active_tab: 1,
+        };
+        session.retain_existing();
+        assert_eq!(session.tabs.len(), 1);
+        assert_eq!(session.tabs[0].path, existing);
+        assert_eq!(session.active_tab, 0);
+
+        let _ = fs::remove_file(&existing);
+    }
+}
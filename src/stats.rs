@@ -0,0 +1,281 @@
+//! Document statistics and subtree size analysis.
+//!
+//! Everything is computed in one pass over the flat node array. The parser
+//! adds children before their parent, so by the time a container is visited
+//! the sizes of all of its children are already known. The root is visited
+//! last because JSON Lines follow mode appends elements after it.
+
+use crate::json_export::number_to_value;
+use crate::parser::{JsonTree, JsonValue};
+
+/// Length of the "longest strings" / "largest arrays" lists
+const TOP_LIST_LEN: usize = 10;
+
+/// Length of the "heaviest nodes" list
+const HEAVIEST_LIST_LEN: usize = 100;
+
+/// How the "heaviest nodes" list is ordered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeavySort {
+    /// Serialized (minified) size of the subtree
+    Bytes,
+    /// Number of nodes in the subtree
+    Nodes,
+}
+
+/// Node counts per `JsonValue` type
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TypeCounts {
+    pub nulls: usize,
+    pub bools: usize,
+    pub numbers: usize,
+    pub strings: usize,
+    pub arrays: usize,
+    pub objects: usize,
+}
+
+/// Statistics for a whole document
+#[derive(Debug)]
+pub struct DocumentStats {
+    pub counts: TypeCounts,
+    pub max_depth: usize,
+    pub average_depth: f64,
+    /// Minified serialized size of each node's value, indexed like the tree
+    pub subtree_bytes: Vec<usize>,
+    /// Number of nodes in each node's subtree (including itself)
+    pub subtree_nodes: Vec<usize>,
+    /// Parent of each node (`None` for the root)
+    parents: Vec<Option<usize>>,
+    /// String nodes ordered by length, longest first
+    pub longest_strings: Vec<usize>,
+    /// Array nodes ordered by element count, largest first
+    pub largest_arrays: Vec<usize>,
+    /// Containers below the root ordered by serialized size
+    heaviest_by_bytes: Vec<usize>,
+    /// Containers below the root ordered by subtree node count
+    heaviest_by_nodes: Vec<usize>,
+    /// Which ordering the panel currently shows
+    pub sort: HeavySort,
+}
+
+impl DocumentStats {
+    pub fn compute(tree: &JsonTree) -> Self {
+        let count = tree.node_count();
+        let mut counts = TypeCounts::default();
+        let mut max_depth = 0;
+        let mut depth_sum = 0usize;
+        let mut subtree_bytes = vec![0; count];
+        let mut subtree_nodes = vec![1; count];
+        let mut parents = vec![None; count];
+        let mut strings = Vec::new();
+        let mut arrays = Vec::new();
+        let mut containers = Vec::new();
+
+        let root = tree.root_index();
+        for index in (0..count).filter(|&i| i != root).chain(std::iter::once(root)) {
+            let Some(node) = tree.get_node(index) else {
+                continue;
+            };
+            max_depth = max_depth.max(node.depth);
+            depth_sum += node.depth;
+
+            for &child in &node.children {
+                parents[child] = Some(index);
+            }
+            let children_bytes: usize = node.children.iter().map(|&c| subtree_bytes[c]).sum();
+            let separators = node.children.len().saturating_sub(1);
+            subtree_nodes[index] += node.children.iter().map(|&c| subtree_nodes[c]).sum::<usize>();
+
+            subtree_bytes[index] = match &node.value {
+                JsonValue::Null => {
+                    counts.nulls += 1;
+                    4
+                }
+                JsonValue::Bool(b) => {
+                    counts.bools += 1;
+                    if *b { 4 } else { 5 }
+                }
+                JsonValue::Number(n) => {
+                    counts.numbers += 1;
+                    number_to_value(*n).to_string().len()
+                }
+                JsonValue::String(s) => {
+                    counts.strings += 1;
+                    strings.push(index);
+                    escaped_len(s)
+                }
+                JsonValue::Array => {
+                    counts.arrays += 1;
+                    arrays.push(index);
+                    containers.push(index);
+                    2 + children_bytes + separators
+                }
+                JsonValue::Object => {
+                    counts.objects += 1;
+                    containers.push(index);
+                    // Every member adds its quoted key and a colon
+                    let keys: usize = node.children.iter()
+                        .filter_map(|&c| tree.get_node(c))
+                        .map(|child| escaped_len(child.key.as_deref().unwrap_or_default()) + 1)
+                        .sum();
+                    2 + keys + children_bytes + separators
+                }
+            };
+        }
+
+        let string_len = |index: usize| match tree.get_node(index).map(|n| &n.value) {
+            Some(JsonValue::String(s)) => s.chars().count(),
+            _ => 0,
+        };
+        let array_len = |index: usize| tree.get_node(index).map_or(0, |n| n.children.len());
+        let longest_strings = top_by(strings, TOP_LIST_LEN, string_len);
+        let largest_arrays = top_by(arrays, TOP_LIST_LEN, array_len);
+
+        containers.retain(|&index| index != tree.root_index());
+        let heaviest_by_bytes = top_by(containers.clone(), HEAVIEST_LIST_LEN, |i| subtree_bytes[i]);
+        let heaviest_by_nodes = top_by(containers, HEAVIEST_LIST_LEN, |i| subtree_nodes[i]);
+
+        DocumentStats {
+            counts,
+            max_depth,
+            average_depth: if count == 0 { 0.0 } else { depth_sum as f64 / count as f64 },
+            subtree_bytes,
+            subtree_nodes,
+            parents,
+            longest_strings,
+            largest_arrays,
+            heaviest_by_bytes,
+            heaviest_by_nodes,
+            sort: HeavySort::Bytes,
+        }
+    }
+
+    /// The heaviest containers in the current sort order
+    pub fn heaviest(&self) -> &[usize] {
+        match self.sort {
+            HeavySort::Bytes => &self.heaviest_by_bytes,
+            HeavySort::Nodes => &self.heaviest_by_nodes,
+        }
+    }
+
+    /// Display path of a node in the same form as the status bar ("users[2].email")
+    pub fn path(&self, tree: &JsonTree, index: usize) -> String {
+        let mut segments = Vec::new();
+        let mut current = index;
+        while let Some(parent) = self.parents.get(current).copied().flatten() {
+            if let Some(key) = tree.get_node(current).and_then(|n| n.key.as_deref()) {
+                segments.push(key);
+            }
+            current = parent;
+        }
+
+        let mut path = String::new();
+        for segment in segments.iter().rev() {
+            if !path.is_empty() && !segment.starts_with('[') {
+                path.push('.');
+            }
+            path.push_str(segment);
+        }
+        if path.is_empty() { "(root)".to_string() } else { path }
+    }
+}
+
+/// The `limit` entries with the largest `metric`, largest first
+fn top_by(mut indices: Vec<usize>, limit: usize, metric: impl Fn(usize) -> usize) -> Vec<usize> {
+    // Stable sort keeps document order among equal entries
+    indices.sort_by_key(|&i| std::cmp::Reverse(metric(i)));
+    indices.truncate(limit);
+    indices
+}
+
+/// Length of a string once quoted and escaped as JSON
+fn escaped_len(s: &str) -> usize {
+    let escapes: usize = s.chars()
+        .map(|c| match c {
+            '"' | '\\' | '\n' | '\r' | '\t' | '\u{8}' | '\u{c}' => 1,
+            c if (c as u32) < 0x20 => 5,
+            _ => 0,
+        })
+        .sum();
+    s.len() + escapes + 2
+}
+
+/// Human readable byte count ("512 B", "1.5 KB", "40.0 MB")
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::build_tree;
+    use serde_json::json;
+
+    #[test]
+    fn test_subtree_bytes_match_minified_json() {
+        let value = json!({
+            "name": "a \"quoted\"\nline\u{1}",
+            "list": [1, 2.5, null, true, false, {"k": []}],
+            "empty": {}
+        });
+        let tree = build_tree(&value);
+        let stats = DocumentStats::compute(&tree);
+
+        assert_eq!(stats.subtree_bytes[tree.root_index()], serde_json::to_string(&value).unwrap().len());
+        let list = tree.get_node(tree.root_index()).unwrap().children[1];
+        assert_eq!(stats.subtree_bytes[list], serde_json::to_string(&value["list"]).unwrap().len());
+        assert_eq!(stats.subtree_nodes[list], 8);
+    }
+
+    #[test]
+    fn test_elements_appended_after_root() {
+        let mut tree = build_tree(&json!([{"a": 1}]));
+        crate::parser::append_root_element(&mut tree, &json!({"b": "xyz"}));
+        let stats = DocumentStats::compute(&tree);
+
+        assert_eq!(stats.subtree_bytes[tree.root_index()], r#"[{"a":1},{"b":"xyz"}]"#.len());
+        assert_eq!(stats.subtree_nodes[tree.root_index()], 5);
+        assert_eq!(stats.path(&tree, tree.node_count() - 2), "[1].b");
+    }
+
+    #[test]
+    fn test_counts_depth_and_rankings() {
+        let tree = build_tree(&json!({
+            "short": "ab",
+            "long": "abcdef",
+            "small": [1],
+            "big": [[1, 2, 3], "x"]
+        }));
+        let stats = DocumentStats::compute(&tree);
+
+        assert_eq!(stats.counts, TypeCounts { nulls: 0, bools: 0, numbers: 4, strings: 3, arrays: 3, objects: 1 });
+        assert_eq!(stats.max_depth, 3);
+
+        let keys = |indices: &[usize]| -> Vec<String> {
+            indices.iter().map(|&i| tree.get_node(i).unwrap().key.clone().unwrap_or_default()).collect()
+        };
+        assert_eq!(keys(&stats.longest_strings), ["long", "short", "[1]"]);
+        assert_eq!(keys(&stats.largest_arrays), ["[0]", "big", "small"]);
+        assert_eq!(keys(stats.heaviest()), ["big", "[0]", "small"]);
+        assert_eq!(stats.path(&tree, stats.largest_arrays[0]), "big[0]");
+        assert_eq!(stats.path(&tree, tree.root_index()), "(root)");
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(40 * 1024 * 1024), "40.0 MB");
+    }
+}
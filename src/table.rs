@@ -0,0 +1,308 @@
+//! Grid view for arrays of objects.
+//!
+//! The union of the elements' keys becomes the columns (in first-seen order)
+//! and every element becomes a row. Rows are rendered with the same virtual
+//! scrolling approach as the tree's `flat_rows`, so only the visible slice of
+//! a large array is ever built into widgets.
+
+use std::cmp::Ordering;
+
+use crate::parser::{JsonTree, JsonValue};
+
+/// Narrowest a column can be dragged to
+pub const MIN_COLUMN_WIDTH: f32 = 40.0;
+
+/// Widest a column gets sized automatically
+const MAX_AUTO_WIDTH: f32 = 300.0;
+
+/// Rows sampled when sizing columns to their content
+const WIDTH_SAMPLE_ROWS: usize = 100;
+
+/// Approximate width of one character of the monospace cell font
+const CHAR_WIDTH: f32 = 7.5;
+
+/// A column of the grid
+#[derive(Debug, Clone)]
+pub struct TableColumn {
+    pub key: String,
+    pub width: f32,
+}
+
+/// State of the grid shown for one array node
+#[derive(Debug)]
+pub struct TableView {
+    /// The array node being shown
+    pub node_index: usize,
+    pub columns: Vec<TableColumn>,
+    /// Element node indices in display order
+    pub rows: Vec<usize>,
+    /// Sorted column and direction (`true` = ascending)
+    pub sort: Option<(usize, bool)>,
+    /// Vertical scroll position and height of the grid body
+    pub scroll_offset: f32,
+    pub viewport_height: f32,
+    /// Column being resized: (column, width at drag start, cursor x at drag start)
+    pub resizing: Option<(usize, f32, Option<f32>)>,
+}
+
+/// Whether a node is a non-empty array whose elements are all objects
+pub fn is_tabular(tree: &JsonTree, node_index: usize) -> bool {
+    let Some(node) = tree.get_node(node_index) else {
+        return false;
+    };
+    matches!(node.value, JsonValue::Array)
+        && !node.children.is_empty()
+        && node.children.iter().all(|&c| {
+            tree.get_node(c).is_some_and(|child| matches!(child.value, JsonValue::Object))
+        })
+}
+
+/// Text shown in a grid cell for a node
+pub fn cell_text(tree: &JsonTree, node_index: usize) -> String {
+    let Some(node) = tree.get_node(node_index) else {
+        return String::new();
+    };
+    match &node.value {
+        JsonValue::Null => "null".to_string(),
+        JsonValue::Bool(b) => b.to_string(),
+        JsonValue::Number(n) => n.to_string(),
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Array => format!("[{} items]", node.children.len()),
+        JsonValue::Object => format!("{{{} keys}}", node.children.len()),
+    }
+}
+
+impl TableView {
+    /// Build the grid for a tabular array node
+    pub fn new(tree: &JsonTree, node_index: usize) -> Option<Self> {
+        if !is_tabular(tree, node_index) {
+            return None;
+        }
+        let rows = tree.get_node(node_index)?.children.clone();
+
+        let mut table = TableView {
+            node_index,
+            columns: Vec::new(),
+            rows: Vec::new(),
+            sort: None,
+            scroll_offset: 0.0,
+            viewport_height: 600.0,
+            resizing: None,
+        };
+        table.add_columns(tree, &rows);
+        table.fit_columns(tree, 0, &rows);
+        table.rows = rows;
+        Some(table)
+    }
+
+    /// Add elements appended to the array (JSON Lines follow mode), keeping
+    /// the current sort and column widths. Returns `false` if an element isn't
+    /// an object, so the array can't be shown as a grid anymore.
+    pub fn append_rows(&mut self, tree: &JsonTree, rows: &[usize]) -> bool {
+        if !rows.iter().all(|&r| tree.get_node(r).is_some_and(|n| matches!(n.value, JsonValue::Object))) {
+            return false;
+        }
+        let first_new_column = self.columns.len();
+        self.add_columns(tree, rows);
+        // Columns that are new only occur in the new rows
+        self.fit_columns(tree, first_new_column, rows);
+        self.rows.extend_from_slice(rows);
+        self.apply_sort(tree);
+        true
+    }
+
+    /// Add a column for every key of `rows` not seen yet, in first-seen order
+    fn add_columns(&mut self, tree: &JsonTree, rows: &[usize]) {
+        for &row in rows {
+            let Some(node) = tree.get_node(row) else {
+                continue;
+            };
+            for &cell in &node.children {
+                let Some(key) = tree.get_node(cell).and_then(|c| c.key.as_ref()) else {
+                    continue;
+                };
+                if !self.columns.iter().any(|c| &c.key == key) {
+                    self.columns.push(TableColumn { key: key.clone(), width: 0.0 });
+                }
+            }
+        }
+    }
+
+    /// Size the columns from `first` on to their header and a sample of `rows`
+    fn fit_columns(&mut self, tree: &JsonTree, first: usize, rows: &[usize]) {
+        let sample = &rows[..rows.len().min(WIDTH_SAMPLE_ROWS)];
+        for column in first..self.columns.len() {
+            let widest = sample.iter()
+                .filter_map(|&row| self.cell(tree, row, column))
+                .map(|cell| cell_text(tree, cell).chars().count())
+                .chain(std::iter::once(self.columns[column].key.chars().count() + 2))
+                .max()
+                .unwrap_or(0);
+            self.columns[column].width = (widest as f32 * CHAR_WIDTH + 16.0).clamp(MIN_COLUMN_WIDTH, MAX_AUTO_WIDTH);
+        }
+    }
+
+    /// The node in a row under a column, if the element has that key
+    pub fn cell(&self, tree: &JsonTree, row: usize, column: usize) -> Option<usize> {
+        let key = &self.columns.get(column)?.key;
+        tree.get_node(row)?.children.iter()
+            .copied()
+            .find(|&c| tree.get_node(c).and_then(|n| n.key.as_ref()) == Some(key))
+    }
+
+    /// Cycle a column through ascending, descending and document order
+    pub fn toggle_sort(&mut self, tree: &JsonTree, column: usize) {
+        self.sort = match self.sort {
+            Some((c, true)) if c == column => Some((column, false)),
+            Some((c, false)) if c == column => None,
+            _ => Some((column, true)),
+        };
+        self.apply_sort(tree);
+    }
+
+    /// Order the rows by the sorted column, or in document order
+    fn apply_sort(&mut self, tree: &JsonTree) {
+        let Some(original) = tree.get_node(self.node_index).map(|n| n.children.clone()) else {
+            return;
+        };
+        let Some((column, ascending)) = self.sort else {
+            self.rows = original;
+            return;
+        };
+
+        // Look every cell up once instead of on each comparison
+        let mut keyed: Vec<(Option<&JsonValue>, usize)> = original.iter()
+            .map(|&row| (self.cell(tree, row, column).and_then(|c| tree.get_node(c)).map(|n| &n.value), row))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| {
+            let ordering = compare_cells(*a, *b);
+            if ascending { ordering } else { ordering.reverse() }
+        });
+        self.rows = keyed.into_iter().map(|(_, row)| row).collect();
+    }
+
+    /// Follow the cursor while a column border is being dragged
+    pub fn drag_resize(&mut self, x: f32) {
+        let Some((column, start_width, start_x)) = &mut self.resizing else {
+            return;
+        };
+        let start_x = *start_x.get_or_insert(x);
+        if let Some(col) = self.columns.get_mut(*column) {
+            col.width = (*start_width + x - start_x).max(MIN_COLUMN_WIDTH);
+        }
+    }
+
+    /// Total width of all columns
+    pub fn width(&self) -> f32 {
+        self.columns.iter().map(|c| c.width).sum()
+    }
+}
+
+/// Order cells: missing, null, booleans, numbers, strings, then containers
+fn compare_cells(a: Option<&JsonValue>, b: Option<&JsonValue>) -> Ordering {
+    fn rank(value: Option<&JsonValue>) -> u8 {
+        match value {
+            None => 0,
+            Some(JsonValue::Null) => 1,
+            Some(JsonValue::Bool(_)) => 2,
+            Some(JsonValue::Number(_)) => 3,
+            Some(JsonValue::String(_)) => 4,
+            Some(JsonValue::Array) => 5,
+            Some(JsonValue::Object) => 6,
+        }
+    }
+
+    match (a, b) {
+        (Some(JsonValue::Bool(x)), Some(JsonValue::Bool(y))) => x.cmp(y),
+        (Some(JsonValue::Number(x)), Some(JsonValue::Number(y))) => x.total_cmp(y),
+        (Some(JsonValue::String(x)), Some(JsonValue::String(y))) => x.cmp(y),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{self, build_tree};
+    use serde_json::json;
+
+    fn sample() -> JsonTree {
+        build_tree(&json!([
+            {"name": "b", "age": 30},
+            {"name": "a", "tags": ["x"]},
+            {"name": "c", "age": 5}
+        ]))
+    }
+
+    #[test]
+    fn test_columns_are_union_of_keys() {
+        let tree = sample();
+        let table = TableView::new(&tree, tree.root_index()).unwrap();
+
+        let keys: Vec<&str> = table.columns.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, ["name", "age", "tags"]);
+        assert_eq!(table.rows.len(), 3);
+        assert!(table.cell(&tree, table.rows[1], 1).is_none());
+        assert_eq!(cell_text(&tree, table.cell(&tree, table.rows[1], 2).unwrap()), "[1 items]");
+    }
+
+    #[test]
+    fn test_not_tabular() {
+        let tree = build_tree(&json!({"list": [1, {"a": 1}], "empty": [], "objects": [{"a": 1}]}));
+        let root = tree.get_node(tree.root_index()).unwrap();
+        let tabular: Vec<bool> = root.children.iter().map(|&c| is_tabular(&tree, c)).collect();
+        // Children keep their source order: list, empty, objects
+        assert_eq!(tabular, [false, false, true]);
+        assert!(!is_tabular(&tree, tree.root_index()));
+    }
+
+    #[test]
+    fn test_sort_cycles_through_orders() {
+        let tree = sample();
+        let mut table = TableView::new(&tree, tree.root_index()).unwrap();
+        let names = |table: &TableView| -> Vec<String> {
+            table.rows.iter().map(|&r| cell_text(&tree, table.cell(&tree, r, 0).unwrap())).collect()
+        };
+
+        table.toggle_sort(&tree, 0);
+        assert_eq!(names(&table), ["a", "b", "c"]);
+        table.toggle_sort(&tree, 0);
+        assert_eq!(names(&table), ["c", "b", "a"]);
+        table.toggle_sort(&tree, 0);
+        assert_eq!(names(&table), ["b", "a", "c"]);
+
+        // Missing cells sort first, numbers numerically
+        table.toggle_sort(&tree, 1);
+        assert_eq!(names(&table), ["a", "c", "b"]);
+    }
+
+    #[test]
+    fn test_append_rows() {
+        let mut tree = sample();
+        let mut table = TableView::new(&tree, tree.root_index()).unwrap();
+        table.toggle_sort(&tree, 0);
+
+        let first = parser::append_root_element(&mut tree, &json!({"name": "0", "id": 9})).unwrap();
+        assert!(table.append_rows(&tree, &[first]));
+        let keys: Vec<&str> = table.columns.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, ["name", "age", "tags", "id"]);
+        assert_eq!(table.rows[0], first, "the sort still applies");
+
+        let scalar = parser::append_root_element(&mut tree, &json!(1)).unwrap();
+        assert!(!table.append_rows(&tree, &[scalar]));
+    }
+
+    #[test]
+    fn test_drag_resize() {
+        let tree = sample();
+        let mut table = TableView::new(&tree, tree.root_index()).unwrap();
+        let start = table.columns[0].width;
+
+        table.resizing = Some((0, start, None));
+        table.drag_resize(100.0);
+        table.drag_resize(150.0);
+        assert_eq!(table.columns[0].width, start + 50.0);
+        table.drag_resize(-1000.0);
+        assert_eq!(table.columns[0].width, MIN_COLUMN_WIDTH);
+    }
+}
@@ -41,6 +41,11 @@ pub struct ThemeColors {
     pub error_context: Color,
     pub text_primary: Color,
     pub text_secondary: Color,
+    // Diff colors (compare mode)
+    pub diff_added: Color,
+    pub diff_removed: Color,
+    pub diff_changed: Color,
+    pub diff_moved: Color,
     // Button colors
     pub btn_bg: Color,
     pub btn_bg_hover: Color,
@@ -75,6 +80,11 @@ impl ThemeColors {
             error_context: Color::from_rgb(0.7, 0.7, 0.5),
             text_primary: Color::WHITE,
             text_secondary: Color::from_rgb(0.7, 0.7, 0.7),
+            // Diff colors
+            diff_added: Color::from_rgb(0.35, 0.75, 0.4),
+            diff_removed: Color::from_rgb(0.9, 0.4, 0.4),
+            diff_changed: Color::from_rgb(0.9, 0.75, 0.3),
+            diff_moved: Color::from_rgb(0.55, 0.6, 0.95),
             // Button colors
             btn_bg: Color::from_rgb(0.28, 0.28, 0.30),
             btn_bg_hover: Color::from_rgb(0.32, 0.32, 0.35),
@@ -109,6 +119,11 @@ impl ThemeColors {
             error_context: Color::from_rgb(0.6, 0.5, 0.2),
             text_primary: Color::from_rgb(0.1, 0.1, 0.1),
             text_secondary: Color::from_rgb(0.4, 0.4, 0.4),
+            // Diff colors
+            diff_added: Color::from_rgb(0.1, 0.55, 0.2),
+            diff_removed: Color::from_rgb(0.8, 0.2, 0.2),
+            diff_changed: Color::from_rgb(0.7, 0.5, 0.0),
+            diff_moved: Color::from_rgb(0.3, 0.35, 0.8),
             // Button colors (lighter)
             btn_bg: Color::from_rgb(0.88, 0.88, 0.90),
             btn_bg_hover: Color::from_rgb(0.82, 0.82, 0.85),
@@ -0,0 +1,147 @@
+//! Keyboard movement through the visible tree rows.
+//!
+//! Works on the flattened rows, so only what is on screen (after expansion)
+//! can be reached; moving left and right expands, collapses, or steps
+//! between a node and its parent.
+
+use crate::flat_row::FlatRow;
+use crate::parser::JsonTree;
+
+/// A navigation key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeMove {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    /// Collapse an expanded node, otherwise select its parent
+    Left,
+    /// Expand a collapsed node, otherwise select its first child
+    Right,
+    /// Collapse the node, or its parent when there is nothing to collapse
+    Collapse,
+    /// Expand the node
+    Expand,
+}
+
+/// What a navigation key does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavAction {
+    Select(usize),
+    /// Select a node and set whether it is expanded
+    SetExpanded(usize, bool),
+}
+
+/// The action for `movement` from the selected node. `page_rows` is how many
+/// rows PageUp/PageDown move. With no (visible) selection, the first row is
+/// selected.
+pub fn navigate(
+    tree: &JsonTree,
+    rows: &[FlatRow],
+    selected: Option<usize>,
+    movement: TreeMove,
+    page_rows: usize,
+) -> Option<NavAction> {
+    let last = rows.len().checked_sub(1)?;
+    let Some(position) = selected.and_then(|node| rows.iter().position(|r| r.node_index == node)) else {
+        let row = if movement == TreeMove::Last { last } else { 0 };
+        return Some(NavAction::Select(rows[row].node_index));
+    };
+    let row = &rows[position];
+
+    let target = match movement {
+        TreeMove::Up => position.saturating_sub(1),
+        TreeMove::Down => (position + 1).min(last),
+        TreeMove::PageUp => position.saturating_sub(page_rows.max(1)),
+        TreeMove::PageDown => (position + page_rows.max(1)).min(last),
+        TreeMove::First => 0,
+        TreeMove::Last => last,
+        TreeMove::Left | TreeMove::Collapse if row.is_expandable && row.is_expanded => {
+            return Some(NavAction::SetExpanded(row.node_index, false));
+        }
+        TreeMove::Left => return parent(tree, row.node_index).map(NavAction::Select),
+        TreeMove::Collapse => {
+            return parent(tree, row.node_index).map(|parent| NavAction::SetExpanded(parent, false));
+        }
+        TreeMove::Right | TreeMove::Expand if row.is_expandable && !row.is_expanded => {
+            return Some(NavAction::SetExpanded(row.node_index, true));
+        }
+        TreeMove::Right if row.is_expandable => {
+            // The first child is the next row when the node is expanded
+            return rows.get(position + 1).map(|r| NavAction::Select(r.node_index));
+        }
+        TreeMove::Right | TreeMove::Expand => return None,
+    };
+    Some(NavAction::Select(rows[target].node_index))
+}
+
+/// The parent of a node, unless it is the (hidden) root
+fn parent(tree: &JsonTree, node_index: usize) -> Option<usize> {
+    let path = tree.get_path_to_node(node_index);
+    let parent = *path.get(path.len().checked_sub(2)?)?;
+    (parent != tree.root_index()).then_some(parent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flat_row::ValueType;
+    use crate::parser::build_tree;
+    use serde_json::json;
+
+    /// Rows for the visible nodes, in order
+    fn rows(tree: &JsonTree, pointers: &[&str]) -> Vec<FlatRow> {
+        pointers.iter().enumerate().map(|(i, pointer)| {
+            let node_index = tree.resolve_pointer(pointer).unwrap();
+            let node = tree.get_node(node_index).unwrap();
+            FlatRow::new(
+                node_index, String::new(), None, String::new(), ValueType::Null,
+                node.is_expandable(), node.expanded, i, String::new(),
+            )
+        }).collect()
+    }
+
+    #[test]
+    fn test_vertical_moves() {
+        let tree = build_tree(&json!({"a": 1, "b": 2, "c": 3, "d": 4}));
+        let rows = rows(&tree, &["/a", "/b", "/c", "/d"]);
+        let node = |p: &str| tree.resolve_pointer(p).unwrap();
+        let go = |from: Option<&str>, movement| navigate(&tree, &rows, from.map(node), movement, 2);
+
+        assert_eq!(go(None, TreeMove::Down), Some(NavAction::Select(node("/a"))));
+        assert_eq!(go(None, TreeMove::Last), Some(NavAction::Select(node("/d"))));
+        assert_eq!(go(Some("/a"), TreeMove::Up), Some(NavAction::Select(node("/a"))));
+        assert_eq!(go(Some("/a"), TreeMove::Down), Some(NavAction::Select(node("/b"))));
+        assert_eq!(go(Some("/b"), TreeMove::PageDown), Some(NavAction::Select(node("/d"))));
+        assert_eq!(go(Some("/c"), TreeMove::PageDown), Some(NavAction::Select(node("/d"))));
+        assert_eq!(go(Some("/d"), TreeMove::First), Some(NavAction::Select(node("/a"))));
+    }
+
+    #[test]
+    fn test_left_and_right() {
+        let mut tree = build_tree(&json!({"list": [{"x": 1}], "n": 1}));
+        let node = |tree: &JsonTree, p| tree.resolve_pointer(p).unwrap();
+        let list = node(&tree, "/list");
+        let item = node(&tree, "/list/0");
+
+        // Collapsed: right expands, left goes nowhere (parent is the root)
+        tree.set_expanded(list, false);
+        let collapsed = rows(&tree, &["/list", "/n"]);
+        assert_eq!(navigate(&tree, &collapsed, Some(list), TreeMove::Right, 1), Some(NavAction::SetExpanded(list, true)));
+        assert_eq!(navigate(&tree, &collapsed, Some(list), TreeMove::Left, 1), None);
+
+        // Expanded: right steps into the first child, left collapses
+        tree.set_expanded(list, true);
+        tree.set_expanded(item, false);
+        let expanded = rows(&tree, &["/list", "/list/0", "/n"]);
+        assert_eq!(navigate(&tree, &expanded, Some(list), TreeMove::Right, 1), Some(NavAction::Select(item)));
+        assert_eq!(navigate(&tree, &expanded, Some(list), TreeMove::Left, 1), Some(NavAction::SetExpanded(list, false)));
+
+        // From a child, left selects the parent and collapse folds it
+        assert_eq!(navigate(&tree, &expanded, Some(item), TreeMove::Left, 1), Some(NavAction::Select(list)));
+        assert_eq!(navigate(&tree, &expanded, Some(item), TreeMove::Collapse, 1), Some(NavAction::SetExpanded(list, false)));
+        assert_eq!(navigate(&tree, &expanded, Some(node(&tree, "/n")), TreeMove::Right, 1), None);
+    }
+}
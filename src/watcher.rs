@@ -0,0 +1,118 @@
+//! Watching the open file for changes on disk.
+//!
+//! The file's directory is watched (not the file itself) so that editors and
+//! services that replace the file with an atomic rename are still noticed.
+//! View state is carried across a reload by JSON Pointer, since node indices
+//! of the new tree have nothing to do with the old ones.
+
+use std::path::{Path, PathBuf};
+
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, Stream, StreamExt};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+
+use crate::message::Message;
+use crate::parser::JsonTree;
+
+/// Stream of `FileChangedOnDisk` messages for `path` (used as a subscription)
+#[allow(clippy::ptr_arg)] // `Subscription::run_with` passes `&PathBuf`
+pub fn watch(path: &PathBuf) -> impl Stream<Item = Message> + use<> {
+    let path = path.clone();
+    iced::stream::channel(4, async move |mut output| {
+        let (sender, mut events) = mpsc::unbounded();
+        let file_name = path.file_name().map(|name| name.to_os_string());
+        let handler = move |result: notify::Result<notify::Event>| {
+            let Ok(event) = result else {
+                return;
+            };
+            let touches_file = event.paths.iter().any(|p| p.file_name() == file_name.as_deref());
+            if touches_file && matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                let _ = sender.unbounded_send(());
+            }
+        };
+
+        let directory = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let watcher = notify::recommended_watcher(handler)
+            .and_then(|mut watcher| watcher.watch(directory, RecursiveMode::NonRecursive).map(|_| watcher));
+        // Keep the watcher alive for as long as the subscription runs
+        let Ok(_watcher) = watcher else {
+            return;
+        };
+
+        while events.next().await.is_some() {
+            // A single save usually produces a burst of events
+            while events.try_recv().is_ok() {}
+            if output.send(Message::FileChangedOnDisk(path.clone())).await.is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// Expansion, selection and search position of a tree, keyed by JSON Pointer
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewSnapshot {
+    expanded: Vec<String>,
+    selected: Option<String>,
+    current_result: Option<String>,
+    pub scroll_offset: f32,
+}
+
+impl ViewSnapshot {
+    pub fn capture(tree: &JsonTree, selected: Option<usize>, current_result: Option<usize>, scroll_offset: f32) -> Self {
+        ViewSnapshot {
+            expanded: tree.expanded_pointers(),
+            selected: selected.map(|index| tree.pointer_to_node(index)),
+            current_result: current_result.map(|index| tree.pointer_to_node(index)),
+            scroll_offset,
+        }
+    }
+
+    /// Re-expand the same paths in `tree`; nodes that no longer exist are skipped
+    pub fn restore_expanded(&self, tree: &mut JsonTree) {
+        for pointer in &self.expanded {
+            if let Some(index) = tree.resolve_pointer(pointer) {
+                tree.set_expanded(index, true);
+            }
+        }
+    }
+
+    /// Index of the previously selected node in `tree`, if it still exists
+    pub fn selected(&self, tree: &JsonTree) -> Option<usize> {
+        self.selected.as_deref().and_then(|pointer| tree.resolve_pointer(pointer))
+    }
+
+    /// Index of the previously focused search result in `tree`, if it still exists
+    pub fn current_result(&self, tree: &JsonTree) -> Option<usize> {
+        self.current_result.as_deref().and_then(|pointer| tree.resolve_pointer(pointer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::build_tree;
+    use serde_json::json;
+
+    #[test]
+    fn test_snapshot_restores_by_path() {
+        let mut old = build_tree(&json!({"config": {"port": 80}, "users": [{"name": "a"}, {"name": "b"}]}));
+        for pointer in ["", "/users", "/users/1"] {
+            let index = old.resolve_pointer(pointer).unwrap();
+            old.set_expanded(index, true);
+        }
+        let selected = old.resolve_pointer("/users/1/name");
+        let snapshot = ViewSnapshot::capture(&old, selected, None, 120.0);
+
+        // A key is added in front, so every node index shifts
+        let mut new = build_tree(&json!({"alpha": 1, "config": {"port": 81}, "users": [{"name": "a"}, {"name": "c"}]}));
+        snapshot.restore_expanded(&mut new);
+
+        assert_eq!(new.expanded_pointers(), ["", "/users", "/users/1"]);
+        assert_eq!(snapshot.selected(&new), new.resolve_pointer("/users/1/name"));
+        assert_eq!(snapshot.current_result(&new), None);
+        assert_eq!(snapshot.scroll_offset, 120.0);
+    }
+}